@@ -27,7 +27,7 @@ impl QbitApi {
 
     /// ## Usage
     /// Gets the build info as a json [`Value`].
-    pub async fn app_build_info(&mut self) -> Result<Value, Error> {
+    pub async fn app_build_info(&self) -> Result<Value, Error> {
         serde_json::from_str(Self::app_build_info_raw(self).await?.as_str()).map_err(|e| Error::build(ErrorType::JsonSerdeError(Box::new(e)), None))
     }
 
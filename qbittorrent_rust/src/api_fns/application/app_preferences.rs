@@ -1336,7 +1336,7 @@ impl QbitApi {
     /// ## Usage
     /// Gets the app preferences as a json [`Value`].
     /// For a list of all the information in the app preferences, refer to the [qbittorrent docs](https://github.com/qbittorrent/qBittorrent/wiki/WebUI-API-(qBittorrent-4.1)#get-application-preferences)
-    pub async fn app_get_preferences(&mut self) -> Result<Value, Error> {
+    pub async fn app_get_preferences(&self) -> Result<Value, Error> {
         serde_json::from_str(Self::get_preferences_raw(self).await?.as_str())
             .map_err(|e| Error::build(ErrorType::JsonSerdeError(Box::new(e)), None))
     }
@@ -1352,15 +1352,20 @@ impl QbitApi {
     /// ## Usage
     /// Sets the app preferences according to the [`QBittorrentConfig`].
     pub async fn app_set_preferences(
-        &mut self,
+        &self,
         config: impl Borrow<QBittorrentConfig>,
     ) -> Result<(), Error> {
+        let config = config.borrow();
         let mut hashmap = HashMap::new();
 
-        hashmap.insert("json", config.borrow());
+        hashmap.insert("json", config);
 
         self.make_request_with_form("/app/setPreferences", "set_preferences", hashmap)
             .await?;
+
+        if let Some(secs) = config.web_ui_session_timeout {
+            self.set_session_timeout(secs.into()).await;
+        }
         Ok(())
     }
 }
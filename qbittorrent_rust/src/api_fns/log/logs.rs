@@ -129,7 +129,7 @@ impl QbitApi {
     /// ## Usage
     /// Gets the log as a json [`Value`] based on the [`GetLogConfig`].
     pub async fn log_get_log(
-        &mut self,
+        &self,
         config: impl Borrow<GetLogConfig>,
     ) -> Result<Value, crate::Error> {
         Ok(
@@ -141,7 +141,7 @@ impl QbitApi {
     /// ## Usage
     /// Gets the log as a [`String`] based on the [`GetLogConfig`].
     pub async fn log_get_log_raw(
-        &mut self,
+        &self,
         config: impl Borrow<GetLogConfig>,
     ) -> Result<String, crate::Error> {
         let config: &GetLogConfig = config.borrow();
@@ -159,7 +159,7 @@ impl QbitApi {
 
     /// ## Usage
     /// Gets the peer log as a json [`Value`] based on the [`GetLogConfig`].
-    pub async fn log_get_peer_log(&mut self, last_known_id: Option<i64>) -> Result<Value, Error> {
+    pub async fn log_get_peer_log(&self, last_known_id: Option<i64>) -> Result<Value, Error> {
         Ok(serde_json::from_str(
             Self::log_get_peer_log_raw(self, last_known_id)
                 .await?
@@ -171,7 +171,7 @@ impl QbitApi {
     /// ## Usage
     /// Gets the peer log as a [`String`] based on the [`GetLogConfig`].
     pub async fn log_get_peer_log_raw(
-        &mut self,
+        &self,
         last_known_id: Option<i64>,
     ) -> Result<String, Error> {
         let x = match last_known_id {
@@ -4,4 +4,5 @@ pub mod log;
 pub mod sync;
 pub mod transfer_info;
 pub mod rss;
-pub mod search;
\ No newline at end of file
+pub mod search;
+pub mod torrent_creator;
\ No newline at end of file
@@ -1 +1,2 @@
-pub mod rss;
\ No newline at end of file
+pub mod rss;
+pub mod rss_items;
\ No newline at end of file
@@ -1,10 +1,14 @@
-use std::{borrow::Borrow, collections::HashMap};
+use std::{borrow::Borrow, collections::HashMap, time::Duration};
 
 use proc_macros_qbittorrent_rust::{experimental, Builder};
 use serde::Serialize;
 use serde_json::Value;
 
-use crate::{core::api::QbitApi, error_handling::error_type::ErrorType, hashmap, request_error_focus, Error};
+use crate::{
+    core::{api::QbitApi, version::ApiVersion},
+    error_handling::error_type::ErrorType,
+    hashmap, Error,
+};
 
 /// ## Info
 /// Describes a rss auto download rule.
@@ -83,92 +87,106 @@ pub struct RssAutoDownloadRuleBuilder{
 impl QbitApi {
     /// ## Usage
     /// Adds a new rss folder.
+    ///
+    /// ## Errors
+    /// - [`ErrorType::RssItemAlreadyExists`] if there's already an item at `path`.
+    /// - [`ErrorType::RssItemNotFound`] if the parent folder doesn't exist.
     #[experimental]
-    pub async fn rss_add_folder(&mut self, path: impl Into<String>) -> Result<(), Error> {
+    pub async fn rss_add_folder(&self, path: impl Into<String>) -> Result<(), Error> {
         let path: String = path.into();
 
-        let hashmap = hashmap!(("path", path));
-
-        request_error_focus!(self, rss_add_folder, "/rss/addFolder", hashmap, (409, ErrorType::MiscError("failure to add feed".to_string())))?;
-
-        Ok(())
+        self.rss_request("/rss/addFolder", hashmap!(("path", path)), false).await
     }
 
     /// ## Usage
     /// Adds a new feed.
+    ///
+    /// ## Arguments
+    /// - url: the url of the feed.
+    /// - path: where to put it, eg: `Linux\Debian`. without it, the feed is named after its url, in the root folder.
+    ///
+    /// ## Errors
+    /// - [`ErrorType::RssItemAlreadyExists`] if there's already an item at `path`, or a feed with this url.
+    /// - [`ErrorType::RssItemNotFound`] if the parent folder doesn't exist.
     #[experimental]
-    pub async fn rss_add_feed(&mut self, url: impl Into<String>, path: Option<impl Into<String>>) -> Result<(), Error> {
-        match path {
-            Some(path) => {
-                let x: String = path.into();
-                let hashmap = hashmap!(("url", Into::<String>::into(url)), ("path", x));
-                request_error_focus!(
-                    self,
-                    rss_add_feed,
-                    "/search/addFeed",
-                    hashmap,
-                    (
-                        409,
-                        ErrorType::MiscError(
-                            "Failure to add feed"
-                                .to_string()
-                        )
-                    )
-                )?;
-                Ok(())
-            },
-
-            None => {
-                let hashmap = hashmap!(("url", Into::<String>::into(url)));
-                request_error_focus!(
-                    self,
-                    rss_add_feed,
-                    "/search/addFeed",
-                    hashmap,
-                    (
-                        409,
-                        ErrorType::MiscError(
-                            "Failure to add feed"
-                                .to_string()
-                        )
-                    )
-                )?;
-                Ok(())
-            }
+    pub async fn rss_add_feed(&self, url: impl Into<String>, path: Option<impl Into<String>>) -> Result<(), Error> {
+        let url: String = url.into();
+        let mut hashmap = hashmap!(("url", url));
+
+        if let Some(path) = path {
+            hashmap.insert("path", path.into());
         }
+
+        self.rss_request("/rss/addFeed", hashmap, false).await
     }
 
     /// ## Usage
     /// Removes a feed or folder.
+    ///
+    /// ## Errors
+    /// - [`ErrorType::RssItemNotFound`] if there's no item at `path`.
+    /// - [`ErrorType::RssInvalidPath`] for the root folder.
     #[experimental]
-    pub async fn rss_remove_item(&mut self, path: impl Into<String>) -> Result<(), Error> {
+    pub async fn rss_remove_item(&self, path: impl Into<String>) -> Result<(), Error> {
         let path: String = path.into();
 
-        let hashmap = hashmap!(("path", path));
-
-        request_error_focus!(self, rss_add_folder, "/rss/removeItem", hashmap, (409, ErrorType::MiscError("failure to remove item".to_string())))?;
-
-        Ok(())
+        self.rss_request("/rss/removeItem", hashmap!(("path", path)), false).await
     }
 
     /// ## Usage
     /// Moves/renames folder or feed.
+    ///
+    /// ## Errors
+    /// - [`ErrorType::RssItemNotFound`] if there's no item at `original_path`, or the destination folder doesn't exist.
+    /// - [`ErrorType::RssItemAlreadyExists`] if there's already an item at `destination_path`.
+    /// - [`ErrorType::RssInvalidPath`] when moving the root folder, or a folder into itself.
     #[experimental]
-    pub async fn rss_move_item(&mut self, original_path: impl Into<String>, destination_path: impl Into<String>) -> Result<(), Error> {
+    pub async fn rss_move_item(&self, original_path: impl Into<String>, destination_path: impl Into<String>) -> Result<(), Error> {
         let path_orig: String = original_path.into();
         let path_dest: String = destination_path.into();
 
         let hashmap = hashmap!(("itemPath", path_orig), ("destPath", path_dest));
 
-        request_error_focus!(self, rss_add_folder, "/rss/moveItem", hashmap, (409, ErrorType::MiscError("failure to move item".to_string())))?;
+        self.rss_request("/rss/moveItem", hashmap, false).await
+    }
 
-        Ok(())
+    /// ## Usage
+    /// Changes the url of a feed, keeping its path and its articles.
+    ///
+    /// ## Errors
+    /// - [`ErrorType::RssItemNotFound`] if there's no feed at `path`.
+    /// - [`ErrorType::RssItemAlreadyExists`] if another feed already has this url.
+    /// - requires the WebAPI 2.9.1 (qbittorrent 4.6); older servers return [`ErrorType::UnsupportedByServer`].
+    #[experimental]
+    pub async fn rss_set_feed_url(&self, path: impl Into<String>, url: impl Into<String>) -> Result<(), Error> {
+        self.require_version(ApiVersion::V2_9_1).await?;
+
+        let path: String = path.into();
+        let url: String = url.into();
+
+        self.rss_request("/rss/setFeedURL", hashmap!(("path", path), ("url", url)), false).await
+    }
+
+    /// ## Usage
+    /// Sets how often a feed is refreshed, instead of the global interval. it's sent in whole seconds.
+    ///
+    /// ## Errors
+    /// - [`ErrorType::RssItemNotFound`] if there's no feed at `path`.
+    /// - requires the WebAPI 2.11.4 (qbittorrent 5.1); older servers return [`ErrorType::UnsupportedByServer`].
+    #[experimental]
+    pub async fn rss_set_feed_refresh_interval(&self, path: impl Into<String>, interval: Duration) -> Result<(), Error> {
+        self.require_version(ApiVersion::V2_11_4).await?;
+
+        let path: String = path.into();
+        let hashmap = hashmap!(("path", path), ("refreshInterval", interval.as_secs().to_string()));
+
+        self.rss_request("/rss/setFeedRefreshInterval", hashmap, true).await
     }
 
     /// ## Usage
     /// Gets all items as a [`String`].
     #[experimental]
-    pub async fn rss_get_all_items_raw(&mut self, with_data: Option<bool>) -> Result<String, Error> {
+    pub async fn rss_get_all_items_raw(&self, with_data: Option<bool>) -> Result<String, Error> {
         if let Some(x) = with_data {
             let y = self.make_request_with_form("/rss/items", "rss_get_all_items", hashmap!(("withData", x))).await?;
             return Ok(y);
@@ -181,42 +199,41 @@ impl QbitApi {
     /// ## Usage
     /// Gets all items as a json [`Value`]
     #[experimental]
-    pub async fn rss_get_all_items(&mut self, with_data: Option<bool>) -> Result<Value, Error> {
+    pub async fn rss_get_all_items(&self, with_data: Option<bool>) -> Result<Value, Error> {
         serde_json::from_str(self.rss_get_all_items_raw(with_data).await?.as_str()).map_err(|e| Error::build(ErrorType::JsonSerdeError(Box::new(e)), None))
     }
 
     /// ## Usage
     /// If article_id is provided only the article is marked as read otherwise the whole feed is going to be marked as read.
+    /// with the path of a folder, all of its feeds are marked as read.
+    ///
+    /// ## Info
+    /// qbittorrent ignores paths and article ids that don't exist, so this doesn't fail for them.
     #[experimental]
-    pub async fn rss_mark_as_read(&mut self, item_path: impl Into<String>, article_id: Option<impl Into<String>>) -> Result<(), Error> {
+    pub async fn rss_mark_as_read(&self, item_path: impl Into<String>, article_id: Option<impl Into<String>>) -> Result<(), Error> {
         let path = item_path.into() as String;
         let mut hashmap = hashmap!(("itemPath", path));
 
-        if let Some(x) = article_id {
-            let id = x.into() as String;
-            hashmap.insert("articleId", id);
-            self.make_request_with_form("/rss/markAsRead", "rss_mark_as_read", hashmap).await?;
-            return Ok(());
-        } else {
-            self.make_request_with_form("/rss/markAsRead", "rss_mark_as_read", hashmap).await?;
-            return Ok(());
+        if let Some(id) = article_id {
+            hashmap.insert("articleId", id.into());
         }
+
+        self.rss_request("/rss/markAsRead", hashmap, true).await
     }
 
     /// ## Usage 
-    /// Refreshes folder or feed.
+    /// Refreshes folder or feed. like [`QbitApi::rss_mark_as_read`], paths that don't exist are ignored.
     #[experimental]
-    pub async fn rss_refresh_item(&mut self, item_path: impl Into<String>) -> Result<(), Error> {
+    pub async fn rss_refresh_item(&self, item_path: impl Into<String>) -> Result<(), Error> {
         let path = item_path.into() as String;
-        let hashmap = hashmap!(("itemPath", path));
-        self.make_request_with_form("/rss/refreshItem", "rss_refresh_item", hashmap).await?;
-        Ok(())
+
+        self.rss_request("/rss/refreshItem", hashmap!(("itemPath", path)), true).await
     }
 
     /// ## Usage
     /// Sets a new auto-downloading rule based on a [`RssAutoDownloadRule`].
     #[experimental]
-    pub async fn rss_set_auto_downloading_rule(&mut self, rule_name: impl Into<String>, rule: impl Borrow<RssAutoDownloadRule>) -> Result<(), Error> {
+    pub async fn rss_set_auto_downloading_rule(&self, rule_name: impl Into<String>, rule: impl Borrow<RssAutoDownloadRule>) -> Result<(), Error> {
         let name = rule_name.into() as String;
         let rule: RssAutoDownloadRule = rule.borrow().clone();
         let rule = serde_json::to_string(&rule).map_err(|e| Error::build(ErrorType::JsonSerdeError(Box::new(e)), None))?;
@@ -228,7 +245,7 @@ impl QbitApi {
     /// ## Usage
     /// Renames an auto-downloading rule.
     #[experimental]
-    pub async fn rss_rename_auto_downloading_rule(&mut self, original_name: impl Into<String>, new_name: impl Into<String>) -> Result<(), Error> {
+    pub async fn rss_rename_auto_downloading_rule(&self, original_name: impl Into<String>, new_name: impl Into<String>) -> Result<(), Error> {
         let name_orig = original_name.into() as String;
         let name_new = new_name.into() as String;
         
@@ -240,7 +257,7 @@ impl QbitApi {
     /// ## Usage
     /// Removes an auto-downloading rule.
     #[experimental]
-    pub async fn rss_remove_auto_downloading_rule(&mut self, rule_name: impl Into<String>) -> Result<(), Error> {
+    pub async fn rss_remove_auto_downloading_rule(&self, rule_name: impl Into<String>) -> Result<(), Error> {
         let name = rule_name.into() as String;
         
         let hashmap = hashmap!(("ruleName", name));
@@ -251,7 +268,7 @@ impl QbitApi {
     /// ## Usage
     /// Gets all auto-downloading rules as a [`String`].
     #[experimental]
-    pub async fn rss_get_all_auto_downloading_rules_raw(&mut self) -> Result<String, Error> {
+    pub async fn rss_get_all_auto_downloading_rules_raw(&self) -> Result<String, Error> {
         let x = self.make_request("/rss/rules", "rss_get_all_auto_downloading_rules_raw").await?;
         Ok(x)
     }
@@ -259,14 +276,14 @@ impl QbitApi {
     /// ## Usage
     /// Gets all auto-downloading rules as a json [`Value`].
     #[experimental]
-    pub async fn rss_get_all_auto_downloading_rules(&mut self) -> Result<Value, Error> {
+    pub async fn rss_get_all_auto_downloading_rules(&self) -> Result<Value, Error> {
         serde_json::from_str(self.rss_get_all_auto_downloading_rules_raw().await?.as_str()).map_err(|e| Error::build(ErrorType::JsonSerdeError(Box::new(e)), None))
     }
 
     /// ## Usage
     /// Gets all articles matching a rule as a [`String`].
     #[experimental]
-    pub async fn rss_get_all_articles_matching_a_rule_raw(&mut self, rule_name: impl Into<String>) -> Result<String, Error> {
+    pub async fn rss_get_all_articles_matching_a_rule_raw(&self, rule_name: impl Into<String>) -> Result<String, Error> {
         let name = rule_name.into() as String;
         
         let hashmap = hashmap!(("ruleName", name));
@@ -277,7 +294,49 @@ impl QbitApi {
     /// ## Usage
     /// Gets all articles matching a rule as a json [`Value`].
     #[experimental]
-    pub async fn rss_get_all_articles_matching_a_rule(&mut self, rule_name: impl Into<String>) -> Result<Value, Error> {
+    pub async fn rss_get_all_articles_matching_a_rule(&self, rule_name: impl Into<String>) -> Result<Value, Error> {
         serde_json::from_str(self.rss_get_all_articles_matching_a_rule_raw(rule_name).await?.as_str()).map_err(|e| Error::build(ErrorType::JsonSerdeError(Box::new(e)), None))
     }
-}
\ No newline at end of file
+}
+
+impl QbitApi {
+    /// posts an rss form. qbittorrent explains its 409s in the body, which becomes a typed error.
+    ///
+    /// requests that aren't `idempotent` aren't retried: repeating an add, move or removal whose response got lost
+    /// would fail with a conflict, even though it worked.
+    async fn rss_request(&self, path: &str, form: HashMap<&str, String>, idempotent: bool) -> Result<(), Error> {
+        let url = format!("{}/api/v2{}", self.authority, path);
+        let response = self.send_with_retry(|| self.reqwest_client.post(&url).form(&form), idempotent).await?;
+
+        if response.status().is_success() {
+            return Ok(());
+        }
+
+        let status = response.status().as_u16();
+        if status != 409 {
+            return Err(Error::build(ErrorType::MiscNetError(status), Some(status)));
+        }
+
+        let message = response
+            .text()
+            .await
+            .map_err(|e| Error::build(ErrorType::ReqwestError(Box::new(e)), None))?;
+        Err(Error::build(rss_conflict(path, &form, message), Some(status)))
+    }
+}
+
+/// sorts a 409 by the endpoint that returned it, and by the form sent to it.
+///
+/// adding, moving and changing the url of an item can be refused for several reasons, which only the message tells apart.
+/// it's translated on servers that aren't in english: there, those conflicts are [`ErrorType::RssInvalidPath`].
+fn rss_conflict(path: &str, form: &HashMap<&str, String>, message: String) -> ErrorType {
+    match path {
+        "/rss/setFeedRefreshInterval" => ErrorType::RssItemNotFound(message),
+        // the root folder is the only item that can't be removed.
+        "/rss/removeItem" if form.get("path").is_some_and(|path| path.is_empty()) => ErrorType::RssInvalidPath(message),
+        "/rss/removeItem" => ErrorType::RssItemNotFound(message),
+        _ if message.contains("already exists") => ErrorType::RssItemAlreadyExists(message),
+        _ if message.contains("doesn't exist") => ErrorType::RssItemNotFound(message),
+        _ => ErrorType::RssInvalidPath(message),
+    }
+}
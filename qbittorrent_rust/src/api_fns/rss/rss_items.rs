@@ -0,0 +1,192 @@
+use std::collections::BTreeMap;
+
+use serde::{de::Error as _, Deserialize, Deserializer};
+use serde_json::Value;
+
+use crate::{core::api::QbitApi, error_handling::error_type::ErrorType, Error};
+
+/// the separator of the parts of an rss item path, like in `Linux\Debian`.
+pub const RSS_PATH_SEPARATOR: char = '\\';
+
+/// ## Info
+/// an article of an rss feed.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RssArticle {
+    /// the id to use with [`QbitApi::rss_mark_as_read`].
+    pub id: String,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// as written in the feed, eg: `Tue, 08 Oct 2024 10:00:00 GMT`.
+    #[serde(default)]
+    pub date: Option<String>,
+    #[serde(default)]
+    pub link: Option<String>,
+    #[serde(default, rename = "torrentURL")]
+    pub torrent_url: Option<String>,
+    #[serde(default)]
+    pub author: Option<String>,
+    #[serde(default)]
+    pub category: Option<String>,
+    #[serde(default)]
+    pub is_read: bool,
+}
+
+/// ## Info
+/// an rss folder or feed, as returned by [`QbitApi::rss_get_all_items_typed`]. the root of the tree is a folder.
+///
+/// ## Variants
+/// - `Folder`: its children, by name.
+/// - `Feed`: a feed. without `with_data`, only `uid` and `url` are known, and the other fields are left empty.
+///
+/// ## Example
+/// ```no_run
+/// # use qbittorrent_rust::{core::api::QbitApi, RssItem};
+/// # async fn example(api: QbitApi) -> Result<(), qbittorrent_rust::Error> {
+/// let tree = api.rss_get_all_items_typed(Some(true)).await?;
+///
+/// if let Some(RssItem::Feed { url, articles, .. }) = tree.get("Linux\\Debian") {
+///     println!("{}: {} articles", url, articles.len());
+/// }
+///
+/// for (path, article) in tree.articles() {
+///     if !article.is_read {
+///         println!("{}: {}", path, article.title);
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RssItem {
+    Folder {
+        children: BTreeMap<String, RssItem>,
+    },
+    Feed {
+        uid: String,
+        url: String,
+        title: String,
+        last_build_date: Option<String>,
+        is_loading: bool,
+        has_error: bool,
+        articles: Vec<RssArticle>,
+    },
+}
+
+/// the JSON of a feed, before it becomes an [`RssItem::Feed`].
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RssFeedData {
+    uid: String,
+    url: String,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    last_build_date: Option<String>,
+    #[serde(default)]
+    is_loading: bool,
+    #[serde(default)]
+    has_error: bool,
+    #[serde(default)]
+    articles: Vec<RssArticle>,
+}
+
+impl<'de> Deserialize<'de> for RssItem {
+    /// folders and feeds are both JSON objects: feeds are the ones with a `uid`.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+
+        if value.get("uid").is_some_and(Value::is_string) {
+            let feed: RssFeedData = serde_json::from_value(value).map_err(D::Error::custom)?;
+            return Ok(RssItem::Feed {
+                uid: feed.uid,
+                url: feed.url,
+                title: feed.title,
+                last_build_date: feed.last_build_date.filter(|date| !date.is_empty()),
+                is_loading: feed.is_loading,
+                has_error: feed.has_error,
+                articles: feed.articles,
+            });
+        }
+
+        let children = serde_json::from_value(value).map_err(D::Error::custom)?;
+        Ok(RssItem::Folder { children })
+    }
+}
+
+impl RssItem {
+    /// ## Usage
+    /// the item at this path, relative to this one. an empty path is this item.
+    ///
+    /// ## Arguments
+    /// - path: the names of the items, separated by `\`. eg: `Linux\Debian`.
+    pub fn get(&self, path: &str) -> Option<&RssItem> {
+        if path.is_empty() {
+            return Some(self);
+        }
+
+        path.split(RSS_PATH_SEPARATOR).try_fold(self, |item, name| match item {
+            RssItem::Folder { children } => children.get(name),
+            RssItem::Feed { .. } => None,
+        })
+    }
+
+    pub fn is_feed(&self) -> bool {
+        matches!(self, RssItem::Feed { .. })
+    }
+
+    pub fn is_folder(&self) -> bool {
+        matches!(self, RssItem::Folder { .. })
+    }
+
+    /// ## Usage
+    /// every folder and feed under this item, with their paths relative to it, parents before their children.
+    pub fn items(&self) -> Vec<(String, &RssItem)> {
+        let mut items = Vec::new();
+        self.collect_items("", &mut items);
+        items
+    }
+
+    fn collect_items<'a>(&'a self, path: &str, items: &mut Vec<(String, &'a RssItem)>) {
+        if let RssItem::Folder { children } = self {
+            for (name, child) in children {
+                let child_path = match path.is_empty() {
+                    true => name.clone(),
+                    false => format!("{}{}{}", path, RSS_PATH_SEPARATOR, name),
+                };
+                items.push((child_path.clone(), child));
+                child.collect_items(&child_path, items);
+            }
+        }
+    }
+
+    /// ## Usage
+    /// every article of the feeds under this item (or of this feed), with the path of their feed.
+    pub fn articles(&self) -> Vec<(String, &RssArticle)> {
+        let mut items = vec![(String::new(), self)];
+        items.extend(self.items());
+
+        items
+            .into_iter()
+            .filter_map(|(path, item)| match item {
+                RssItem::Feed { articles, .. } => Some((path, articles)),
+                RssItem::Folder { .. } => None,
+            })
+            .flat_map(|(path, articles)| articles.iter().map(move |article| (path.clone(), article)))
+            .collect()
+    }
+}
+
+impl QbitApi {
+    /// ## Usage
+    /// Gets all items as an [`RssItem`]: the root folder.
+    ///
+    /// ## Arguments
+    /// - with_data: whether to also get the title, status and articles of the feeds.
+    pub async fn rss_get_all_items_typed(&self, with_data: Option<bool>) -> Result<RssItem, Error> {
+        serde_json::from_str(self.rss_get_all_items_raw(with_data).await?.as_str())
+            .map_err(|e| Error::build(ErrorType::JsonSerdeError(Box::new(e)), None))
+    }
+}
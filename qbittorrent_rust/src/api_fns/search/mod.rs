@@ -1 +1,4 @@
-pub mod search;
\ No newline at end of file
+pub mod search;
+pub mod search_session;
+pub mod search_pick;
+pub mod search_plugins;
\ No newline at end of file
@@ -1,7 +1,7 @@
 use std::{borrow::Borrow, collections::HashMap};
 
 use proc_macros_qbittorrent_rust::requires_id;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use crate::{
@@ -11,7 +11,8 @@ use crate::{
 
 /// ## Info
 /// Represents a search plugin.
-/// to get a vector of the available search plugins, use [`QbitApi::search_get_search_plugins_descriptor`]
+/// to get a vector of the available search plugins, use [`QbitApi::search_get_search_plugins`];
+/// to also get their version, url and whether they're enabled, use [`QbitApi::search_get_search_plugins_info`].
 /// 
 /// ## Fields
 /// name: the name of the search plugin.
@@ -114,6 +115,7 @@ impl SearchPluginsDescriptor {
 /// 
 /// ## Variants
 /// - The `Custom` variant contains a vector of names of plugins; to get the names of available plugins, use [`QbitApi::`].
+#[derive(Debug, Clone)]
 pub enum SearchPluginsSpec {
     All,
     Enabled,
@@ -125,22 +127,75 @@ pub enum SearchPluginsSpec {
 /// 
 /// ## Variants
 /// - The `Custom` variant contains a vector of names of categories
+#[derive(Debug, Clone)]
 pub enum Categories {
     All,
     Custom(Vec<String>),
 }
 
+/// ## Info
+/// a result of a search job.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResult {
+    pub file_name: String,
+    /// the url of the torrent, often a magnet link.
+    pub file_url: String,
+    /// the size in bytes, or `-1` if unknown.
+    pub file_size: i64,
+    /// `-1` if unknown.
+    pub nb_seeders: i64,
+    /// `-1` if unknown.
+    pub nb_leechers: i64,
+    /// the url of the site the result comes from.
+    pub site_url: String,
+    /// the url of the description page of the result.
+    pub descr_link: String,
+}
+
+/// ## Info
+/// the status of a search job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum SearchJobStatus {
+    Running,
+    /// the search is over, or was stopped with [`QbitApi::search_stop`].
+    Stopped,
+    /// a status this library doesn't know about.
+    #[serde(other)]
+    Unknown,
+}
+
+/// ## Info
+/// the status of a search job, as returned by [`QbitApi::search_status_typed`].
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct SearchStatus {
+    pub id: u64,
+    pub status: SearchJobStatus,
+    /// how many results were found so far.
+    pub total: u64,
+}
+
+/// ## Info
+/// a page of results of a search job, as returned by [`QbitApi::search_results_typed`].
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SearchResults {
+    pub results: Vec<SearchResult>,
+    pub status: SearchJobStatus,
+    /// how many results were found so far, not only the ones in this page.
+    pub total: u64,
+}
+
 impl QbitApi {
     /// ## Usage
     /// Gets all available search plugins as a [`String`].
-    pub async fn search_get_search_plugins_raw(&mut self) -> Result<String, crate::Error> {
+    pub async fn search_get_search_plugins_raw(&self) -> Result<String, crate::Error> {
         self.make_request("/search/plugins", "search_get_search_plugins_raw")
             .await
     }
 
     /// ## Usage
     /// Gets all available search plugins as a json [`Value`].
-    pub async fn search_get_search_plugins_json(&mut self) -> Result<Value, crate::Error> {
+    pub async fn search_get_search_plugins_json(&self) -> Result<Value, crate::Error> {
         serde_json::from_str(self.search_get_search_plugins_raw().await?.as_str())
             .map_err(|e| Error::build(ErrorType::JsonSerdeError(Box::new(e)), None))
     }
@@ -148,7 +203,7 @@ impl QbitApi {
     /// ## Usage
     /// Gets the available search plugins as a [`Vec`] of [`SearchPlugin`]s.
     pub async fn search_get_search_plugins(
-        &mut self,
+        &self,
     ) -> Result<Vec<SearchPlugin>, Error> {
         let value = self.search_get_search_plugins_json().await?;
         let names = value
@@ -191,7 +246,7 @@ impl QbitApi {
 
     /// ## Usage
     /// gets the names of the plugins in a [`Vec`].
-    pub async fn search_get_search_plugins_names(&mut self) -> Result<Vec<String>, Error> {
+    pub async fn search_get_search_plugins_names(&self) -> Result<Vec<String>, Error> {
         Ok(self.search_get_search_plugins().await?.into_iter().map(|el|el.get_name()).collect::<Vec<String>>())
     }
 
@@ -201,7 +256,7 @@ impl QbitApi {
     /// ## Returns
     /// If everything goes well, it returns the search id.
     pub async fn search_start(
-        &mut self,
+        &self,
         pattern: impl Into<String>,
         plugins: impl Borrow<SearchPluginsSpec>,
         categories: impl Borrow<Categories>,
@@ -220,22 +275,22 @@ impl QbitApi {
 
         match categories {
             Categories::All => hashmap.insert("category", "all".to_string()),
-            Categories::Custom(vec) => hashmap.insert("plugins", SepVec::new(vec, "|").to_string()),
+            Categories::Custom(vec) => hashmap.insert("category", SepVec::new(vec, "|").to_string()),
         };
 
-        let string = request_error_focus!(
-            self,
-            search_start,
-            "/search/start",
-            hashmap,
-            (
-                409,
-                ErrorType::MiscError(
-                    "user has reached the limit of max 'Running' searches (currently set to 5)"
-                        .to_string()
-                )
-            )
-        )?;
+        // every attempt starts a new job, so it's never retried.
+        let string = self
+            .make_request_with_form_non_idempotent("/search/start", "search_start", hashmap)
+            .await
+            .map_err(|e| match e.code {
+                Some(409) => Error::build(
+                    ErrorType::MiscError(
+                        "user has reached the limit of max 'Running' searches (currently set to 5)".to_string(),
+                    ),
+                    e.code,
+                ),
+                _ => e,
+            })?;
         let val: Value = serde_json::from_str(string.as_str())
             .map_err(|e| Error::build(ErrorType::JsonSerdeError(Box::new(e)), None))?;
         Ok(val.get("id").unwrap().as_u64().unwrap())
@@ -245,7 +300,7 @@ impl QbitApi {
     /// ## Usage
     /// stops a search.
     #[requires_id]
-    pub async fn search_stop(&mut self, id: u64) -> Result<(), Error> {
+    pub async fn search_stop(&self, id: u64) -> Result<(), Error> {
         let hashmap = hashmap!(("id", id));
         request_error_focus!(
             self,
@@ -263,7 +318,7 @@ impl QbitApi {
     /// ## Usage
     /// Gets the status of a search job as a [`String`].
     #[requires_id]
-    pub async fn search_status_raw(&mut self, id: Option<u64>) -> Result<String, Error> {
+    pub async fn search_status_raw(&self, id: Option<u64>) -> Result<String, Error> {
         match id {
             Some(n) => {
                 let hashmap = hashmap!(("id", n));
@@ -301,7 +356,15 @@ impl QbitApi {
     /// ## Usage
     /// Gets the status of a search job as a json [`Value`].
     #[requires_id]
-    pub async fn search_status(&mut self, id: Option<u64>) -> Result<Value, Error> {
+    pub async fn search_status(&self, id: Option<u64>) -> Result<Value, Error> {
+        serde_json::from_str(self.search_status_raw(id).await?.as_str())
+            .map_err(|e| Error::build(ErrorType::JsonSerdeError(Box::new(e)), None))
+    }
+
+    /// ## Usage
+    /// Gets the status of a search job, or of all of them if `id` is `None`, as a [`Vec`] of [`SearchStatus`].
+    #[requires_id]
+    pub async fn search_status_typed(&self, id: Option<u64>) -> Result<Vec<SearchStatus>, Error> {
         serde_json::from_str(self.search_status_raw(id).await?.as_str())
             .map_err(|e| Error::build(ErrorType::JsonSerdeError(Box::new(e)), None))
     }
@@ -314,7 +377,7 @@ impl QbitApi {
     /// offset: result to start at. A negative number means count backwards (e.g. -2 returns the 2 most recent results)
     #[requires_id]
     pub async fn search_results_raw(
-        &mut self,
+        &self,
         id: u64,
         limit: Option<i64>,
         offset: Option<i64>,
@@ -329,9 +392,18 @@ impl QbitApi {
             hashmap.insert("offset", ofst);
         }
 
-        let res = request_error_focus!(self, search_results_raw, "/search/results", hashmap, (404, ErrorType::MiscError("search job was not found".to_string())), (409, ErrorType::MiscError("Offset is too large, or too small (e.g. absolute value of negative number is greater than # results)".to_string())))?;
-
-        Ok(res)
+        self.make_request_with_form("/search/results", "search_results_raw", hashmap)
+            .await
+            .map_err(|e| match e.code {
+                Some(404) => Error::build(ErrorType::MiscError("search job was not found".to_string()), e.code),
+                Some(409) => Error::build(
+                    ErrorType::MiscError(
+                        "Offset is too large, or too small (e.g. absolute value of negative number is greater than # results)".to_string(),
+                    ),
+                    e.code,
+                ),
+                _ => e,
+            })
     }
 
 
@@ -343,7 +415,7 @@ impl QbitApi {
     /// offset: result to start at. A negative number means count backwards (e.g. -2 returns the 2 most recent results)
     #[requires_id]
     pub async fn search_results(
-        &mut self,
+        &self,
         id: u64,
         limit: Option<i64>,
         offset: Option<i64>,
@@ -352,10 +424,26 @@ impl QbitApi {
             .map_err(|e| Error::build(ErrorType::JsonSerdeError(Box::new(e)), None))
     }
 
+    /// ## Usage
+    /// Gets the results of a search job as [`SearchResults`].
+    ///
+    /// ## Arguments
+    /// the same as [`QbitApi::search_results`].
+    #[requires_id]
+    pub async fn search_results_typed(
+        &self,
+        id: u64,
+        limit: Option<i64>,
+        offset: Option<i64>,
+    ) -> Result<SearchResults, Error> {
+        serde_json::from_str(self.search_results_raw(id, limit, offset).await?.as_str())
+            .map_err(|e| Error::build(ErrorType::JsonSerdeError(Box::new(e)), None))
+    }
+
     /// ## Usage
     /// deletes a search
     #[requires_id]
-    pub async fn search_delete(&mut self, id: u64) -> Result<(), Error> {
+    pub async fn search_delete(&self, id: u64) -> Result<(), Error> {
         let hashmap = hashmap!(("id", id));
         request_error_focus!(
             self,
@@ -376,7 +464,7 @@ impl QbitApi {
     /// ## Arguments
     /// sources: the urls to the plugins
     pub async fn search_install_plugins<S: Into<String> + Clone>(
-        &mut self,
+        &self,
         sources: impl Borrow<Vec<S>>,
     ) -> Result<(), Error> {
         let sources: Vec<S> = sources.borrow().clone();
@@ -401,7 +489,7 @@ impl QbitApi {
     /// ## Arguments
     /// sources: the urls to the plugins
     pub async fn search_uninstall_plugins<S: Into<String> + Clone>(
-        &mut self,
+        &self,
         names: impl Borrow<Vec<S>>,
     ) -> Result<(), Error> {
         let sources: Vec<S> = names.borrow().clone();
@@ -430,7 +518,7 @@ impl QbitApi {
     /// ## Arguments
     /// - enable: whether to enable (true) or disable (false) the plugins.
     pub async fn search_enable_plugins<S: Into<String> + Clone>(
-        &mut self,
+        &self,
         names: impl Borrow<Vec<S>>,
         enable: bool,
     ) -> Result<(), Error> {
@@ -454,20 +542,10 @@ impl QbitApi {
             enable,
         };
 
+        let url = format!("{}/api/v2{}", self.authority, "/search/enablePlugin");
         let response = self
-            .reqwest_client
-            .post(format!(
-                "{}/api/v2{}",
-                self.authority, "/search/enablePlugin"
-            ))
-            .header(
-                reqwest::header::COOKIE,
-                format!("SID={}", self.get_cookie().await?),
-            )
-            .form(&temp)
-            .send()
-            .await
-            .map_err(|e| Error::build(ErrorType::ReqwestError(Box::new(e)), None))?;
+            .send_with_reauth(|| self.reqwest_client.post(&url).form(&temp))
+            .await?;
 
         if response.status().is_success() {
             return Ok(());
@@ -484,8 +562,8 @@ impl QbitApi {
 
     /// ## Usage
     /// updates the search plugins
-    pub async fn search_update_plugins(&mut self) -> Result<(), Error> {
-        self.make_request("search/updatePlugins", "search_update_plugins").await?;
+    pub async fn search_update_plugins(&self) -> Result<(), Error> {
+        self.make_request("/search/updatePlugins", "search_update_plugins").await?;
         Ok(())
     }
 }
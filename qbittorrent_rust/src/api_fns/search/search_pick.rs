@@ -0,0 +1,261 @@
+use std::{borrow::Borrow, collections::HashSet, future::poll_fn, pin::Pin, sync::Arc, time::Duration};
+
+use futures_core::Stream;
+use proc_macros_qbittorrent_rust::Builder;
+
+use crate::{
+    api_fns::torrents::{
+        add_torrent::{AddTorrentOutcome, TorrentAddDescriptorBuilder},
+        magnet::MagnetLink,
+        torrents::{Torrent, TorrentType},
+    },
+    core::api::QbitApi,
+    Error,
+};
+
+use super::search::{Categories, SearchPluginsSpec, SearchResult};
+
+/// the closure ranking the results: the higher the score, the better the result.
+type Scorer = Arc<dyn Fn(&SearchResult) -> f64 + Send + Sync>;
+
+/// ## Info
+/// describes a search for [`QbitApi::search_pick_results`] and [`QbitApi::search_and_pick`]:
+/// what to search, which results to keep, and how to rank them.
+/// create it with [`SearchPick::builder`].
+#[derive(Clone)]
+pub struct SearchPick {
+    pattern: String,
+    plugins: SearchPluginsSpec,
+    categories: Categories,
+    min_seeders: Option<i64>,
+    min_size: Option<i64>,
+    max_size: Option<i64>,
+    limit: usize,
+    poll_interval: Duration,
+    timeout: Option<Duration>,
+    score: Scorer,
+}
+
+impl SearchPick {
+    /// ## Usage
+    /// returns a new instance of [`SearchPickBuilder`]: the builder for [`SearchPick`].
+    pub fn builder(pattern: impl Into<String>) -> SearchPickBuilder {
+        SearchPickBuilder::new(pattern)
+    }
+}
+
+/// ## Info
+/// the builder struct for [`SearchPick`].
+///
+/// ## Fields:
+/// | Property        | Type                 | Description                                                                  |
+/// |-----------------|----------------------|------------------------------------------------------------------------------|
+/// | `plugins`       | [`SearchPluginsSpec`] | The plugins to search with. (default: the enabled ones)                     |
+/// | `categories`    | [`Categories`]       | The categories to search in. (default: all of them)                          |
+/// | `min_seeders`   | `i64`                | Drop the results with fewer seeders. Results with an unknown number are dropped too. |
+/// | `min_size`      | `i64`                | Drop the results smaller than this, in bytes.                                |
+/// | `max_size`      | `i64`                | Drop the results bigger than this, in bytes.                                 |
+/// | `limit`         | `usize`              | How many results to keep, the best ones. (default: 1)                       |
+/// | `poll_interval` | `Duration`           | The time between two polls of the results. (default: 1 second)              |
+/// | `timeout`       | `Duration`           | Stop searching after this long, and rank what was found. (default: none)    |
+/// | `score`         | `Fn(&SearchResult) -> f64` | Ranks the results, the highest score first. (default: the number of seeders) |
+#[derive(Clone, Builder)]
+pub struct SearchPickBuilder {
+    #[builder(custom)]
+    pattern: Option<String>,
+    plugins: Option<SearchPluginsSpec>,
+    categories: Option<Categories>,
+    min_seeders: Option<i64>,
+    min_size: Option<i64>,
+    max_size: Option<i64>,
+    limit: Option<usize>,
+    poll_interval: Option<Duration>,
+    timeout: Option<Duration>,
+    #[builder(custom)]
+    score: Option<Scorer>,
+}
+
+impl SearchPickBuilder {
+    /// ## Usage
+    /// creates a new instance of [`SearchPickBuilder`], with all the other fields set as [`Option::None`].
+    pub fn new(pattern: impl Into<String>) -> Self {
+        Self {
+            pattern: Some(pattern.into()),
+            plugins: None,
+            categories: None,
+            min_seeders: None,
+            min_size: None,
+            max_size: None,
+            limit: None,
+            poll_interval: None,
+            timeout: None,
+            score: None,
+        }
+    }
+
+    /// ## Usage
+    /// sets the closure ranking the results: the higher the score, the better the result.
+    ///
+    /// ## Example
+    /// ```
+    /// # use qbittorrent_rust::SearchPick;
+    /// // prefer the smallest results with at least some seeders.
+    /// let pick = SearchPick::builder("ubuntu")
+    ///     .score(|result| if result.nb_seeders > 5 { -(result.file_size as f64) } else { f64::MIN })
+    ///     .build();
+    /// ```
+    pub fn score(mut self, score: impl Fn(&SearchResult) -> f64 + Send + Sync + 'static) -> Self {
+        self.score = Some(Arc::new(score));
+        self
+    }
+
+    /// ## Usage
+    /// returns the finalized [`SearchPick`].
+    pub fn build(self) -> SearchPick {
+        SearchPick {
+            pattern: self.pattern.unwrap_or_default(),
+            plugins: self.plugins.unwrap_or(SearchPluginsSpec::Enabled),
+            categories: self.categories.unwrap_or(Categories::All),
+            min_seeders: self.min_seeders,
+            min_size: self.min_size,
+            max_size: self.max_size,
+            limit: self.limit.unwrap_or(1),
+            poll_interval: self.poll_interval.unwrap_or(Duration::from_secs(1)),
+            timeout: self.timeout,
+            score: self.score.unwrap_or_else(|| Arc::new(|result| result.nb_seeders as f64)),
+        }
+    }
+}
+
+/// ## Info
+/// a result picked by [`QbitApi::search_and_pick`], and what happened when adding it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PickedTorrent {
+    pub result: SearchResult,
+    pub outcome: AddTorrentOutcome,
+}
+
+impl SearchPick {
+    /// whether the result passes the seeders and size filters.
+    fn accepts(&self, result: &SearchResult) -> bool {
+        self.min_seeders.is_none_or(|min| result.nb_seeders >= min)
+            && self.min_size.is_none_or(|min| result.file_size >= min)
+            && self.max_size.is_none_or(|max| result.file_size >= 0 && result.file_size <= max)
+    }
+
+    /// filters the results, ranks them, drops the duplicates and keeps the best ones.
+    fn pick(&self, results: Vec<SearchResult>) -> Vec<SearchResult> {
+        let mut scored: Vec<(f64, SearchResult)> = results
+            .into_iter()
+            .filter(|result| self.accepts(result))
+            .map(|result| ((self.score)(&result), result))
+            .collect();
+        // stable, so equal scores keep the order qbittorrent found them in.
+        scored.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+
+        // the best of each group of duplicates comes first, so it's the one kept.
+        let mut seen_hashes = HashSet::new();
+        let mut seen_names = HashSet::new();
+        scored
+            .into_iter()
+            .map(|(_, result)| result)
+            .filter(|result| {
+                let hash = result.file_url.parse::<MagnetLink>().ok().map(|magnet| magnet.hash());
+                let name = (result.file_name.trim().to_lowercase(), result.file_size);
+
+                let duplicate = hash.as_ref().is_some_and(|hash| seen_hashes.contains(hash)) || seen_names.contains(&name);
+                seen_hashes.extend(hash);
+                seen_names.insert(name);
+                !duplicate
+            })
+            .take(self.limit)
+            .collect()
+    }
+}
+
+impl QbitApi {
+    /// ## Usage
+    /// runs a search until it's over (or until the timeout), then returns the best results:
+    /// the ones passing the filters, without duplicates, ranked by score.
+    ///
+    /// two results are duplicates if their magnet links have the same info-hash,
+    /// or if they have the same name (ignoring case) and size.
+    ///
+    /// ## Errors
+    /// the same as [`QbitApi::search_session`], plus any error while polling the results.
+    pub async fn search_pick_results(&self, pick: impl Borrow<SearchPick>) -> Result<Vec<SearchResult>, Error> {
+        let pick = pick.borrow();
+        let mut session = self
+            .search_session(pick.pattern.clone(), &pick.plugins, &pick.categories, pick.poll_interval)
+            .await?;
+
+        let mut results = Vec::new();
+        let collect = async {
+            while let Some(result) = poll_fn(|cx| Pin::new(&mut session).poll_next(cx)).await {
+                results.push(result?);
+            }
+            Ok::<(), Error>(())
+        };
+
+        match pick.timeout {
+            // on timeout, the results found so far are ranked anyway.
+            Some(timeout) => {
+                if let Ok(result) = tokio::time::timeout(timeout, collect).await {
+                    result?;
+                }
+            }
+            None => collect.await?,
+        }
+
+        Ok(pick.pick(results))
+    }
+
+    /// ## Usage
+    /// runs a search with [`QbitApi::search_pick_results`], then adds the picked results.
+    ///
+    /// ## Arguments
+    /// - pick: what to search, and how to pick the results.
+    /// - add_options: the options to add the torrents with. its torrents are replaced by the picked results,
+    ///   so it can be created with `TorrentAddDescriptor::builder(vec![])`.
+    ///
+    /// ## Returns
+    /// the picked results, best first, each with its [`AddTorrentOutcome`]. it's empty if nothing was picked.
+    ///
+    /// ## Example
+    /// ```no_run
+    /// # use std::time::Duration;
+    /// # use qbittorrent_rust::{core::api::QbitApi, SearchPick, TorrentAddDescriptor};
+    /// # async fn example(api: QbitApi) -> Result<(), qbittorrent_rust::Error> {
+    /// let pick = SearchPick::builder("ubuntu 24.04")
+    ///     .min_seeders(10)
+    ///     .max_size(8 * 1024 * 1024 * 1024)
+    ///     .timeout(Duration::from_secs(30))
+    ///     .build();
+    ///
+    /// let picked = api.search_and_pick(&pick, TorrentAddDescriptor::builder(vec![]).category("linux")).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn search_and_pick(
+        &self,
+        pick: impl Borrow<SearchPick>,
+        add_options: TorrentAddDescriptorBuilder,
+    ) -> Result<Vec<PickedTorrent>, Error> {
+        let results = self.search_pick_results(pick).await?;
+        if results.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let torrents = results
+            .iter()
+            .map(|result| Torrent::new(TorrentType::Url(result.file_url.clone())))
+            .collect();
+        let outcomes = self.torrents_add_torrent(add_options.torrents(torrents).build()?).await?;
+
+        Ok(results
+            .into_iter()
+            .zip(outcomes)
+            .map(|(result, outcome)| PickedTorrent { result, outcome })
+            .collect())
+    }
+}
@@ -0,0 +1,284 @@
+use std::{borrow::Borrow, cmp::Ordering, path::Path, time::Duration};
+
+use serde::Deserialize;
+use tokio::time::Instant;
+
+use crate::{core::api::QbitApi, error_handling::error_type::ErrorType, Error};
+
+/// how often the plugin list is checked while plugins are being installed.
+const INSTALL_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// ## Info
+/// a search plugin installed on the server, with everything qbittorrent reports about it.
+/// to get them, use [`QbitApi::search_get_search_plugins_info`].
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchPluginInfo {
+    /// the short name, used by the other `search_*` methods. it's the name of the plugin file, without `.py`.
+    pub name: String,
+    /// the name of the site, as displayed by qbittorrent.
+    pub full_name: String,
+    pub enabled: bool,
+    /// eg: `2.14`.
+    pub version: String,
+    /// the url of the site.
+    pub url: String,
+    pub supported_categories: Vec<SearchPluginCategory>,
+}
+
+impl SearchPluginInfo {
+    /// ## Usage
+    /// whether the installed version is older than `version`. versions are compared number by number, so `1.10` is newer than `1.9`.
+    pub fn is_older_than(&self, version: &str) -> bool {
+        compare_versions(&self.version, version) == Ordering::Less
+    }
+}
+
+/// ## Info
+/// a category a search plugin can search in.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct SearchPluginCategory {
+    /// the id, to use in [`Categories::Custom`](super::search::Categories::Custom).
+    pub id: String,
+    pub name: String,
+}
+
+/// ## Info
+/// a search plugin as it should be on the server, part of a [`SearchPluginManifest`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DesiredSearchPlugin {
+    /// the name the plugin has once installed: the name of its file, without `.py`.
+    pub name: String,
+    /// where to install it from: a url, or a path on the machine running qbittorrent.
+    pub source: String,
+    pub enabled: bool,
+    /// if the installed version is older, the plugin is installed again from `source`.
+    pub min_version: Option<String>,
+}
+
+impl DesiredSearchPlugin {
+    /// ## Usage
+    /// creates a new instance of [`DesiredSearchPlugin`], enabled and without a minimum version.
+    pub fn new(name: impl Into<String>, source: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            source: source.into(),
+            enabled: true,
+            min_version: None,
+        }
+    }
+}
+
+/// ## Info
+/// the search plugins a server should have, to converge to with [`QbitApi::search_apply_plugin_manifest`].
+///
+/// ## Example
+/// ```no_run
+/// # use std::time::Duration;
+/// # use qbittorrent_rust::{core::api::QbitApi, DesiredSearchPlugin, SearchPluginManifest};
+/// # async fn example(api: QbitApi) -> Result<(), qbittorrent_rust::Error> {
+/// let manifest = SearchPluginManifest {
+///     plugins: vec![
+///         DesiredSearchPlugin::new("eztv", "https://raw.githubusercontent.com/qbittorrent/search-plugins/master/nova3/engines/eztv.py"),
+///         DesiredSearchPlugin { min_version: Some("1.4".to_string()), ..DesiredSearchPlugin::new("mysite", "/opt/plugins/mysite.py") },
+///     ],
+///     uninstall_others: true,
+/// };
+///
+/// let outcome = api.search_apply_plugin_manifest(&manifest, Duration::from_secs(30)).await?;
+/// println!("installed: {:?}, uninstalled: {:?}", outcome.applied.install, outcome.applied.uninstall);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchPluginManifest {
+    pub plugins: Vec<DesiredSearchPlugin>,
+    /// whether to uninstall the plugins that aren't in `plugins`.
+    pub uninstall_others: bool,
+}
+
+/// ## Info
+/// what to change on the server to match a [`SearchPluginManifest`], as returned by [`SearchPluginManifest::diff`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchPluginChanges {
+    /// the sources of the plugins to install, or to install again because they're too old.
+    pub install: Vec<String>,
+    /// the names of the plugins to uninstall.
+    pub uninstall: Vec<String>,
+    /// the names of the plugins to enable.
+    pub enable: Vec<String>,
+    /// the names of the plugins to disable.
+    pub disable: Vec<String>,
+}
+
+impl SearchPluginChanges {
+    /// ## Usage
+    /// whether the server already matches the manifest.
+    pub fn is_empty(&self) -> bool {
+        self.install.is_empty() && self.uninstall.is_empty() && self.enable.is_empty() && self.disable.is_empty()
+    }
+}
+
+/// ## Info
+/// what [`QbitApi::search_apply_plugin_manifest`] did.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchPluginManifestOutcome {
+    /// the changes that were sent to the server.
+    pub applied: SearchPluginChanges,
+    /// the changes that weren't made because their plugins didn't finish installing in time:
+    /// `install` holds the sources of those plugins (their installation was requested anyway), `enable` and `disable` their names.
+    /// applying the manifest again once they're installed makes the rest of the changes.
+    pub skipped: SearchPluginChanges,
+}
+
+impl SearchPluginManifest {
+    /// ## Usage
+    /// compares the installed plugins with the manifest, without changing anything.
+    pub fn diff(&self, installed: &[SearchPluginInfo]) -> SearchPluginChanges {
+        let mut changes = SearchPluginChanges::default();
+
+        for desired in &self.plugins {
+            let current = installed.iter().find(|plugin| plugin.name == desired.name);
+            let outdated = current.is_some_and(|plugin| {
+                desired.min_version.as_deref().is_some_and(|version| plugin.is_older_than(version))
+            });
+
+            if current.is_none() || outdated {
+                changes.install.push(desired.source.clone());
+            }
+
+            // a freshly installed plugin is enabled.
+            let enabled = match current {
+                Some(plugin) if !outdated => plugin.enabled,
+                _ => true,
+            };
+            match (desired.enabled, enabled) {
+                (true, false) => changes.enable.push(desired.name.clone()),
+                (false, true) => changes.disable.push(desired.name.clone()),
+                _ => {}
+            }
+        }
+
+        if self.uninstall_others {
+            changes.uninstall = installed
+                .iter()
+                .filter(|plugin| self.plugins.iter().all(|desired| desired.name != plugin.name))
+                .map(|plugin| plugin.name.clone())
+                .collect();
+        }
+
+        changes
+    }
+}
+
+impl QbitApi {
+    /// ## Usage
+    /// gets the installed search plugins as a [`Vec`] of [`SearchPluginInfo`]s.
+    pub async fn search_get_search_plugins_info(&self) -> Result<Vec<SearchPluginInfo>, Error> {
+        serde_json::from_str(self.search_get_search_plugins_raw().await?.as_str())
+            .map_err(|e| Error::build(ErrorType::JsonSerdeError(Box::new(e)), None))
+    }
+
+    /// ## Usage
+    /// installs a search plugin from a `.py` file.
+    ///
+    /// ## Warning
+    /// the WebAPI can't upload files for plugins: qbittorrent reads the file itself,
+    /// so the path must exist on the machine running qbittorrent, which isn't always this one.
+    /// if it's this one, pass an absolute path.
+    pub async fn search_install_plugin_file(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let path = path.as_ref().to_string_lossy().into_owned();
+
+        self.search_install_plugins(vec![path]).await
+    }
+
+    /// ## Usage
+    /// installs, uninstalls, enables and disables search plugins so that the server matches the manifest.
+    ///
+    /// ## Arguments
+    /// - install_timeout: how long to wait for the installed plugins to appear in the plugin list.
+    ///   qbittorrent installs plugins in the background, and can only enable or disable them once they're installed.
+    ///
+    /// ## Returns
+    /// the changes that were applied, and the ones that were skipped because their plugins were still installing after `install_timeout`.
+    pub async fn search_apply_plugin_manifest(
+        &self,
+        manifest: impl Borrow<SearchPluginManifest>,
+        install_timeout: Duration,
+    ) -> Result<SearchPluginManifestOutcome, Error> {
+        let manifest = manifest.borrow();
+        let installed = self.search_get_search_plugins_info().await?;
+        let changes = manifest.diff(&installed);
+
+        if !changes.uninstall.is_empty() {
+            self.search_uninstall_plugins(&changes.uninstall).await?;
+        }
+
+        // once the installed plugins are listed, the manifest is compared again to know what to enable and disable.
+        let mut remaining = changes.clone();
+        if !changes.install.is_empty() {
+            self.search_install_plugins(&changes.install).await?;
+
+            let deadline = Instant::now() + install_timeout;
+            loop {
+                remaining = manifest.diff(&self.search_get_search_plugins_info().await?);
+                let now = Instant::now();
+                if remaining.install.is_empty() || now >= deadline {
+                    break;
+                }
+                tokio::time::sleep(INSTALL_POLL_INTERVAL.min(deadline - now)).await;
+            }
+        }
+
+        let installing: Vec<&str> = manifest
+            .plugins
+            .iter()
+            .filter(|plugin| remaining.install.contains(&plugin.source))
+            .map(|plugin| plugin.name.as_str())
+            .collect();
+        let (enable, skipped_enable): (Vec<String>, Vec<String>) =
+            remaining.enable.into_iter().partition(|name| !installing.contains(&name.as_str()));
+        let (disable, skipped_disable): (Vec<String>, Vec<String>) =
+            remaining.disable.into_iter().partition(|name| !installing.contains(&name.as_str()));
+
+        if !enable.is_empty() {
+            self.search_enable_plugins(&enable, true).await?;
+        }
+        if !disable.is_empty() {
+            self.search_enable_plugins(&disable, false).await?;
+        }
+
+        Ok(SearchPluginManifestOutcome {
+            applied: SearchPluginChanges {
+                install: changes.install,
+                uninstall: changes.uninstall,
+                enable,
+                disable,
+            },
+            skipped: SearchPluginChanges {
+                install: remaining.install,
+                uninstall: Vec::new(),
+                enable: skipped_enable,
+                disable: skipped_disable,
+            },
+        })
+    }
+}
+
+/// compares dotted versions number by number; missing numbers are `0`, and parts that aren't numbers count as `0`.
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    let parse = |version: &str| -> Vec<u64> {
+        version
+            .trim()
+            .trim_start_matches('v')
+            .split('.')
+            .map(|part| part.trim().parse().unwrap_or(0))
+            .collect()
+    };
+    let (a, b) = (parse(a), parse(b));
+
+    (0..a.len().max(b.len()))
+        .map(|i| a.get(i).unwrap_or(&0).cmp(b.get(i).unwrap_or(&0)))
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
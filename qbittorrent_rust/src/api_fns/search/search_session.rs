@@ -0,0 +1,150 @@
+use std::{
+    borrow::Borrow,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use futures_core::Stream;
+use tokio::{
+    runtime::Handle,
+    sync::mpsc,
+    task::JoinHandle,
+    time::{self, MissedTickBehavior},
+};
+
+use crate::{core::api::QbitApi, Error};
+
+use super::search::{Categories, SearchJobStatus, SearchPluginsSpec, SearchResult};
+
+/// ## Info
+/// a running search job, returned by [`QbitApi::search_session()`].
+/// it's an asynchronous [`Stream`] of the [`SearchResult`]s, yielded as soon as qbittorrent finds them.
+///
+/// ## Usage
+/// the results come from a background task polling `/search/results`, asking only for the new ones each time.
+/// the stream ends once the job is `Stopped` and all its results were yielded.
+/// if a request fails, the error is yielded and the task keeps polling at the next tick,
+/// unless the job doesn't exist anymore.
+///
+/// dropping the session stops the background task and deletes the search job,
+/// so that abandoned jobs don't count towards the limit of 5 running searches.
+///
+/// ## Example
+/// ```no_run
+/// # use std::{future::poll_fn, pin::Pin, time::Duration};
+/// # use futures_core::Stream;
+/// # use qbittorrent_rust::{core::api::QbitApi, Categories, SearchPluginsSpec};
+/// # async fn example(api: QbitApi) -> Result<(), qbittorrent_rust::Error> {
+/// let mut session = api.search_session("ubuntu", SearchPluginsSpec::Enabled, Categories::All, Duration::from_secs(1)).await?;
+/// // with the `futures` crate, `session.next().await` does the same.
+/// while let Some(result) = poll_fn(|cx| Pin::new(&mut session).poll_next(cx)).await {
+///     let result = result?;
+///     println!("{} ({} seeders): {}", result.file_name, result.nb_seeders, result.file_url);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct SearchSession {
+    id: u64,
+    api: QbitApi,
+    receiver: mpsc::Receiver<Result<SearchResult, Error>>,
+    task: JoinHandle<()>,
+}
+
+impl SearchSession {
+    /// ## Usage
+    /// the id of the search job, for the other `search_*` methods.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+}
+
+impl Stream for SearchSession {
+    type Item = Result<SearchResult, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_recv(cx)
+    }
+}
+
+impl Drop for SearchSession {
+    fn drop(&mut self) {
+        self.task.abort();
+
+        // there's no async drop: the job is deleted in a new task, if there's still a runtime to run it.
+        if let Ok(handle) = Handle::try_current() {
+            let api = self.api.clone();
+            let id = self.id;
+            handle.spawn(async move {
+                let _ = api.search_delete(id).await;
+            });
+        }
+    }
+}
+
+impl QbitApi {
+    /// ## Usage
+    /// Starts a search, and returns a [`SearchSession`] streaming its results.
+    ///
+    /// ## Arguments
+    /// - pattern, plugins, categories: the same as [`QbitApi::search_start`].
+    /// - interval: the time between two polls of the results.
+    ///
+    /// ## Errors
+    /// the same as [`QbitApi::search_start`]; notably, qbittorrent refuses to run more than 5 searches at once.
+    ///
+    /// ## Warning
+    /// - this method spawns a tokio task, so it must be called from within a tokio runtime.
+    pub async fn search_session(
+        &self,
+        pattern: impl Into<String>,
+        plugins: impl Borrow<SearchPluginsSpec>,
+        categories: impl Borrow<Categories>,
+        interval: Duration,
+    ) -> Result<SearchSession, Error> {
+        let id = self.search_start(pattern, plugins, categories).await?;
+
+        let (sender, receiver) = mpsc::channel(256);
+        let api = self.clone();
+
+        let task = tokio::spawn(async move {
+            let mut offset = 0;
+            let mut ticker = time::interval(interval);
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+            loop {
+                ticker.tick().await;
+
+                let page = match api.search_results_typed(id, None, Some(offset)).await {
+                    Ok(page) => page,
+                    Err(e) => {
+                        let job_is_gone = e.code == Some(404);
+                        if sender.send(Err(e)).await.is_err() || job_is_gone {
+                            return;
+                        }
+                        continue;
+                    }
+                };
+
+                offset += page.results.len() as i64;
+                for result in page.results {
+                    if sender.send(Ok(result)).await.is_err() {
+                        return;
+                    }
+                }
+
+                if page.status == SearchJobStatus::Stopped && offset as u64 >= page.total {
+                    return;
+                }
+            }
+        });
+
+        Ok(SearchSession {
+            id,
+            api: self.clone(),
+            receiver,
+            task,
+        })
+    }
+}
@@ -1 +1,3 @@
-pub mod sync;
\ No newline at end of file
+pub mod sync;
+pub mod sync_state;
+pub mod sync_events;
\ No newline at end of file
@@ -0,0 +1,186 @@
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use futures_core::Stream;
+use tokio::{
+    sync::mpsc,
+    task::JoinHandle,
+    time::{self, MissedTickBehavior},
+};
+
+use crate::{
+    api_fns::torrents::info::{TorrentInfo, TorrentState},
+    core::api::QbitApi,
+    Error,
+};
+
+use super::sync_state::{ServerState, SyncState};
+
+/// ## Info
+/// describes a change in the main data of the server, derived from the `/sync/maindata` updates.
+///
+/// ## Variants
+/// - `TorrentAdded`: a new torrent appeared. on the first update, every torrent is reported this way.
+/// - `TorrentRemoved`: a torrent was removed; it contains the last known version of the torrent.
+/// - `StateChanged`: the [`TorrentState`] of a torrent changed.
+/// - `ProgressChanged`: the progress of a torrent changed, from `0.0` to `1.0`.
+/// - `CategoryChanged`: the category of a torrent changed. an empty string means no category.
+/// - `TagsChanged`: the tags of a torrent changed.
+/// - `ServerStateChanged`: the global state of the server changed; it contains the new state.
+#[derive(Debug, Clone)]
+pub enum SyncEvent {
+    TorrentAdded(TorrentInfo),
+    TorrentRemoved(TorrentInfo),
+    StateChanged {
+        hash: String,
+        from: TorrentState,
+        to: TorrentState,
+    },
+    ProgressChanged {
+        hash: String,
+        from: f64,
+        to: f64,
+    },
+    CategoryChanged {
+        hash: String,
+        from: String,
+        to: String,
+    },
+    TagsChanged {
+        hash: String,
+        from: Vec<String>,
+        to: Vec<String>,
+    },
+    ServerStateChanged(ServerState),
+}
+
+impl SyncEvent {
+    /// ## Usage
+    /// returns the hash of the torrent the event is about, or [`None`] for [`SyncEvent::ServerStateChanged`].
+    pub fn hash(&self) -> Option<&str> {
+        match self {
+            SyncEvent::TorrentAdded(torrent) | SyncEvent::TorrentRemoved(torrent) => {
+                Some(torrent.hash.as_str())
+            }
+            SyncEvent::StateChanged { hash, .. }
+            | SyncEvent::ProgressChanged { hash, .. }
+            | SyncEvent::CategoryChanged { hash, .. }
+            | SyncEvent::TagsChanged { hash, .. } => Some(hash.as_str()),
+            SyncEvent::ServerStateChanged(_) => None,
+        }
+    }
+
+    /// pushes to `events` every change between the old and the new version of a torrent.
+    pub(crate) fn push_torrent_changes(
+        events: &mut Vec<SyncEvent>,
+        old: Option<&TorrentInfo>,
+        new: &TorrentInfo,
+    ) {
+        let old = match old {
+            Some(old) => old,
+            None => {
+                events.push(SyncEvent::TorrentAdded(new.clone()));
+                return;
+            }
+        };
+
+        if old.state != new.state {
+            events.push(SyncEvent::StateChanged {
+                hash: new.hash.clone(),
+                from: old.state,
+                to: new.state,
+            });
+        }
+
+        if old.progress != new.progress {
+            events.push(SyncEvent::ProgressChanged {
+                hash: new.hash.clone(),
+                from: old.progress,
+                to: new.progress,
+            });
+        }
+
+        if old.category != new.category {
+            events.push(SyncEvent::CategoryChanged {
+                hash: new.hash.clone(),
+                from: old.category.clone(),
+                to: new.category.clone(),
+            });
+        }
+
+        if old.tags != new.tags {
+            events.push(SyncEvent::TagsChanged {
+                hash: new.hash.clone(),
+                from: old.tags.clone(),
+                to: new.tags.clone(),
+            });
+        }
+    }
+}
+
+/// ## Info
+/// an asynchronous [`Stream`] of [`SyncEvent`]s, returned by [`QbitApi::sync_events()`].
+///
+/// ## Usage
+/// the events come from a background task polling `/sync/maindata`.
+/// if a request fails, the error is yielded and the task keeps polling at the next tick.
+/// dropping the stream stops the background task.
+pub struct SyncEventStream {
+    receiver: mpsc::Receiver<Result<SyncEvent, Error>>,
+    task: JoinHandle<()>,
+}
+
+impl Stream for SyncEventStream {
+    type Item = Result<SyncEvent, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_recv(cx)
+    }
+}
+
+impl Drop for SyncEventStream {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+impl QbitApi {
+    /// ## Usage
+    /// Starts polling `/sync/maindata` in the background, and returns a [`SyncEventStream`] of the changes.
+    ///
+    /// ## Arguments
+    /// - interval: the time between two polls.
+    ///
+    /// ## Warning
+    /// - this method spawns a tokio task, so it must be called from within a tokio runtime.
+    pub fn sync_events(&self, interval: Duration) -> SyncEventStream {
+        let (sender, receiver) = mpsc::channel(256);
+        let api = self.clone();
+
+        let task = tokio::spawn(async move {
+            let mut state = SyncState::new();
+            let mut ticker = time::interval(interval);
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+            loop {
+                ticker.tick().await;
+
+                let results = match api.sync_update_state(&mut state).await {
+                    Ok(events) => events.into_iter().map(Ok).collect(),
+                    Err(e) => vec![Err(e)],
+                };
+
+                for result in results {
+                    if sender.send(result).await.is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+
+        SyncEventStream { receiver, task }
+    }
+}
@@ -0,0 +1,300 @@
+use std::collections::HashMap;
+
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{Map, Value};
+
+use crate::{
+    api_fns::torrents::info::TorrentInfo, core::api::QbitApi,
+    error_handling::error_type::ErrorType, Error,
+};
+
+use super::sync_events::SyncEvent;
+
+/// ## Info
+/// represents a category, as reported by `/sync/maindata` and `/torrents/categories`.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct CategoryInfo {
+    pub name: String,
+    #[serde(rename = "savePath")]
+    pub save_path: String,
+}
+
+/// ## Info
+/// represents the global state of the server, as reported by the `server_state` object of `/sync/maindata`.
+///
+/// ## Fields
+/// | Field                     | Type      | Description                                          |
+/// |---------------------------|-----------|------------------------------------------------------|
+/// | `alltime_dl`              | `Integer` | Data downloaded since the first start (bytes)        |
+/// | `alltime_ul`              | `Integer` | Data uploaded since the first start (bytes)          |
+/// | `connection_status`       | `String`  | `connected`, `firewalled` or `disconnected`          |
+/// | `dht_nodes`               | `Integer` | DHT nodes connected to                               |
+/// | `dl_info_data`            | `Integer` | Data downloaded this session (bytes)                 |
+/// | `dl_info_speed`           | `Integer` | Global download rate (bytes/s)                       |
+/// | `dl_rate_limit`           | `Integer` | Download rate limit (bytes/s)                        |
+/// | `free_space_on_disk`      | `Integer` | Free space in the default save path (bytes)          |
+/// | `global_ratio`            | `String`  | Global share ratio                                   |
+/// | `queueing`                | `Bool`    | Whether torrent queueing is enabled                  |
+/// | `refresh_interval`        | `Integer` | Suggested refresh interval for the WebUI (ms)        |
+/// | `up_info_data`            | `Integer` | Data uploaded this session (bytes)                   |
+/// | `up_info_speed`           | `Integer` | Global upload rate (bytes/s)                         |
+/// | `up_rate_limit`           | `Integer` | Upload rate limit (bytes/s)                          |
+/// | `use_alt_speed_limits`    | `Bool`    | Whether the alternative speed limits are enabled     |
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct ServerState {
+    pub alltime_dl: i64,
+    pub alltime_ul: i64,
+    pub average_time_queue: i64,
+    pub connection_status: String,
+    pub dht_nodes: i64,
+    pub dl_info_data: i64,
+    pub dl_info_speed: i64,
+    pub dl_rate_limit: i64,
+    pub free_space_on_disk: i64,
+    pub global_ratio: String,
+    pub queued_io_jobs: i64,
+    pub queueing: bool,
+    pub read_cache_hits: String,
+    pub read_cache_overload: String,
+    pub refresh_interval: i64,
+    pub total_buffers_size: i64,
+    pub total_peer_connections: i64,
+    pub total_queued_size: i64,
+    pub total_wasted_session: i64,
+    pub up_info_data: i64,
+    pub up_info_speed: i64,
+    pub up_rate_limit: i64,
+    pub use_alt_speed_limits: bool,
+    pub write_cache_overload: String,
+}
+
+/// ## Info
+/// holds the main data model of the server, kept up to date through `/sync/maindata`.
+///
+/// ## Usage
+/// create one with [`SyncState::new()`], then call [`QbitApi::sync_update_state()`] as often as needed:
+/// the state keeps track of the `rid` by itself and merges every partial update it receives,
+/// including removed torrents, categories and tags.
+///
+/// ## Example
+/// ```no_run
+/// # use qbittorrent_rust::{core::{api::QbitApi, creds::Credentials}, SyncState};
+/// # async fn example() -> Result<(), qbittorrent_rust::Error> {
+/// let api = QbitApi::new("http://localhost:6011/", Credentials::new("user_name", "password")).await?;
+/// let mut state = SyncState::new();
+///
+/// api.sync_update_state(&mut state).await?;
+/// for torrent in state.torrents().values() {
+///     println!("{}: {:.1}%", torrent.name, torrent.progress * 100.0);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SyncState {
+    rid: u64,
+    torrents: HashMap<String, TorrentInfo>,
+    categories: HashMap<String, CategoryInfo>,
+    tags: Vec<String>,
+    server_state: ServerState,
+    trackers: HashMap<String, Vec<String>>,
+
+    // the merged json objects the typed fields are built from: partial updates only carry the changed keys.
+    raw_torrents: HashMap<String, Map<String, Value>>,
+    raw_categories: HashMap<String, Map<String, Value>>,
+    raw_server_state: Map<String, Value>,
+}
+
+impl SyncState {
+    /// ## Usage
+    /// creates a new, empty [`SyncState`]. The first update will request the full data.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// ## Usage
+    /// returns the response id of the last applied update. `0` means no update was applied yet.
+    pub fn rid(&self) -> u64 {
+        self.rid
+    }
+
+    /// ## Usage
+    /// returns all the torrents, keyed by their hash.
+    pub fn torrents(&self) -> &HashMap<String, TorrentInfo> {
+        &self.torrents
+    }
+
+    /// ## Usage
+    /// returns the torrent with the specified hash, if there is one.
+    pub fn torrent(&self, hash: impl AsRef<str>) -> Option<&TorrentInfo> {
+        self.torrents.get(hash.as_ref())
+    }
+
+    /// ## Usage
+    /// returns all the categories, keyed by their name.
+    pub fn categories(&self) -> &HashMap<String, CategoryInfo> {
+        &self.categories
+    }
+
+    /// ## Usage
+    /// returns all the tags.
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    /// ## Usage
+    /// returns the global state of the server.
+    pub fn server_state(&self) -> &ServerState {
+        &self.server_state
+    }
+
+    /// ## Usage
+    /// returns the trackers, keyed by their url; each one is associated to the hashes of the torrents using it.
+    pub fn trackers(&self) -> &HashMap<String, Vec<String>> {
+        &self.trackers
+    }
+
+    /// ## Usage
+    /// applies a `/sync/maindata` response to the state, and advances the `rid`.
+    /// if the response is a full update, everything known until now is discarded first.
+    ///
+    /// ## Returns
+    /// the [`SyncEvent`]s describing what changed. on the first update, every torrent is reported as added.
+    ///
+    /// ## Errors
+    /// - returns an [`Error`] with error type [`ErrorType::ParameterNotExpected`] if `main_data` isn't a json object.
+    /// - returns an [`Error`] with error type [`ErrorType::JsonSerdeError`] if one of the merged objects couldn't be parsed.
+    pub fn apply(&mut self, main_data: Value) -> Result<Vec<SyncEvent>, Error> {
+        let mut main_data = match main_data {
+            Value::Object(map) => map,
+            _ => return Err(Error::build(ErrorType::ParameterNotExpected, None)),
+        };
+
+        let mut events = vec![];
+        let previous_server_state = self.server_state.clone();
+
+        // on a full update the torrents are compared against the ones known before it.
+        let mut previous_torrents = None;
+        if main_data.get("full_update").and_then(Value::as_bool).unwrap_or(false) {
+            let old = std::mem::take(self);
+            self.rid = old.rid;
+            self.server_state = old.server_state;
+            self.raw_server_state = old.raw_server_state;
+            previous_torrents = Some(old.torrents);
+        }
+
+        if let Some(Value::Object(torrents)) = main_data.remove("torrents") {
+            for (hash, delta) in torrents {
+                let raw = self.raw_torrents.entry(hash.clone()).or_default();
+                merge(raw, delta);
+                raw.insert("hash".to_string(), Value::String(hash.clone()));
+                let torrent: TorrentInfo = typed(raw)?;
+
+                let old = match previous_torrents.as_mut() {
+                    Some(previous) => previous.remove(&hash),
+                    None => self.torrents.get(&hash).cloned(),
+                };
+                SyncEvent::push_torrent_changes(&mut events, old.as_ref(), &torrent);
+
+                self.torrents.insert(hash, torrent);
+            }
+        }
+
+        if let Some(previous) = previous_torrents {
+            events.extend(previous.into_values().map(SyncEvent::TorrentRemoved));
+        }
+
+        for hash in strings(main_data.remove("torrents_removed")) {
+            self.raw_torrents.remove(&hash);
+            if let Some(torrent) = self.torrents.remove(&hash) {
+                events.push(SyncEvent::TorrentRemoved(torrent));
+            }
+        }
+
+        if let Some(Value::Object(categories)) = main_data.remove("categories") {
+            for (name, delta) in categories {
+                let raw = self.raw_categories.entry(name.clone()).or_default();
+                merge(raw, delta);
+                let category = typed(raw)?;
+                self.categories.insert(name, category);
+            }
+        }
+
+        for name in strings(main_data.remove("categories_removed")) {
+            self.raw_categories.remove(&name);
+            self.categories.remove(&name);
+        }
+
+        for tag in strings(main_data.remove("tags")) {
+            if !self.tags.contains(&tag) {
+                self.tags.push(tag);
+            }
+        }
+
+        let removed_tags = strings(main_data.remove("tags_removed"));
+        self.tags.retain(|tag| !removed_tags.contains(tag));
+
+        if let Some(delta) = main_data.remove("server_state") {
+            merge(&mut self.raw_server_state, delta);
+            self.server_state = typed(&self.raw_server_state)?;
+
+            if self.server_state != previous_server_state {
+                events.push(SyncEvent::ServerStateChanged(self.server_state.clone()));
+            }
+        }
+
+        if let Some(Value::Object(trackers)) = main_data.remove("trackers") {
+            for (url, hashes) in trackers {
+                self.trackers.insert(url, strings(Some(hashes)));
+            }
+        }
+
+        for url in strings(main_data.remove("trackers_removed")) {
+            self.trackers.remove(&url);
+        }
+
+        if let Some(rid) = main_data.get("rid").and_then(Value::as_u64) {
+            self.rid = rid;
+        }
+
+        Ok(events)
+    }
+}
+
+impl QbitApi {
+    /// ## Usage
+    /// Requests the changes since the last update of the [`SyncState`] and applies them to it.
+    ///
+    /// ## Returns
+    /// the [`SyncEvent`]s describing what changed.
+    pub async fn sync_update_state(&self, state: &mut SyncState) -> Result<Vec<SyncEvent>, Error> {
+        let main_data = self.sync_get_main_data(state.rid()).await?;
+        state.apply(main_data)
+    }
+}
+
+/// overwrites the keys of `target` with the ones present in `delta`.
+fn merge(target: &mut Map<String, Value>, delta: Value) {
+    if let Value::Object(delta) = delta {
+        for (key, value) in delta {
+            target.insert(key, value);
+        }
+    }
+}
+
+fn typed<T: DeserializeOwned>(raw: &Map<String, Value>) -> Result<T, Error> {
+    serde_json::from_value(Value::Object(raw.clone()))
+        .map_err(|e| Error::build(ErrorType::JsonSerdeError(Box::new(e)), None))
+}
+
+fn strings(value: Option<Value>) -> Vec<String> {
+    match value {
+        Some(Value::Array(vec)) => vec
+            .into_iter()
+            .filter_map(|v| v.as_str().map(|s| s.to_string()))
+            .collect(),
+        _ => vec![],
+    }
+}
@@ -0,0 +1 @@
+pub mod torrent_creator;
\ No newline at end of file
@@ -0,0 +1,390 @@
+use std::{collections::HashMap, time::Duration};
+
+use proc_macros_qbittorrent_rust::Builder;
+use serde::Deserialize;
+
+use crate::{
+    code,
+    core::{api::QbitApi, version::ApiVersion},
+    error_handling::error_type::ErrorType,
+    misc::sep_vec::SepVec,
+    Error,
+};
+
+/// ## Info
+/// the format of a created torrent. `V2` and `Hybrid` require qbittorrent to be built with libtorrent 2.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TorrentFormat {
+    V1,
+    V2,
+    Hybrid,
+}
+impl TorrentFormat {
+    fn as_str(&self) -> &'static str {
+        match self {
+            TorrentFormat::V1 => "v1",
+            TorrentFormat::V2 => "v2",
+            TorrentFormat::Hybrid => "hybrid",
+        }
+    }
+}
+
+/// ## Info
+/// describes a `.torrent` file to create on the server, with [`QbitApi::torrent_creator_add_task`].
+///
+/// ## Example
+/// ```no_run
+/// # use std::time::Duration;
+/// # use qbittorrent_rust::{core::api::QbitApi, TorrentCreationTask, TorrentFormat};
+/// # async fn example(api: QbitApi) -> Result<(), qbittorrent_rust::Error> {
+/// let task = TorrentCreationTask::builder("/data/datasets/weather-2024")
+///     .private(true)
+///     .trackers(vec!["https://tracker.example.com/announce".to_string()])
+///     .format(TorrentFormat::Hybrid)
+///     .build();
+/// let torrent = api
+///     .torrent_creator_create_torrent(&task, Duration::from_secs(1), Some(Duration::from_secs(600)))
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct TorrentCreationTask {
+    source_path: String,
+    torrent_file_path: Option<String>,
+    piece_size: Option<u64>,
+    private: Option<bool>,
+    start_seeding: Option<bool>,
+    comment: Option<String>,
+    source: Option<String>,
+    trackers: Option<Vec<String>>,
+    web_seeds: Option<Vec<String>>,
+    format: Option<TorrentFormat>,
+}
+impl TorrentCreationTask {
+    /// ## Usage
+    /// returns a [`TorrentCreationTaskBuilder`], the builder for [`TorrentCreationTask`].
+    ///
+    /// ## Arguments
+    /// - source_path: the file or folder, on the server, to make the torrent of.
+    pub fn builder(source_path: impl Into<String>) -> TorrentCreationTaskBuilder {
+        TorrentCreationTaskBuilder::new(source_path)
+    }
+
+    fn form(&self) -> HashMap<&'static str, String> {
+        let mut form = HashMap::new();
+        form.insert("sourcePath", self.source_path.clone());
+
+        if let Some(torrent_file_path) = &self.torrent_file_path {
+            form.insert("torrentFilePath", torrent_file_path.clone());
+        }
+        if let Some(piece_size) = self.piece_size {
+            form.insert("pieceSize", piece_size.to_string());
+        }
+        if let Some(private) = self.private {
+            form.insert("private", private.to_string());
+        }
+        if let Some(start_seeding) = self.start_seeding {
+            form.insert("startSeeding", start_seeding.to_string());
+        }
+        if let Some(comment) = &self.comment {
+            form.insert("comment", comment.clone());
+        }
+        if let Some(source) = &self.source {
+            form.insert("source", source.clone());
+        }
+        if let Some(trackers) = &self.trackers {
+            form.insert("trackers", SepVec::new(trackers.clone(), "|").to_string());
+        }
+        if let Some(web_seeds) = &self.web_seeds {
+            form.insert("urlSeeds", SepVec::new(web_seeds.clone(), "|").to_string());
+        }
+        if let Some(format) = self.format {
+            form.insert("format", format.as_str().to_string());
+        }
+
+        form
+    }
+}
+
+/// ## Info
+/// builder struct for [`TorrentCreationTask`].
+///
+/// ## Fields
+/// | Property            | Type          | Description                                                                  |
+/// |---------------------|---------------|------------------------------------------------------------------------------|
+/// | `torrent_file_path` | `String`      | where to also save the `.torrent` file on the server.                        |
+/// | `piece_size`        | `u64`         | the piece size in bytes. `0` (the default) lets qbittorrent choose.          |
+/// | `private`           | `bool`        | whether the torrent is private. (default: false)                             |
+/// | `start_seeding`     | `bool`        | whether to add the torrent to qbittorrent and start seeding it. (default: false) |
+/// | `comment`           | `String`      | the comment of the torrent.                                                  |
+/// | `source`            | `String`      | the source of the torrent, used by some private trackers.                    |
+/// | `trackers`          | `Vec<String>` | the tracker urls.                                                            |
+/// | `web_seeds`         | `Vec<String>` | the web seed urls.                                                           |
+/// | `format`            | `TorrentFormat` | v1, v2 or hybrid.                                                          |
+#[derive(Debug, Clone, Builder)]
+pub struct TorrentCreationTaskBuilder {
+    #[builder(custom)]
+    source_path: Option<String>,
+    torrent_file_path: Option<String>,
+    piece_size: Option<u64>,
+    private: Option<bool>,
+    start_seeding: Option<bool>,
+    comment: Option<String>,
+    source: Option<String>,
+    trackers: Option<Vec<String>>,
+    web_seeds: Option<Vec<String>>,
+    format: Option<TorrentFormat>,
+}
+impl TorrentCreationTaskBuilder {
+    /// ## Usage
+    /// creates a new instance of [`TorrentCreationTaskBuilder`].
+    pub fn new(source_path: impl Into<String>) -> Self {
+        Self {
+            source_path: Some(source_path.into()),
+            torrent_file_path: None,
+            piece_size: None,
+            private: None,
+            start_seeding: None,
+            comment: None,
+            source: None,
+            trackers: None,
+            web_seeds: None,
+            format: None,
+        }
+    }
+
+    /// ## Usage
+    /// returns the finalized [`TorrentCreationTask`].
+    pub fn build(self) -> TorrentCreationTask {
+        TorrentCreationTask {
+            source_path: self.source_path.unwrap_or_default(),
+            torrent_file_path: self.torrent_file_path,
+            piece_size: self.piece_size,
+            private: self.private,
+            start_seeding: self.start_seeding,
+            comment: self.comment,
+            source: self.source,
+            trackers: self.trackers,
+            web_seeds: self.web_seeds,
+            format: self.format,
+        }
+    }
+}
+
+/// ## Info
+/// the status of a torrent creation task.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+pub enum TorrentCreationState {
+    Queued,
+    Running,
+    Finished,
+    Failed,
+    #[default]
+    #[serde(other)]
+    Unknown,
+}
+
+/// ## Info
+/// the status of a torrent creation task, as returned by [`QbitApi::torrent_creator_status`].
+///
+/// the times are formatted by qbittorrent, and are empty if the task didn't reach that point yet.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TorrentCreationStatus {
+    #[serde(rename = "taskID")]
+    pub task_id: String,
+    pub source_path: String,
+    #[serde(default)]
+    pub torrent_file_path: Option<String>,
+    #[serde(default)]
+    pub piece_size: u64,
+    #[serde(default)]
+    pub private: bool,
+    #[serde(default)]
+    pub status: TorrentCreationState,
+    /// from 0 to 100.
+    #[serde(default)]
+    pub progress: f64,
+    #[serde(default)]
+    pub error_message: Option<String>,
+    #[serde(default)]
+    pub time_added: Option<String>,
+    #[serde(default)]
+    pub time_started: Option<String>,
+    #[serde(default)]
+    pub time_finished: Option<String>,
+}
+
+impl QbitApi {
+    /// ## Usage
+    /// queues the creation of a `.torrent` file on the server, returning the id of the task.
+    ///
+    /// ## Errors
+    /// requires the WebAPI 2.11 (qbittorrent 5.0); older servers return [`ErrorType::UnsupportedByServer`].
+    pub async fn torrent_creator_add_task(&self, task: &TorrentCreationTask) -> Result<String, Error> {
+        self.require_version(ApiVersion::V2_11_0).await?;
+
+        let response = self
+            .make_request_with_form_non_idempotent("/torrentcreator/addTask", "torrent_creator_add_task", task.form())
+            .await
+            .map_err(|e| match e.code {
+                Some(400) => Error::build(ErrorType::MiscError("the source path is missing or invalid".to_string()), e.code),
+                Some(409) => Error::build(ErrorType::MiscError("the torrent creation queue is full".to_string()), e.code),
+                _ => e,
+            })?;
+
+        #[derive(Deserialize)]
+        struct AddedTask {
+            #[serde(rename = "taskID")]
+            task_id: String,
+        }
+
+        let added: AddedTask = serde_json::from_str(&response)
+            .map_err(|e| Error::build(ErrorType::JsonSerdeError(Box::new(e)), None))?;
+        Ok(added.task_id)
+    }
+
+    /// ## Usage
+    /// gets the status of every torrent creation task, or only of the one with the given id.
+    pub async fn torrent_creator_status(&self, task_id: Option<&str>) -> Result<Vec<TorrentCreationStatus>, Error> {
+        self.require_version(ApiVersion::V2_11_0).await?;
+
+        let mut form = HashMap::new();
+        if let Some(task_id) = task_id {
+            form.insert("taskID", task_id.to_string());
+        }
+
+        let response = self
+            .make_request_with_form("/torrentcreator/status", "torrent_creator_status", form)
+            .await
+            .map_err(task_not_found)?;
+
+        serde_json::from_str(&response).map_err(|e| Error::build(ErrorType::JsonSerdeError(Box::new(e)), None))
+    }
+
+    /// ## Usage
+    /// gets the `.torrent` file made by a finished task.
+    pub async fn torrent_creator_torrent_file(&self, task_id: &str) -> Result<Vec<u8>, Error> {
+        self.require_version(ApiVersion::V2_11_0).await?;
+
+        let url = format!("{}/api/v2/torrentcreator/torrentFile", self.authority);
+        let form = [("taskID", task_id)];
+
+        let response = self
+            .send_with_reauth(|| self.reqwest_client.post(&url).form(&form))
+            .await?;
+
+        match response.status().as_u16() {
+            200..=299 => Ok(response
+                .bytes()
+                .await
+                .map_err(|e| Error::build(ErrorType::ReqwestError(Box::new(e)), None))?
+                .to_vec()),
+            404 => Err(task_not_found(Error::build(ErrorType::MiscNetError(404), Some(404)))),
+            409 => Err(Error::build(
+                ErrorType::MiscError("the torrent creation task didn't finish, or failed".to_string()),
+                code!(response),
+            )),
+            num => Err(Error::build(ErrorType::MiscNetError(num), Some(num))),
+        }
+    }
+
+    /// ## Usage
+    /// deletes a torrent creation task, stopping it if it's running.
+    pub async fn torrent_creator_delete_task(&self, task_id: &str) -> Result<(), Error> {
+        self.require_version(ApiVersion::V2_11_0).await?;
+
+        // a repeat after a lost response wouldn't find the task anymore.
+        self.make_request_with_form_non_idempotent(
+            "/torrentcreator/deleteTask",
+            "torrent_creator_delete_task",
+            HashMap::from([("taskID", task_id)]),
+        )
+        .await
+        .map_err(task_not_found)?;
+
+        Ok(())
+    }
+
+    /// ## Usage
+    /// waits until a torrent creation task is finished, checking its status every `poll_interval`,
+    /// and returns the `.torrent` file.
+    ///
+    /// ## Arguments
+    /// - timeout: how long to wait at most. if `None`, waits until the task is over, however long it takes.
+    ///
+    /// ## Errors
+    /// - if the task fails, returns an [`Error`] with error type [`ErrorType::MiscError`] holding qbittorrent's error message.
+    /// - if the task isn't over after `timeout`, returns an [`Error`] with error type [`ErrorType::MiscError`]. the task is left running.
+    pub async fn torrent_creator_wait_for_torrent(
+        &self,
+        task_id: &str,
+        poll_interval: Duration,
+        timeout: Option<Duration>,
+    ) -> Result<Vec<u8>, Error> {
+        let wait = async {
+            loop {
+                let status = self
+                    .torrent_creator_status(Some(task_id))
+                    .await?
+                    .into_iter()
+                    .next()
+                    .ok_or_else(|| task_not_found(Error::build(ErrorType::MiscNetError(404), Some(404))))?;
+
+                match status.status {
+                    TorrentCreationState::Finished => return self.torrent_creator_torrent_file(task_id).await,
+                    TorrentCreationState::Failed => {
+                        return Err(Error::build(
+                            ErrorType::MiscError(format!(
+                                "the torrent creation failed: {}",
+                                status.error_message.unwrap_or_default()
+                            )),
+                            None,
+                        ))
+                    }
+                    _ => tokio::time::sleep(poll_interval).await,
+                }
+            }
+        };
+
+        match timeout {
+            Some(timeout) => tokio::time::timeout(timeout, wait).await.unwrap_or_else(|_| {
+                Err(Error::build(
+                    ErrorType::MiscError(format!("the torrent creation didn't finish within {:?}", timeout)),
+                    None,
+                ))
+            }),
+            None => wait.await,
+        }
+    }
+
+    /// ## Usage
+    /// creates a `.torrent` file on the server and returns it: adds the task, waits for it (for `timeout` at most, if given), and deletes it.
+    ///
+    /// ## Errors
+    /// same as [`QbitApi::torrent_creator_wait_for_torrent`]. the task is deleted, and so stopped, on timeout too.
+    /// an error while deleting the task isn't returned, and the task is then left on the server.
+    pub async fn torrent_creator_create_torrent(
+        &self,
+        task: &TorrentCreationTask,
+        poll_interval: Duration,
+        timeout: Option<Duration>,
+    ) -> Result<Vec<u8>, Error> {
+        let task_id = self.torrent_creator_add_task(task).await?;
+
+        let torrent = self.torrent_creator_wait_for_torrent(&task_id, poll_interval, timeout).await;
+        // the task is deleted even if it failed. failing to delete it is ignored: the torrent, or the error of the task,
+        // is what was asked for, and a task that's left over doesn't change it.
+        let _ = self.torrent_creator_delete_task(&task_id).await;
+
+        torrent
+    }
+}
+
+fn task_not_found(e: Error) -> Error {
+    match e.code {
+        Some(404) => Error::build(ErrorType::MiscError("the torrent creation task was not found".to_string()), e.code),
+        _ => e,
+    }
+}
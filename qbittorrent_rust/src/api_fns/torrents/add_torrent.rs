@@ -1,15 +1,19 @@
-use core::panic;
-use std::borrow::Borrow;
+use std::{borrow::Borrow, collections::HashMap};
 
 use proc_macros_qbittorrent_rust::Builder;
-use reqwest::header;
+use serde::Deserialize;
 use tokio::{fs::File, io::AsyncReadExt};
 
 use crate::{
-    code, core::api::QbitApi, error_handling::{error_type::ErrorType, errors::Error}, misc::sep_vec::SepVec
+    code, core::{api::QbitApi, version::ApiVersion}, error_handling::{error_type::ErrorType, errors::Error}, misc::sep_vec::SepVec
 };
 
-use super::torrents::Torrent;
+use super::{
+    info::{TorrentHash, TorrentListGetConfig},
+    magnet::MagnetLink,
+    metainfo::Metainfo,
+    torrents::{Torrent, TorrentInner},
+};
 
 /// ## Info
 /// struct that describes the adding of a torrent.
@@ -18,9 +22,7 @@ use super::torrents::Torrent;
 /// - using the `builder` function; if you use this option, remember to always set the `torrents`. 
 #[derive(Debug, Clone)]
 pub struct TorrentAddDescriptor {
-    urls: SepVec<String, String>,
-
-    paths: Vec<String>,
+    torrents: Vec<TorrentInner>,
 
     /// Download folder path
     savepath: Option<String>,
@@ -194,37 +196,22 @@ impl TorrentAddDescriptorBuilder {
         }
     }
 
+    /// ## Info
+    /// replaces the torrents to add, keeping the other options.
+    pub fn torrents(mut self, torrents: Vec<Torrent>) -> Self {
+        self.torrents = Some(torrents);
+        self
+    }
+
     /// ## Info
     /// returns the finalized [`TorrentAddDescriptor`].
     ///
     /// ## Errors
     /// - if the `torrent`s vector was set as empty, it will return an [`Error`] with error type [`ErrorType::TorrentsNotSet`].
     pub fn build(self) -> Result<TorrentAddDescriptor, Error> {
-        let (urls, paths) = match self.torrents {
-            Some(t) => {
-                if t.is_empty() {
-                    return Err(Error::build(ErrorType::TorrentsNotSet, None));
-                } else {
-                    let mut vec_urls = vec![];
-                    let mut vec_paths = vec![];
-
-                    for item in t.iter().map(|l| l.get_inner()) {
-                        match item {
-                            crate::api_fns::torrents::torrents::TorrentInner::Url(url) => {
-                                vec_urls.push(url)
-                            }
-                            crate::api_fns::torrents::torrents::TorrentInner::RawTorrent(path) => {
-                                vec_paths.push(path)
-                            }
-                        }
-                    }
-
-                    (SepVec::new(vec_urls, "".to_string()), vec_paths)
-                }
-            }
-            None => {
-                return Err(Error::build(ErrorType::TorrentsNotSet, None))
-            }
+        let torrents = match self.torrents {
+            Some(t) if !t.is_empty() => t.iter().map(Torrent::get_inner).collect(),
+            _ => return Err(Error::build(ErrorType::TorrentsNotSet, None)),
         };
 
         let tags = self.tags.and_then(|v| Some(SepVec::new(v, ',')));
@@ -236,8 +223,7 @@ impl TorrentAddDescriptorBuilder {
         };
 
         Ok(TorrentAddDescriptor {
-            urls,
-            paths,
+            torrents,
             savepath: self.savepath,
             cookie: self.cookie,
             category: self.category,
@@ -257,127 +243,251 @@ impl TorrentAddDescriptorBuilder {
     }
 }
 
+/// ## Info
+/// what happened to one of the torrents given to [`QbitApi::torrents_add_torrent`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AddTorrentOutcome {
+    /// the torrent was added.
+    Added(TorrentHash),
+    /// the torrent was already there, so it wasn't added again.
+    AlreadyPresent(TorrentHash),
+    /// qbittorrent refused the torrent, for the reason given.
+    Rejected(String),
+    /// qbittorrent accepted the torrent, but it isn't in the torrent list yet:
+    /// it's still downloading the `.torrent` file of a url, or still adding the torrent.
+    /// the hash is known for magnet links and `.torrent` files, but not for other urls.
+    Pending(Option<TorrentHash>),
+}
+
+impl AddTorrentOutcome {
+    /// ## Usage
+    /// the hash of the torrent, if it's known.
+    pub fn torrent_hash(&self) -> Option<&TorrentHash> {
+        match self {
+            Self::Added(hash) | Self::AlreadyPresent(hash) | Self::Pending(Some(hash)) => Some(hash),
+            Self::Rejected(_) | Self::Pending(None) => None,
+        }
+    }
+
+    /// ## Usage
+    /// whether the torrent is now on the server: added, already present, or about to be.
+    pub fn is_accepted(&self) -> bool {
+        !matches!(self, Self::Rejected(_))
+    }
+}
+
 impl QbitApi {
     ///## Usage
-    /// adds one (or more) torrents.
-    pub async fn torrents_add_torrent(&mut self, descriptor: impl Borrow<TorrentAddDescriptor>) -> Result<(), Error> {
+    /// adds one (or more) torrents, returning an [`AddTorrentOutcome`] for each of them, in the same order.
+    ///
+    /// ## Info
+    /// the hashes come from the response of qbittorrent when it lists the added torrents,
+    /// or from parsing the `.torrent` files and magnet links, then looking them up in the torrent list.
+    ///
+    /// ## Example
+    /// ```no_run
+    /// # use qbittorrent_rust::{core::api::QbitApi, AddTorrentOutcome, TorrentAddDescriptor, TorrentHashesDesc};
+    /// # async fn example(api: QbitApi, descriptor: TorrentAddDescriptor) -> Result<(), qbittorrent_rust::Error> {
+    /// let outcomes = api.torrents_add_torrent(&descriptor).await?;
+    /// let hashes = outcomes.iter().filter_map(AddTorrentOutcome::torrent_hash).cloned().collect();
+    /// api.torrents_add_tags(TorrentHashesDesc::Hashes(hashes), vec!["linux"]).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn torrents_add_torrent(&self, descriptor: impl Borrow<TorrentAddDescriptor>) -> Result<Vec<AddTorrentOutcome>, Error> {
         let descriptor = descriptor.borrow();
+        let paused_field = if self.supports(ApiVersion::V2_11_0).await { "stopped" } else { "paused" };
 
-        match (
-            descriptor.paths.is_empty(),
-            descriptor.urls.inner_vec().is_empty(),
-        ) {
-            (true, true) => panic!(),
-            (true, false) => {
-                let mut form_urls = reqwest::multipart::Form::new();
-
-                form_urls = form_urls.text("urls", descriptor.urls.to_string());
-
-                form_urls = thing(form_urls, descriptor.clone());
-
-                let response_urls = self
-                    .reqwest_client
-                    .post(format!("{}/api/v2/torrents/add", self.authority))
-                    .multipart(form_urls)
-                    .header(header::COOKIE, format!("SID={}", self.get_cookie().await?))
-                    .send()
-                    .await
-                    .map_err(|e| {
-                        Error::build(ErrorType::ReqwestError(Box::new(e)), None)
-                    })?;
-
-                if response_urls.status().is_success() {
-                    return Ok(());
-                } else {
-                    return Err(Error::build(ErrorType::MiscNetError(code!(response_urls).unwrap()), code!(response_urls)));
+        if descriptor.torrents.is_empty() {
+            return Err(Error::build(ErrorType::TorrentsNotSet, None));
+        }
+
+        // urls and files are sent separately, so each torrent remembers which request it's part of.
+        let mut urls = Vec::new();
+        let mut files = Vec::new();
+        let mut inputs = Vec::new();
+
+        for torrent in &descriptor.torrents {
+            match torrent {
+                TorrentInner::Url(url) => {
+                    let expected = url.parse::<MagnetLink>().ok().map(|magnet| magnet.torrent_hash());
+                    inputs.push((AddBatch::Urls, expected));
+                    urls.push(url.clone());
                 }
-            }
-            (false, true) => {
-                let form = torrents_part(&descriptor).await?;
-
-                let response_torrents = self
-                    .reqwest_client
-                    .post(format!("{}/api/v2/torrents/add", self.authority))
-                    .multipart(form)
-                    .header(header::COOKIE, format!("SID={}", self.get_cookie().await?))
-                    .send()
-                    .await
-                    .map_err(|e| {
-                        Error::build(ErrorType::ReqwestError(Box::new(e)), None)
-                    })?;
-
-                if response_torrents.status().is_success() {
-                    return Ok(());
-                } else {
-                    return Err(Error::build(ErrorType::MiscNetError(code!(response_torrents).unwrap()), code!(response_torrents)));
+                TorrentInner::RawTorrent(path) => {
+                    let data = read_torrent_file(path).await?;
+                    let expected = Metainfo::from_bytes(data.as_slice()).ok().map(|metainfo| metainfo.torrent_hash());
+                    inputs.push((AddBatch::Files, expected));
+                    files.push(data);
+                }
+                TorrentInner::Metainfo(metainfo) => {
+                    inputs.push((AddBatch::Files, Some(metainfo.torrent_hash())));
+                    files.push(metainfo.as_bytes().to_vec());
                 }
             }
+        }
+
+        let expected_hashes: Vec<String> = inputs.iter().filter_map(|(_, hash)| hash.as_ref()).map(|hash| hash.hash.clone()).collect();
+        let before = self.torrents_lookup(&expected_hashes).await?;
+
+        let url = format!("{}/api/v2/torrents/add", self.authority);
+        let send_urls = async {
+            if urls.is_empty() {
+                return Ok(AddResponse::default());
+            }
+            let response = self
+                .send_non_idempotent(|| self.reqwest_client.post(&url).multipart(urls_part(descriptor, &urls, paused_field)))
+                .await?;
+            AddResponse::read(response).await
+        };
+        let send_files = async {
+            if files.is_empty() {
+                return Ok(AddResponse::default());
+            }
+            let response = self
+                .send_non_idempotent(|| self.reqwest_client.post(&url).multipart(torrents_part(descriptor, &files, paused_field)))
+                .await?;
+            AddResponse::read(response).await
+        };
+
+        let (urls_response, files_response) = tokio::join!(send_urls, send_files);
+        let (urls_response, files_response) = (urls_response?, files_response?);
+        let response = |batch: &AddBatch| match batch {
+            AddBatch::Urls => &urls_response,
+            AddBatch::Files => &files_response,
+        };
+
+        // the ids qbittorrent listed that don't belong to a known torrent, for the torrents whose hash is unknown.
+        let mut unmatched_ids: HashMap<AddBatch, Vec<String>> = HashMap::new();
+        for batch in [AddBatch::Urls, AddBatch::Files] {
+            let ids = response(&batch).added_ids.iter().flatten();
+            unmatched_ids.insert(batch, ids.filter(|id| !expected_hashes.contains(id)).cloned().collect());
+        }
+        let mut guessed = Vec::new();
+        for (index, (batch, expected)) in inputs.iter().enumerate() {
+            let unknown = inputs.iter().filter(|(b, hash)| b == batch && hash.is_none()).count();
+            if expected.is_none() && unknown == 1 && unmatched_ids[batch].len() == 1 {
+                guessed.push((index, unmatched_ids[batch][0].clone()));
+            }
+        }
 
-            (false, false) => {
-                // ---------- TORRENT FILES ----------
-                let form_torrents = torrents_part(&descriptor).await?;
+        let to_find: Vec<String> = expected_hashes
+            .iter()
+            .filter(|hash| !before.contains_key(*hash))
+            .chain(guessed.iter().map(|(_, id)| id))
+            .cloned()
+            .collect();
+        let after = self.torrents_lookup(&to_find).await?;
+
+        let outcomes = inputs
+            .into_iter()
+            .enumerate()
+            .map(|(index, (batch, expected))| {
+                let response = response(&batch);
+                let expected = expected.or_else(|| {
+                    let (_, id) = guessed.iter().find(|(i, _)| *i == index)?;
+                    Some(TorrentHash::new(id.clone(), id.clone()))
+                });
+
+                match expected {
+                    Some(hash) if before.contains_key(&hash.hash) => AddTorrentOutcome::AlreadyPresent(before[&hash.hash].clone()),
+                    Some(hash) if after.contains_key(&hash.hash) => AddTorrentOutcome::Added(after[&hash.hash].clone()),
+                    Some(hash) if response.confirms(&hash.hash) => AddTorrentOutcome::Added(hash),
+                    expected => match &response.failure {
+                        Some(reason) => AddTorrentOutcome::Rejected(reason.clone()),
+                        None => AddTorrentOutcome::Pending(expected),
+                    },
+                }
+            })
+            .collect();
 
-                let built_torrents = self
-                    .reqwest_client
-                    .post(format!("{}/api/v2/torrents/add", self.authority))
-                    .multipart(form_torrents)
-                    .header(header::COOKIE, format!("SID={}", self.get_cookie().await?));
+        Ok(outcomes)
+    }
 
-                // ---------- TORRENT FILES ----------
+    /// the torrents with these hashes that are on the server.
+    async fn torrents_lookup(&self, hashes: &[String]) -> Result<HashMap<String, TorrentHash>, Error> {
+        if hashes.is_empty() {
+            return Ok(HashMap::new());
+        }
 
-                // ---------- URLS ----------
-                let mut form_urls = reqwest::multipart::Form::new();
+        let config = TorrentListGetConfig::builder().hashes(hashes.to_vec()).build();
+        let torrents = self.torrents_get_torrent_list_typed(config).await?;
 
-                form_urls = form_urls.text("urls", descriptor.urls.to_string());
-                form_urls = thing(form_urls, descriptor.clone());
+        Ok(torrents.iter().map(|torrent| (torrent.hash.clone(), TorrentHash::from(torrent))).collect())
+    }
+}
 
-                let built_urls = self
-                    .reqwest_client
-                    .post(format!("{}/api/v2/torrents/add", self.authority))
-                    .multipart(form_urls)
-                    .header(header::COOKIE, format!("SID={}", self.get_cookie().await?));
+/// the two requests `torrents_add_torrent` may send: one with the urls, one with the files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum AddBatch {
+    Urls,
+    Files,
+}
 
-                // ---------- URLS ----------
+/// what qbittorrent answered to one `/torrents/add` request.
+#[derive(Debug, Default)]
+struct AddResponse {
+    /// why nothing was added, if nothing was.
+    failure: Option<String>,
+    /// the ids of the added torrents, from the JSON answer of newer qbittorrent versions.
+    added_ids: Option<Vec<String>>,
+}
 
-                let (response_torrents, response_urls) =
-                    tokio::join!(built_torrents.send(), built_urls.send());
+/// the JSON answer of `/torrents/add` on newer qbittorrent versions.
+#[derive(Debug, Deserialize)]
+struct AddTorrentsResult {
+    #[serde(default)]
+    success_count: u64,
+    #[serde(default)]
+    failure_count: u64,
+    #[serde(default)]
+    pending_count: u64,
+    #[serde(default)]
+    added_torrent_ids: Option<Vec<String>>,
+}
 
-                let mut thing = (false, false);
+impl AddResponse {
+    async fn read(response: reqwest::Response) -> Result<Self, Error> {
+        if response.status().as_u16() == 415 {
+            return Ok(Self {
+                failure: Some("the torrent file isn't valid.".to_string()),
+                added_ids: None,
+            });
+        }
+        if !response.status().is_success() {
+            return Err(Error::build(ErrorType::MiscNetError(code!(response).unwrap()), code!(response)));
+        }
 
-                if response_torrents
-                    .map_err(|e| {
-                        Error::build(ErrorType::ReqwestError(Box::new(e)), None)
-                    })?
-                    .status()
-                    .is_success()
-                {
-                    thing.0 = true;
-                }
+        let body = response
+            .text()
+            .await
+            .map_err(|e| Error::build(ErrorType::ReqwestError(Box::new(e)), None))?;
+
+        if let Ok(result) = serde_json::from_str::<AddTorrentsResult>(&body) {
+            let failed = result.success_count == 0 && result.pending_count == 0 && result.failure_count > 0;
+            return Ok(Self {
+                failure: failed.then(|| "qbittorrent couldn't add the torrent.".to_string()),
+                added_ids: result.added_torrent_ids.map(|ids| ids.into_iter().map(|id| id.to_lowercase()).collect()),
+            });
+        }
 
-                if response_urls
-                    .map_err(|e| {
-                        Error::build(ErrorType::ReqwestError(Box::new(e)), None)
-                    })?
-                    .status()
-                    .is_success()
-                {
-                    thing.1 = true
-                }
+        // older versions answer `Ok.` if at least one torrent was added, `Fails.` otherwise.
+        Ok(Self {
+            failure: (body.trim() == "Fails.").then(|| "qbittorrent couldn't add the torrent.".to_string()),
+            added_ids: None,
+        })
+    }
 
-                match thing {
-                        (true, true) => return Ok(()),
-                        (true, false) => return Err(Error::build(ErrorType::MiscError("something went wrong while adding urls.".to_string()), None)),
-                        (false, true) => return Err(Error::build(ErrorType::MiscError("something went wrong while adding torrent files.".to_string()), None)),
-                        (false, false) => return Err(Error::build(ErrorType::MiscError("wow, you really messed up. both torrents and urls failed.".to_string()), None)),
-                    }
-            }
-        };
+    /// whether qbittorrent listed this hash as added.
+    fn confirms(&self, hash: &str) -> bool {
+        self.added_ids.iter().flatten().any(|id| id == hash)
     }
 }
 
 fn thing(
     mut form: reqwest::multipart::Form,
     descriptor: TorrentAddDescriptor,
+    paused_field: &'static str,
 ) -> reqwest::multipart::Form {
     if let Some(savepath) = descriptor.savepath {
         form = form.text("savepath", savepath);
@@ -400,7 +510,7 @@ fn thing(
     }
 
     if let Some(paused) = descriptor.paused {
-        form = form.text("paused", paused.to_string());
+        form = form.text(paused_field, paused.to_string());
     }
 
     if let Some(root_folder) = descriptor.root_folder {
@@ -442,28 +552,39 @@ fn thing(
     form
 }
 
-async fn torrents_part(
-    descriptor: &TorrentAddDescriptor,
-) -> Result<reqwest::multipart::Form, Error> {
-    let mut form_torrents = reqwest::multipart::Form::new();
-    for path in descriptor.paths.clone() {
-        let mut file = File::open(path)
-            .await
-            .map_err(|_| Error::build(ErrorType::TorrentFilePathError, None))?;
+/// `paused_field` is `stopped` on qbittorrent 5, `paused` before.
+fn urls_part(descriptor: &TorrentAddDescriptor, urls: &[String], paused_field: &'static str) -> reqwest::multipart::Form {
+    let form_urls = reqwest::multipart::Form::new().text("urls", SepVec::new(urls.to_vec(), "\n".to_string()).to_string());
 
-        let mut buffer = Vec::new();
+    thing(form_urls, descriptor.clone(), paused_field)
+}
 
-        file.read_to_end(&mut buffer)
-            .await
-            .map_err(|_| Error::build(ErrorType::TorrentFilePathError, None))?;
+async fn read_torrent_file(path: &str) -> Result<Vec<u8>, Error> {
+    let mut file = File::open(path)
+        .await
+        .map_err(|_| Error::build(ErrorType::TorrentFilePathError, None))?;
+
+    let mut buffer = Vec::new();
+
+    file.read_to_end(&mut buffer)
+        .await
+        .map_err(|_| Error::build(ErrorType::TorrentFilePathError, None))?;
+
+    Ok(buffer)
+}
 
+/// the multipart form is rebuilt for every attempt, since a sent one can't be reused.
+fn torrents_part(descriptor: &TorrentAddDescriptor, files: &[Vec<u8>], paused_field: &'static str) -> reqwest::multipart::Form {
+    let mut form_torrents = reqwest::multipart::Form::new();
+    for buffer in files {
         // part 4 the multipart form
-        let file_part = reqwest::multipart::Part::bytes(buffer)
+        let file_part = reqwest::multipart::Part::bytes(buffer.clone())
             .file_name("torrent_file.torrent")
             .mime_str("application/x-bittorrent")
             .unwrap();
 
         form_torrents = form_torrents.part("torrents", file_part);
     }
-    Ok(form_torrents)
-}
\ No newline at end of file
+
+    thing(form_torrents, descriptor.clone(), paused_field)
+}
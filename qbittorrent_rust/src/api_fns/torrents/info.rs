@@ -1,12 +1,14 @@
-use std::borrow::Borrow;
+use std::{borrow::Borrow, collections::HashMap, time::Duration};
 
-use crate::{core::api::QbitApi, error_handling::error_type::ErrorType, misc::sep_vec::SepVec, Error};
+use crate::{core::{api::QbitApi, version::ApiVersion}, error_handling::error_type::ErrorType, misc::{deserializers, sep_vec::SepVec}, Error};
+use chrono::{DateTime, Utc};
 use proc_macros_qbittorrent_rust::Builder;
+use serde::Deserialize;
 use serde_json::Value;
 
 /// ## Info
 /// represents a torrent hash.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TorrentHash {
     pub name: String,
     pub hash: String,
@@ -19,22 +21,6 @@ impl TorrentHash {
     pub fn new<S:Into<String>, T:Into<String>>(name: S, hash: T) -> Self {
         TorrentHash { name: name.into(), hash: hash.into() }
     }
-
-    pub(crate) fn new_multiple<S, T, U, V>(names: U, hashes: V) -> Vec<Self> 
-    where
-        T: Into<String>,
-        U: IntoIterator<Item = T>, 
-        S: Into<String>,
-        V: IntoIterator<Item = S>
-    {
-        let binding = names.into_iter().map(|x| Into::<String>::into(x)).collect::<Vec<String>>();
-        let iter_names = binding.iter();
-        let binding = hashes.into_iter().map(|x| Into::<String>::into(x)).collect::<Vec<String>>();
-        let iter_hashes = binding.iter();
-
-        let torrent_hashes = iter_names.zip(iter_hashes).map(|(l, d)| Self::new(l, d)).collect::<Vec<Self>>();
-        torrent_hashes
-    }
 }
 
 /// ## Info
@@ -68,6 +54,9 @@ impl Category {
 
 /// ## Info
 /// represents the state of a torrent.
+///
+/// `Paused` and `Stopped`, like `Resumed` and `Running`, are the same filter:
+/// qbittorrent 5 renamed them, and the one the server understands is sent.
 #[derive(Debug)]
 pub enum State {
     All,
@@ -75,9 +64,11 @@ pub enum State {
     Seeding,
     Completed,
     Paused,
+    Stopped,
     Active,
     Inactive,
     Resumed,
+    Running,
     Stalled,
     StalledUploading,
     StalledDownloading,
@@ -91,9 +82,11 @@ impl State {
             State::Seeding => String::from("seeding"),
             State::Completed => String::from("completed"),
             State::Paused => String::from("paused"),
+            State::Stopped => String::from("stopped"),
             State::Active => String::from("active"),
             State::Inactive => String::from("inactive"),
             State::Resumed => String::from("resumed"),
+            State::Running => String::from("running"),
             State::Stalled => String::from("stalled"),
             State::StalledUploading => String::from("stalled_uploading"),
             State::StalledDownloading => String::from("stalled_downloading"),
@@ -120,12 +113,16 @@ impl State {
             Ok(State::Completed)
         } else if string == "paused".to_string() {
             Ok(State::Paused)
+        } else if string == "stopped" {
+            Ok(State::Stopped)
         } else if string == "active".to_string() {
             Ok(State::Active)
         } else if string == "inactive".to_string() {
             Ok(State::Inactive)
         } else if string == "resumed".to_string() {
             Ok(State::Resumed)
+        } else if string == "running" {
+            Ok(State::Running)
         } else if string == "stalled".to_string() {
             Ok(State::Stalled)
         } else if string == "stalled_uploading".to_string() {
@@ -138,6 +135,141 @@ impl State {
             Err(Error::build(ErrorType::ParameterNotExpected, None))            
         }
     }
+
+    /// translates a filter string to the name the server uses: `stopped` and `running` since the WebAPI 2.11,
+    /// `paused` and `resumed` before.
+    pub(crate) fn filter_for_server(filter: String, stopped_names: bool) -> String {
+        match (filter.as_str(), stopped_names) {
+            ("paused", true) => String::from("stopped"),
+            ("resumed", true) => String::from("running"),
+            ("stopped", false) => String::from("paused"),
+            ("running", false) => String::from("resumed"),
+            _ => filter,
+        }
+    }
+}
+
+/// ## Info
+/// represents the actual state a torrent is in, as reported by the `state` field of a torrent.
+/// unlike [`State`], which is only used to filter torrent lists, this describes one single torrent.
+///
+/// ## Variants
+/// - `PausedUploading` and `PausedDownloading` are reported by qbittorrent 4.x,
+///   `StoppedUploading` and `StoppedDownloading` by qbittorrent 5.x; `is_paused` and `is_stopped` cover both.
+/// - `Unknown` is used for every state string the library doesn't recognize.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Deserialize)]
+pub enum TorrentState {
+    #[serde(rename = "error")]
+    Error,
+    #[serde(rename = "missingFiles")]
+    MissingFiles,
+    #[serde(rename = "uploading")]
+    Uploading,
+    #[serde(rename = "pausedUP")]
+    PausedUploading,
+    #[serde(rename = "stoppedUP")]
+    StoppedUploading,
+    #[serde(rename = "queuedUP")]
+    QueuedUploading,
+    #[serde(rename = "stalledUP")]
+    StalledUploading,
+    #[serde(rename = "checkingUP")]
+    CheckingUploading,
+    #[serde(rename = "forcedUP")]
+    ForcedUploading,
+    #[serde(rename = "allocating")]
+    Allocating,
+    #[serde(rename = "downloading")]
+    Downloading,
+    #[serde(rename = "metaDL")]
+    MetadataDownloading,
+    #[serde(rename = "pausedDL")]
+    PausedDownloading,
+    #[serde(rename = "stoppedDL")]
+    StoppedDownloading,
+    #[serde(rename = "queuedDL")]
+    QueuedDownloading,
+    #[serde(rename = "stalledDL")]
+    StalledDownloading,
+    #[serde(rename = "checkingDL")]
+    CheckingDownloading,
+    #[serde(rename = "forcedDL")]
+    ForcedDownloading,
+    #[serde(rename = "checkingResumeData")]
+    CheckingResumeData,
+    #[serde(rename = "moving")]
+    Moving,
+    #[default]
+    #[serde(other)]
+    Unknown,
+}
+impl TorrentState {
+    /// ## Usage
+    /// returns the string qbittorrent uses for this state. eg: `"pausedUP"`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TorrentState::Error => "error",
+            TorrentState::MissingFiles => "missingFiles",
+            TorrentState::Uploading => "uploading",
+            TorrentState::PausedUploading => "pausedUP",
+            TorrentState::StoppedUploading => "stoppedUP",
+            TorrentState::QueuedUploading => "queuedUP",
+            TorrentState::StalledUploading => "stalledUP",
+            TorrentState::CheckingUploading => "checkingUP",
+            TorrentState::ForcedUploading => "forcedUP",
+            TorrentState::Allocating => "allocating",
+            TorrentState::Downloading => "downloading",
+            TorrentState::MetadataDownloading => "metaDL",
+            TorrentState::PausedDownloading => "pausedDL",
+            TorrentState::StoppedDownloading => "stoppedDL",
+            TorrentState::QueuedDownloading => "queuedDL",
+            TorrentState::StalledDownloading => "stalledDL",
+            TorrentState::CheckingDownloading => "checkingDL",
+            TorrentState::ForcedDownloading => "forcedDL",
+            TorrentState::CheckingResumeData => "checkingResumeData",
+            TorrentState::Moving => "moving",
+            TorrentState::Unknown => "unknown",
+        }
+    }
+
+    /// ## Usage
+    /// returns `true` if the torrent finished downloading, whether it's still seeding or not.
+    pub fn is_completed(&self) -> bool {
+        matches!(
+            self,
+            TorrentState::Uploading
+                | TorrentState::PausedUploading
+                | TorrentState::StoppedUploading
+                | TorrentState::QueuedUploading
+                | TorrentState::StalledUploading
+                | TorrentState::CheckingUploading
+                | TorrentState::ForcedUploading
+        )
+    }
+
+    /// ## Usage
+    /// returns `true` if the torrent is paused (or stopped, as qbittorrent 5 calls it).
+    pub fn is_paused(&self) -> bool {
+        matches!(
+            self,
+            TorrentState::PausedUploading
+                | TorrentState::PausedDownloading
+                | TorrentState::StoppedUploading
+                | TorrentState::StoppedDownloading
+        )
+    }
+
+    /// ## Usage
+    /// alias of `is_paused`.
+    pub fn is_stopped(&self) -> bool {
+        self.is_paused()
+    }
+
+    /// ## Usage
+    /// returns `true` if the torrent is in an errored state.
+    pub fn is_errored(&self) -> bool {
+        matches!(self, TorrentState::Error | TorrentState::MissingFiles)
+    }
 }
 
 /// ## Info
@@ -231,33 +363,172 @@ impl TorrentListGetConfigBuilder {
     }
 }
 
+/// ## Info
+/// represents a torrent, as returned by [`QbitApi::torrents_get_torrent_list_typed()`].
+/// every field that isn't listed here (eg: the ones added by newer versions of qbittorrent) is kept in `extra`.
+///
+/// ## Fields
+/// | Field                | Type                    | Description                                                                 |
+/// |----------------------|-------------------------|-----------------------------------------------------------------------------|
+/// | `hash`               | `String`                | Torrent hash                                                                |
+/// | `infohash_v1`        | `String`                | Torrent v1 info hash (empty for v2-only torrents)                           |
+/// | `infohash_v2`        | `String`                | Torrent v2 info hash (empty for v1-only torrents)                           |
+/// | `name`               | `String`                | Torrent name                                                                |
+/// | `added_on`           | `DateTime<Utc>`         | Time when the torrent was added to the client                               |
+/// | `amount_left`        | `Integer`               | Amount of data left to download (bytes)                                     |
+/// | `auto_tmm`           | `Bool`                  | Whether this torrent is managed by Automatic Torrent Management             |
+/// | `availability`       | `Float`                 | Percentage of file pieces currently available                               |
+/// | `category`           | `String`                | Category of the torrent (empty if there's none)                             |
+/// | `completed`          | `Integer`               | Amount of transfer data completed (bytes)                                   |
+/// | `completion_on`      | `Option<DateTime<Utc>>` | Time when the torrent completed                                             |
+/// | `content_path`       | `String`                | Absolute path of torrent content                                            |
+/// | `dl_limit`           | `Integer`               | Torrent download speed limit (bytes/s). `-1` if unlimited                   |
+/// | `dlspeed`            | `Integer`               | Torrent download speed (bytes/s)                                            |
+/// | `download_path`      | `String`                | Path where the incomplete torrent is downloaded                             |
+/// | `downloaded`         | `Integer`               | Amount of data downloaded                                                   |
+/// | `downloaded_session` | `Integer`               | Amount of data downloaded this session                                      |
+/// | `eta`                | `Option<Duration>`      | Torrent ETA, [`None`] if infinite                                           |
+/// | `f_l_piece_prio`     | `Bool`                  | Whether first and last piece are prioritized                                |
+/// | `force_start`        | `Bool`                  | Whether force start is enabled for this torrent                             |
+/// | `is_private`         | `Bool`                  | Whether the torrent is private                                              |
+/// | `last_activity`      | `Option<DateTime<Utc>>` | Last time when a chunk was downloaded/uploaded                              |
+/// | `magnet_uri`         | `String`                | Magnet URI corresponding to this torrent                                    |
+/// | `max_ratio`          | `Float`                 | Maximum share ratio until torrent is stopped from seeding/uploading         |
+/// | `max_seeding_time`   | `Integer`               | Maximum seeding time (minutes) until torrent is stopped from seeding        |
+/// | `num_complete`       | `Integer`               | Number of seeds in the swarm                                                |
+/// | `num_incomplete`     | `Integer`               | Number of leechers in the swarm                                             |
+/// | `num_leechs`         | `Integer`               | Number of leechers connected to                                             |
+/// | `num_seeds`          | `Integer`               | Number of seeds connected to                                                |
+/// | `priority`           | `Integer`               | Torrent priority. `0` if queuing is disabled or the torrent is seeding      |
+/// | `progress`           | `Float`                 | Torrent progress, from `0.0` to `1.0`                                       |
+/// | `ratio`              | `Float`                 | Torrent share ratio                                                         |
+/// | `ratio_limit`        | `Float`                 | Share ratio limit. `-2` means the global limit is used, `-1` no limit       |
+/// | `reannounce`         | `Duration`              | Time until the next tracker reannounce                                      |
+/// | `save_path`          | `String`                | Path where this torrent's data is stored                                    |
+/// | `seeding_time`       | `Duration`              | Time the torrent has been seeding for                                       |
+/// | `seeding_time_limit` | `Integer`               | Seeding time limit (minutes). `-2` means the global limit is used, `-1` no limit |
+/// | `seen_complete`      | `Option<DateTime<Utc>>` | Time when this torrent was last seen complete                               |
+/// | `seq_dl`             | `Bool`                  | Whether sequential download is enabled                                      |
+/// | `size`               | `Integer`               | Total size of the files selected for download (bytes)                       |
+/// | `state`              | `TorrentState`          | Torrent state                                                               |
+/// | `super_seeding`      | `Bool`                  | Whether super seeding is enabled                                            |
+/// | `tags`               | `Vec<String>`           | Tags of the torrent                                                         |
+/// | `time_active`        | `Duration`              | Total active time                                                           |
+/// | `total_size`         | `Integer`               | Total size of all the files in this torrent, including unselected ones (bytes) |
+/// | `tracker`            | `String`                | The first tracker with working status (empty if there's none)              |
+/// | `trackers_count`     | `Integer`               | Number of trackers                                                          |
+/// | `up_limit`           | `Integer`               | Torrent upload speed limit (bytes/s). `-1` if unlimited                     |
+/// | `uploaded`           | `Integer`               | Amount of data uploaded                                                     |
+/// | `uploaded_session`   | `Integer`               | Amount of data uploaded this session                                        |
+/// | `upspeed`            | `Integer`               | Torrent upload speed (bytes/s)                                              |
+/// | `extra`              | `HashMap<String, Value>`| Every other field returned by the server                                    |
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct TorrentInfo {
+    pub hash: String,
+    pub infohash_v1: String,
+    pub infohash_v2: String,
+    pub name: String,
+    #[serde(deserialize_with = "deserializers::timestamp")]
+    pub added_on: DateTime<Utc>,
+    pub amount_left: i64,
+    pub auto_tmm: bool,
+    pub availability: f64,
+    pub category: String,
+    pub completed: i64,
+    #[serde(deserialize_with = "deserializers::optional_timestamp")]
+    pub completion_on: Option<DateTime<Utc>>,
+    pub content_path: String,
+    pub dl_limit: i64,
+    pub dlspeed: i64,
+    pub download_path: String,
+    pub downloaded: i64,
+    pub downloaded_session: i64,
+    #[serde(deserialize_with = "deserializers::eta")]
+    pub eta: Option<Duration>,
+    pub f_l_piece_prio: bool,
+    pub force_start: bool,
+    #[serde(rename = "isPrivate")]
+    pub is_private: bool,
+    #[serde(deserialize_with = "deserializers::optional_timestamp")]
+    pub last_activity: Option<DateTime<Utc>>,
+    pub magnet_uri: String,
+    pub max_ratio: f64,
+    pub max_seeding_time: i64,
+    pub num_complete: i64,
+    pub num_incomplete: i64,
+    pub num_leechs: i64,
+    pub num_seeds: i64,
+    pub priority: i64,
+    pub progress: f64,
+    pub ratio: f64,
+    pub ratio_limit: f64,
+    #[serde(deserialize_with = "deserializers::seconds")]
+    pub reannounce: Duration,
+    pub save_path: String,
+    #[serde(deserialize_with = "deserializers::seconds")]
+    pub seeding_time: Duration,
+    pub seeding_time_limit: i64,
+    #[serde(deserialize_with = "deserializers::optional_timestamp")]
+    pub seen_complete: Option<DateTime<Utc>>,
+    pub seq_dl: bool,
+    pub size: i64,
+    pub state: TorrentState,
+    pub super_seeding: bool,
+    #[serde(deserialize_with = "deserializers::comma_separated")]
+    pub tags: Vec<String>,
+    #[serde(deserialize_with = "deserializers::seconds")]
+    pub time_active: Duration,
+    pub total_size: i64,
+    pub tracker: String,
+    pub trackers_count: i64,
+    pub up_limit: i64,
+    pub uploaded: i64,
+    pub uploaded_session: i64,
+    pub upspeed: i64,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl From<&TorrentInfo> for TorrentHash {
+    fn from(value: &TorrentInfo) -> Self {
+        TorrentHash::new(value.name.clone(), value.hash.clone())
+    }
+}
+
 impl QbitApi {
     /// ## Usage
     /// returns a [`Vec`] containing multiple [`TorrentHash`]es, each corresponding to a torrent.
-    pub async fn torrents_get_hashes(&mut self) -> Result<Vec<TorrentHash>, Error> {
-        let jsons = self.torrents_get_torrent_list(TorrentListGetConfig::new()).await?;
+    pub async fn torrents_get_hashes(&self) -> Result<Vec<TorrentHash>, Error> {
+        let torrents = self.torrents_get_torrent_list_typed(TorrentListGetConfig::new()).await?;
+
+        Ok(torrents.iter().map(TorrentHash::from).collect())
+    }
 
-        let names: Vec<String> = jsons.as_array().unwrap().iter().map(|k| k.clone()["name"].take().as_str().unwrap().to_string()).collect();
-        let hashes: Vec<String> = jsons.as_array().unwrap().iter().map(|k| k.clone()["hash"].take().as_str().unwrap().to_string()).collect();
-        Ok(TorrentHash::new_multiple(names, hashes))
+    /// ## Usage
+    /// gets the torrent list as a [`Vec`] of [`TorrentInfo`]s.
+    pub async fn torrents_get_torrent_list_typed(&self, config: impl Borrow<TorrentListGetConfig>) -> Result<Vec<TorrentInfo>, Error> {
+        serde_json::from_str(Self::torrents_get_torrent_list_raw(self, config).await?.as_str()).map_err(|e| Error::build(ErrorType::JsonSerdeError(Box::new(e)), None))
     }
 
     /// ## Usage
     /// gets the torrent list as a [`serde_json::value::Value`]
-    pub async fn torrents_get_torrent_list(&mut self, config: impl Borrow<TorrentListGetConfig>) -> Result<Value, Error> {
+    pub async fn torrents_get_torrent_list(&self, config: impl Borrow<TorrentListGetConfig>) -> Result<Value, Error> {
         serde_json::from_str(Self::torrents_get_torrent_list_raw(self, config).await?.as_str()).map_err(|e| Error::build(ErrorType::JsonSerdeError(Box::new(e)), None))
     }
 
     /// ## Usage
     /// gets the list of torrents as a [`String`]. 
-    pub async fn torrents_get_torrent_list_raw(&mut self, config: impl Borrow<TorrentListGetConfig>) -> Result<String, Error> {
+    pub async fn torrents_get_torrent_list_raw(&self, config: impl Borrow<TorrentListGetConfig>) -> Result<String, Error> {
         let config: TorrentListGetConfig = config.borrow().clone();
 
         let vec = config.hashes.and_then(|x| Some(x.to_string()));
+        let stopped_names = self.supports(ApiVersion::V2_11_0).await;
+        let filter = config.filter.map(|filter| State::filter_for_server(filter, stopped_names));
         
         let url = crate::url!(
             "/torrents/info",
-            ("filter", config.filter),
+            ("filter", filter),
             ("category", config.category),
             ("tag", config.tag),
             ("sort", config.sort),
@@ -269,4 +540,19 @@ impl QbitApi {
 
         Self::make_request(self, url, "get_torrent_list").await
     }
+
+    /// ## Usage
+    /// gets how many torrents there are, without listing them.
+    ///
+    /// ## Errors
+    /// requires the WebAPI 2.9.3 (qbittorrent 4.6); older servers return [`ErrorType::UnsupportedByServer`].
+    pub async fn torrents_count(&self) -> Result<u64, Error> {
+        self.require_version(ApiVersion::V2_9_3).await?;
+
+        let count = self.make_request("/torrents/count", "torrents_count").await?;
+
+        count.trim().parse().map_err(|_| {
+            Error::build(ErrorType::MiscError(format!("unexpected torrent count: {}", count)), None)
+        })
+    }
 }
\ No newline at end of file
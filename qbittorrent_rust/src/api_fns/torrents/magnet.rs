@@ -0,0 +1,274 @@
+use std::{
+    fmt::{Display, Formatter},
+    ops::RangeInclusive,
+    str::FromStr,
+};
+
+use url::{form_urlencoded::byte_serialize, Url};
+
+use crate::error_handling::{error_type::ErrorType, errors::Error};
+
+use super::{info::TorrentHash, metainfo::Metainfo};
+
+/// ## Info
+/// a magnet link (`magnet:?xt=urn:btih:...`), parsed or built locally.
+///
+/// it knows the hash of the torrent before qbittorrent resolves its metadata,
+/// so the torrent can be found in the torrent list right after adding it.
+///
+/// ## Example
+/// ```no_run
+/// # use qbittorrent_rust::{core::api::QbitApi, MagnetLink, Torrent, TorrentAddDescriptor};
+/// # async fn example(api: QbitApi) -> Result<(), qbittorrent_rust::Error> {
+/// let magnet: MagnetLink = "magnet:?xt=urn:btih:dd8255ecdc7ca55fb0bbf81323d87062db1f6d1c&dn=Big+Buck+Bunny".parse()?;
+/// println!("{:?}: {}", magnet.display_name, magnet.hash());
+///
+/// api.torrents_add_torrent(TorrentAddDescriptor::new(vec![Torrent::from_magnet(magnet)])?).await?;
+///
+/// let mut magnet = MagnetLink::from_hash("dd8255ecdc7ca55fb0bbf81323d87062db1f6d1c")?;
+/// magnet.trackers.push("udp://tracker.example.org:1337/announce".to_string());
+/// println!("{}", magnet);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MagnetLink {
+    /// the v1 info-hash (`urn:btih:`), in lowercase hex.
+    pub info_hash_v1: Option<String>,
+    /// the v2 info-hash (`urn:btmh:`), the SHA-256 of the info dictionary in lowercase hex.
+    pub info_hash_v2: Option<String>,
+    /// the name to show until the metadata is resolved (`dn`).
+    pub display_name: Option<String>,
+    /// the tracker urls (`tr`).
+    pub trackers: Vec<String>,
+    /// the web seed urls (`ws`).
+    pub web_seeds: Vec<String>,
+    /// the size of the torrent in bytes (`xl`).
+    pub exact_length: Option<u64>,
+    /// the indexes of the files to download (`so`). empty means all of them.
+    pub select_only: Vec<RangeInclusive<usize>>,
+}
+
+/// the multihash prefix of a SHA-256 digest: the hash function code and the digest length.
+const SHA256_MULTIHASH_PREFIX: &str = "1220";
+
+impl MagnetLink {
+    /// ## Usage
+    /// creates a magnet link with only a hash, either:
+    /// - a v1 info-hash, as 40 hex characters or 32 base32 characters;
+    /// - a v2 info-hash, as 64 hex characters.
+    ///
+    /// ## Errors
+    /// - returns an [`Error`] with error type [`ErrorType::InvalidMagnetLink`] if the hash isn't one of those.
+    pub fn from_hash<S: AsRef<str>>(hash: S) -> Result<Self, Error> {
+        let hash = hash.as_ref();
+        let mut magnet = Self::empty();
+
+        if hash.len() == 64 {
+            magnet.info_hash_v2 = Some(parse_hex(hash, 32)?);
+        } else {
+            magnet.info_hash_v1 = Some(parse_btih(hash)?);
+        }
+
+        Ok(magnet)
+    }
+
+    fn empty() -> Self {
+        Self {
+            info_hash_v1: None,
+            info_hash_v2: None,
+            display_name: None,
+            trackers: Vec::new(),
+            web_seeds: Vec::new(),
+            exact_length: None,
+            select_only: Vec::new(),
+        }
+    }
+
+    /// ## Usage
+    /// the hash qbittorrent identifies the torrent with: the v1 info-hash,
+    /// or the v2 info-hash truncated to 40 characters if there is no v1 one.
+    pub fn hash(&self) -> String {
+        match (&self.info_hash_v1, &self.info_hash_v2) {
+            (Some(v1), _) => v1.clone(),
+            (None, Some(v2)) => v2[..40].to_string(),
+            (None, None) => unreachable!("a magnet link has at least one info-hash"),
+        }
+    }
+
+    /// ## Usage
+    /// a [`TorrentHash`] for this torrent, to use with the other methods once it's added.
+    /// until the metadata is resolved, qbittorrent names the torrent after the display name, or the hash if there's none.
+    pub fn torrent_hash(&self) -> TorrentHash {
+        let hash = self.hash();
+        TorrentHash::new(self.display_name.clone().unwrap_or_else(|| hash.clone()), hash)
+    }
+}
+
+impl FromStr for MagnetLink {
+    type Err = Error;
+
+    /// parses a magnet link. unknown parameters are ignored.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let url = Url::parse(s.trim()).map_err(|e| invalid(e.to_string()))?;
+        if url.scheme() != "magnet" {
+            return Err(invalid(format!("expected the magnet scheme, got {:?}", url.scheme())));
+        }
+
+        let mut magnet = Self::empty();
+
+        for (key, value) in url.query_pairs() {
+            // BEP 9 allows numbered parameters, like `tr.1` or `xt.2`.
+            let key = key.split('.').next().unwrap_or_default();
+
+            match key {
+                "xt" => {
+                    if let Some(hash) = value.strip_prefix("urn:btih:") {
+                        magnet.info_hash_v1 = Some(parse_btih(hash)?);
+                    } else if let Some(multihash) = value.strip_prefix("urn:btmh:") {
+                        let hash = multihash
+                            .strip_prefix(SHA256_MULTIHASH_PREFIX)
+                            .ok_or_else(|| invalid("only SHA-256 multihashes are supported".to_string()))?;
+                        magnet.info_hash_v2 = Some(parse_hex(hash, 32)?);
+                    }
+                }
+                "dn" => magnet.display_name = Some(value.into_owned()),
+                "tr" => magnet.trackers.push(value.into_owned()),
+                "ws" => magnet.web_seeds.push(value.into_owned()),
+                "xl" => {
+                    let length = value.parse().map_err(|_| invalid(format!("invalid length {:?}", value)))?;
+                    magnet.exact_length = Some(length);
+                }
+                "so" => magnet.select_only = parse_select_only(&value)?,
+                _ => {}
+            }
+        }
+
+        if magnet.info_hash_v1.is_none() && magnet.info_hash_v2.is_none() {
+            return Err(invalid("there is no BitTorrent info-hash".to_string()));
+        }
+
+        Ok(magnet)
+    }
+}
+
+impl Display for MagnetLink {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut params = Vec::new();
+
+        if let Some(hash) = &self.info_hash_v1 {
+            params.push(format!("xt=urn:btih:{}", hash));
+        }
+        if let Some(hash) = &self.info_hash_v2 {
+            params.push(format!("xt=urn:btmh:{}{}", SHA256_MULTIHASH_PREFIX, hash));
+        }
+        if let Some(name) = &self.display_name {
+            params.push(format!("dn={}", encode(name)));
+        }
+        if let Some(length) = self.exact_length {
+            params.push(format!("xl={}", length));
+        }
+        params.extend(self.trackers.iter().map(|tracker| format!("tr={}", encode(tracker))));
+        params.extend(self.web_seeds.iter().map(|seed| format!("ws={}", encode(seed))));
+        if !self.select_only.is_empty() {
+            let ranges = self
+                .select_only
+                .iter()
+                .map(|range| match range.start() == range.end() {
+                    true => range.start().to_string(),
+                    false => format!("{}-{}", range.start(), range.end()),
+                })
+                .collect::<Vec<_>>();
+            params.push(format!("so={}", ranges.join(",")));
+        }
+
+        write!(f, "magnet:?{}", params.join("&"))
+    }
+}
+
+impl From<&Metainfo> for MagnetLink {
+    /// a magnet link for a parsed `.torrent` file, with its hashes, name, size, trackers and web seeds.
+    fn from(metainfo: &Metainfo) -> Self {
+        Self {
+            info_hash_v1: metainfo.info_hash_v1.clone(),
+            info_hash_v2: metainfo.info_hash_v2.clone(),
+            display_name: Some(metainfo.name.clone()),
+            trackers: metainfo.trackers.clone(),
+            web_seeds: metainfo.web_seeds.clone(),
+            exact_length: Some(metainfo.total_size()),
+            select_only: Vec::new(),
+        }
+    }
+}
+
+/// parses a v1 info-hash, in hex or base32.
+fn parse_btih(hash: &str) -> Result<String, Error> {
+    match hash.len() {
+        40 => parse_hex(hash, 20),
+        32 => decode_base32(hash).map(|bytes| hex(&bytes)),
+        _ => Err(invalid(format!("invalid info-hash {:?}", hash))),
+    }
+}
+
+fn parse_hex(hash: &str, length: usize) -> Result<String, Error> {
+    if hash.len() != length * 2 || !hash.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return Err(invalid(format!("invalid info-hash {:?}", hash)));
+    }
+
+    Ok(hash.to_ascii_lowercase())
+}
+
+/// decodes unpadded RFC 4648 base32, as used by old magnet links.
+fn decode_base32(hash: &str) -> Result<Vec<u8>, Error> {
+    let mut bytes = Vec::with_capacity(hash.len() * 5 / 8);
+    let mut buffer: u64 = 0;
+    let mut bits = 0;
+
+    for char in hash.bytes() {
+        let value = match char.to_ascii_uppercase() {
+            c @ b'A'..=b'Z' => c - b'A',
+            c @ b'2'..=b'7' => c - b'2' + 26,
+            _ => return Err(invalid(format!("invalid info-hash {:?}", hash))),
+        };
+
+        buffer = (buffer << 5) | u64::from(value);
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+
+    Ok(bytes)
+}
+
+/// parses a list of file indexes and ranges, like `0,2,4-6`.
+fn parse_select_only(value: &str) -> Result<Vec<RangeInclusive<usize>>, Error> {
+    let index = |index: &str| {
+        index
+            .trim()
+            .parse::<usize>()
+            .map_err(|_| invalid(format!("invalid file selection {:?}", value)))
+    };
+
+    value
+        .split(',')
+        .filter(|part| !part.trim().is_empty())
+        .map(|part| match part.split_once('-') {
+            Some((start, end)) => Ok(index(start)?..=index(end)?),
+            None => index(part).map(|i| i..=i),
+        })
+        .collect()
+}
+
+fn encode(value: &str) -> String {
+    byte_serialize(value.as_bytes()).collect()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn invalid(reason: String) -> Error {
+    Error::build(ErrorType::InvalidMagnetLink(reason), None)
+}
@@ -0,0 +1,308 @@
+use std::path::Path;
+
+use ring::digest::{digest, SHA1_FOR_LEGACY_USE_ONLY, SHA256};
+
+use crate::{
+    error_handling::{error_type::ErrorType, errors::Error},
+    misc::bencode::{Bencode, Decoder},
+};
+
+use super::{info::TorrentHash, magnet::MagnetLink};
+
+/// ## Info
+/// the BitTorrent version(s) a torrent file is made for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetainfoVersion {
+    V1,
+    V2,
+    /// both v1 and v2: the torrent has both info-hashes.
+    Hybrid,
+}
+
+/// ## Info
+/// a file of a [`Metainfo`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetainfoFile {
+    /// the path of the file, with `/` as separator.
+    /// for torrents with more than one file, it starts with the name of the torrent, like in qbittorrent.
+    pub path: String,
+    /// the size in bytes.
+    pub size: u64,
+}
+
+/// ## Info
+/// the content of a `.torrent` file, parsed locally.
+///
+/// ## Example
+/// ```no_run
+/// # use qbittorrent_rust::{core::api::QbitApi, Metainfo, Torrent, TorrentAddDescriptor};
+/// # async fn example(api: QbitApi) -> Result<(), qbittorrent_rust::Error> {
+/// let metainfo = Metainfo::from_file("/home/user/ubuntu.torrent").await?;
+/// println!("{} ({} bytes): {}", metainfo.name, metainfo.total_size(), metainfo.hash());
+///
+/// let already_added = api.torrents_get_hashes().await?.iter().any(|t| t.hash == metainfo.hash());
+/// if !already_added {
+///     api.torrents_add_torrent(TorrentAddDescriptor::new(vec![Torrent::from_metainfo(metainfo)])?).await?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Metainfo {
+    pub name: String,
+    pub version: MetainfoVersion,
+    /// the SHA-1 of the info dictionary, in lowercase hex. `None` for v2-only torrents.
+    pub info_hash_v1: Option<String>,
+    /// the SHA-256 of the info dictionary, in lowercase hex. `None` for v1-only torrents.
+    pub info_hash_v2: Option<String>,
+    pub piece_length: u64,
+    /// padding files of hybrid torrents aren't listed.
+    pub files: Vec<MetainfoFile>,
+    /// the tracker urls, from `announce-list` (tier after tier) and `announce`, without duplicates.
+    pub trackers: Vec<String>,
+    /// the web seed urls (`url-list`).
+    pub web_seeds: Vec<String>,
+    pub private: bool,
+    pub comment: Option<String>,
+    pub created_by: Option<String>,
+    /// unix timestamp, in seconds.
+    pub creation_date: Option<i64>,
+    raw: Vec<u8>,
+}
+
+impl Metainfo {
+    /// ## Usage
+    /// parses the content of a `.torrent` file.
+    ///
+    /// ## Errors
+    /// - returns an [`Error`] with error type [`ErrorType::InvalidTorrentFile`] if the data isn't a valid torrent.
+    pub fn from_bytes(bytes: impl Into<Vec<u8>>) -> Result<Self, Error> {
+        let raw = bytes.into();
+
+        let root = Bencode::decode(&raw)?;
+        let info_span = info_span(&raw)?;
+        let info = root.get("info").ok_or_else(|| invalid("the info dictionary is missing"))?;
+
+        let name = info
+            .get("name.utf-8")
+            .or(info.get("name"))
+            .and_then(Bencode::as_string)
+            .ok_or_else(|| invalid("the name is missing"))?;
+
+        let piece_length = info
+            .get("piece length")
+            .and_then(Bencode::as_integer)
+            .and_then(|length| u64::try_from(length).ok())
+            .filter(|length| *length > 0)
+            .ok_or_else(|| invalid("the piece length is missing"))?;
+
+        let is_v1 = info.get("pieces").and_then(Bencode::as_bytes).is_some();
+        let is_v2 = info.get("meta version").and_then(Bencode::as_integer) == Some(2);
+
+        let version = match (is_v1, is_v2) {
+            (true, true) => MetainfoVersion::Hybrid,
+            (true, false) => MetainfoVersion::V1,
+            (false, true) => MetainfoVersion::V2,
+            (false, false) => return Err(invalid("the torrent has neither pieces nor a file tree")),
+        };
+
+        let files = if is_v1 {
+            v1_files(info, &name)?
+        } else {
+            let file_tree = info.get("file tree").ok_or_else(|| invalid("the file tree is missing"))?;
+            let mut files = Vec::new();
+            v2_files(file_tree, &mut Vec::new(), &mut files)?;
+            // a single-file torrent's tree is just the file, named like the torrent.
+            // anything else, even one file in a folder, is inside a folder named like the torrent.
+            let single_file = file_tree.as_dict().is_some_and(|tree| {
+                tree.len() == 1 && tree.get(name.as_bytes()).is_some_and(|node| node.get("").is_some())
+            });
+            if !single_file {
+                for file in &mut files {
+                    file.path = format!("{}/{}", name, file.path);
+                }
+            }
+            files
+        };
+
+        let info_bytes = &raw[info_span];
+        let info_hash_v1 = is_v1.then(|| hex(digest(&SHA1_FOR_LEGACY_USE_ONLY, info_bytes).as_ref()));
+        let info_hash_v2 = is_v2.then(|| hex(digest(&SHA256, info_bytes).as_ref()));
+
+        let mut trackers: Vec<String> = Vec::new();
+        let tiers = root.get("announce-list").and_then(Bencode::as_list).unwrap_or_default();
+        let announce = root.get("announce").and_then(Bencode::as_string);
+        for tracker in tiers
+            .iter()
+            .filter_map(Bencode::as_list)
+            .flatten()
+            .filter_map(Bencode::as_string)
+            .chain(announce)
+        {
+            if !tracker.is_empty() && !trackers.contains(&tracker) {
+                trackers.push(tracker);
+            }
+        }
+
+        let web_seeds = match root.get("url-list") {
+            Some(Bencode::List(list)) => list.iter().filter_map(Bencode::as_string).collect(),
+            Some(url) => url.as_string().into_iter().filter(|url| !url.is_empty()).collect(),
+            None => Vec::new(),
+        };
+
+        Ok(Self {
+            name,
+            version,
+            info_hash_v1,
+            info_hash_v2,
+            piece_length,
+            files,
+            trackers,
+            web_seeds,
+            private: info.get("private").and_then(Bencode::as_integer) == Some(1),
+            comment: root.get("comment.utf-8").or(root.get("comment")).and_then(Bencode::as_string),
+            created_by: root.get("created by").and_then(Bencode::as_string),
+            creation_date: root.get("creation date").and_then(Bencode::as_integer),
+            raw,
+        })
+    }
+
+    /// ## Usage
+    /// reads and parses a `.torrent` file.
+    ///
+    /// ## Errors
+    /// - returns an [`Error`] with error type [`ErrorType::TorrentFilePathError`] if the file couldn't be read.
+    /// - returns an [`Error`] with error type [`ErrorType::InvalidTorrentFile`] if the file isn't a valid torrent.
+    pub async fn from_file(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let bytes = tokio::fs::read(path)
+            .await
+            .map_err(|_| Error::build(ErrorType::TorrentFilePathError, None))?;
+
+        Self::from_bytes(bytes)
+    }
+
+    /// ## Usage
+    /// the hash qbittorrent identifies the torrent with: the v1 info-hash,
+    /// or the v2 info-hash truncated to 40 characters for v2-only torrents.
+    pub fn hash(&self) -> String {
+        match (&self.info_hash_v1, &self.info_hash_v2) {
+            (Some(v1), _) => v1.clone(),
+            (None, Some(v2)) => v2[..40].to_string(),
+            (None, None) => unreachable!("a parsed torrent has at least one info-hash"),
+        }
+    }
+
+    /// ## Usage
+    /// a [`TorrentHash`] for this torrent, to use with the other methods once it's added.
+    pub fn torrent_hash(&self) -> TorrentHash {
+        TorrentHash::new(self.name.clone(), self.hash())
+    }
+
+    /// ## Usage
+    /// a [`MagnetLink`] for this torrent.
+    pub fn magnet_link(&self) -> MagnetLink {
+        MagnetLink::from(self)
+    }
+
+    /// ## Usage
+    /// the size of all the files, in bytes.
+    pub fn total_size(&self) -> u64 {
+        self.files.iter().map(|file| file.size).sum()
+    }
+
+    /// ## Usage
+    /// the content of the `.torrent` file.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.raw
+    }
+}
+
+/// where the value of the `info` key is, since the info-hashes are computed over its exact bytes.
+fn info_span(raw: &[u8]) -> Result<std::ops::Range<usize>, Error> {
+    let mut decoder = Decoder::new(raw);
+    decoder.dict_start()?;
+
+    while let Some(key) = decoder.dict_key()? {
+        let (_, span) = decoder.value_with_span()?;
+        if key == b"info" {
+            return Ok(span);
+        }
+    }
+
+    Err(invalid("the info dictionary is missing"))
+}
+
+fn v1_files(info: &Bencode, name: &str) -> Result<Vec<MetainfoFile>, Error> {
+    let Some(files) = info.get("files") else {
+        let size = info
+            .get("length")
+            .and_then(Bencode::as_integer)
+            .and_then(|size| u64::try_from(size).ok())
+            .ok_or_else(|| invalid("the length is missing"))?;
+        return Ok(vec![MetainfoFile { path: name.to_string(), size }]);
+    };
+
+    let mut result = Vec::new();
+    for file in files.as_list().ok_or_else(|| invalid("the files aren't a list"))? {
+        let is_padding = file
+            .get("attr")
+            .and_then(Bencode::as_bytes)
+            .is_some_and(|attr| attr.contains(&b'p'));
+        if is_padding {
+            continue;
+        }
+
+        let size = file
+            .get("length")
+            .and_then(Bencode::as_integer)
+            .and_then(|size| u64::try_from(size).ok())
+            .ok_or_else(|| invalid("a file has no length"))?;
+        let path = file
+            .get("path.utf-8")
+            .or(file.get("path"))
+            .and_then(Bencode::as_list)
+            .ok_or_else(|| invalid("a file has no path"))?;
+
+        let mut components = vec![name.to_string()];
+        components.extend(path.iter().filter_map(Bencode::as_string));
+        result.push(MetainfoFile {
+            path: components.join("/"),
+            size,
+        });
+    }
+
+    Ok(result)
+}
+
+/// walks a v2 file tree, where files are dictionaries with an empty key.
+fn v2_files(tree: &Bencode, path: &mut Vec<String>, files: &mut Vec<MetainfoFile>) -> Result<(), Error> {
+    let tree = tree.as_dict().ok_or_else(|| invalid("the file tree isn't a dictionary"))?;
+
+    for (name, node) in tree {
+        if name.is_empty() {
+            let size = node
+                .get("length")
+                .and_then(Bencode::as_integer)
+                .and_then(|size| u64::try_from(size).ok())
+                .ok_or_else(|| invalid("a file has no length"))?;
+            files.push(MetainfoFile {
+                path: path.join("/"),
+                size,
+            });
+        } else {
+            path.push(String::from_utf8_lossy(name).into_owned());
+            v2_files(node, path, files)?;
+            path.pop();
+        }
+    }
+
+    Ok(())
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn invalid(reason: &str) -> Error {
+    Error::build(ErrorType::InvalidTorrentFile(reason.to_string()), None)
+}
@@ -1,4 +1,7 @@
 pub mod add_torrent;
 pub mod torrents;
 pub mod info;
-pub mod torrent_managing_misc;
\ No newline at end of file
+pub mod torrent_managing_misc;
+pub mod properties;
+pub mod metainfo;
+pub mod magnet;
\ No newline at end of file
@@ -0,0 +1,336 @@
+use std::{borrow::Borrow, collections::HashMap, time::Duration};
+
+use chrono::{DateTime, Utc};
+use proc_macros_qbittorrent_rust::requires_hash;
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+
+use crate::{
+    core::api::QbitApi, error_handling::error_type::ErrorType, misc::deserializers, Error,
+};
+
+use super::{info::TorrentHash, torrent_managing_misc::TorrentContent};
+
+/// ## Info
+/// represents the generic properties of a torrent, as returned by [`QbitApi::torrents_get_torrent_generic_properties_typed()`].
+/// every field that isn't listed here is kept in `extra`.
+///
+/// ## Fields
+/// | Field                      | Type                    | Description                                              |
+/// |----------------------------|-------------------------|----------------------------------------------------------|
+/// | `save_path`                | `String`                | Torrent save path                                        |
+/// | `creation_date`            | `Option<DateTime<Utc>>` | Torrent creation date                                    |
+/// | `piece_size`               | `Integer`               | Torrent piece size (bytes)                               |
+/// | `comment`                  | `String`                | Torrent comment                                          |
+/// | `total_wasted`             | `Integer`               | Total data wasted for torrent (bytes)                     |
+/// | `total_uploaded`           | `Integer`               | Total data uploaded for torrent (bytes)                   |
+/// | `total_uploaded_session`   | `Integer`               | Total data uploaded this session (bytes)                 |
+/// | `total_downloaded`         | `Integer`               | Total data downloaded for torrent (bytes)                 |
+/// | `total_downloaded_session` | `Integer`               | Total data downloaded this session (bytes)               |
+/// | `up_limit`                 | `Integer`               | Torrent upload limit (bytes/s)                           |
+/// | `dl_limit`                 | `Integer`               | Torrent download limit (bytes/s)                         |
+/// | `time_elapsed`             | `Duration`              | Torrent elapsed time                                     |
+/// | `seeding_time`             | `Duration`              | Torrent elapsed time while complete                      |
+/// | `nb_connections`           | `Integer`               | Torrent connection count                                 |
+/// | `nb_connections_limit`     | `Integer`               | Torrent connection count limit                           |
+/// | `share_ratio`              | `Float`                 | Torrent share ratio                                      |
+/// | `addition_date`            | `Option<DateTime<Utc>>` | When this torrent was added                              |
+/// | `completion_date`          | `Option<DateTime<Utc>>` | Torrent completion date                                  |
+/// | `created_by`               | `String`                | Torrent creator                                          |
+/// | `dl_speed_avg`             | `Integer`               | Torrent average download speed (bytes/s)                 |
+/// | `dl_speed`                 | `Integer`               | Torrent download speed (bytes/s)                         |
+/// | `eta`                      | `Option<Duration>`      | Torrent ETA, [`None`] if infinite                        |
+/// | `last_seen`                | `Option<DateTime<Utc>>` | Last seen complete date                                  |
+/// | `peers`                    | `Integer`               | Number of peers connected to                             |
+/// | `peers_total`              | `Integer`               | Number of peers in the swarm                             |
+/// | `pieces_have`              | `Integer`               | Number of pieces owned                                   |
+/// | `pieces_num`               | `Integer`               | Number of pieces of the torrent                          |
+/// | `reannounce`               | `Duration`              | Time until the next announce                             |
+/// | `seeds`                    | `Integer`               | Number of seeds connected to                             |
+/// | `seeds_total`              | `Integer`               | Number of seeds in the swarm                             |
+/// | `total_size`               | `Integer`               | Torrent total size (bytes)                               |
+/// | `up_speed_avg`             | `Integer`               | Torrent average upload speed (bytes/s)                   |
+/// | `up_speed`                 | `Integer`               | Torrent upload speed (bytes/s)                           |
+/// | `extra`                    | `HashMap<String, Value>`| Every other field returned by the server                 |
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct TorrentProperties {
+    pub save_path: String,
+    #[serde(deserialize_with = "deserializers::optional_timestamp")]
+    pub creation_date: Option<DateTime<Utc>>,
+    pub piece_size: i64,
+    pub comment: String,
+    pub total_wasted: i64,
+    pub total_uploaded: i64,
+    pub total_uploaded_session: i64,
+    pub total_downloaded: i64,
+    pub total_downloaded_session: i64,
+    pub up_limit: i64,
+    pub dl_limit: i64,
+    #[serde(deserialize_with = "deserializers::seconds")]
+    pub time_elapsed: Duration,
+    #[serde(deserialize_with = "deserializers::seconds")]
+    pub seeding_time: Duration,
+    pub nb_connections: i64,
+    pub nb_connections_limit: i64,
+    pub share_ratio: f64,
+    #[serde(deserialize_with = "deserializers::optional_timestamp")]
+    pub addition_date: Option<DateTime<Utc>>,
+    #[serde(deserialize_with = "deserializers::optional_timestamp")]
+    pub completion_date: Option<DateTime<Utc>>,
+    pub created_by: String,
+    pub dl_speed_avg: i64,
+    pub dl_speed: i64,
+    #[serde(deserialize_with = "deserializers::eta")]
+    pub eta: Option<Duration>,
+    #[serde(deserialize_with = "deserializers::optional_timestamp")]
+    pub last_seen: Option<DateTime<Utc>>,
+    pub peers: i64,
+    pub peers_total: i64,
+    pub pieces_have: i64,
+    pub pieces_num: i64,
+    #[serde(deserialize_with = "deserializers::seconds")]
+    pub reannounce: Duration,
+    pub seeds: i64,
+    pub seeds_total: i64,
+    pub total_size: i64,
+    pub up_speed_avg: i64,
+    pub up_speed: i64,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+/// ## Info
+/// represents the status of a tracker.
+///
+/// ## Variants
+/// | Variant        | Code | Description                                                     |
+/// |----------------|------|-----------------------------------------------------------------|
+/// | `Disabled`     | `0`  | Tracker is disabled (used for DHT, PeX, and LSD)                |
+/// | `NotContacted` | `1`  | Tracker has not been contacted yet                              |
+/// | `Working`      | `2`  | Tracker has been contacted and is working                       |
+/// | `Updating`     | `3`  | Tracker is updating                                             |
+/// | `NotWorking`   | `4`  | Tracker has been contacted, but it is not working (or doesn't send proper replies) |
+/// | `Unknown`      |      | A code the library doesn't recognize                            |
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TrackerStatus {
+    Disabled,
+    NotContacted,
+    Working,
+    Updating,
+    NotWorking,
+    #[default]
+    Unknown,
+}
+impl TrackerStatus {
+    /// ## Usage
+    /// returns the [`TrackerStatus`] corresponding to a status code.
+    pub fn from_code(code: i64) -> Self {
+        match code {
+            0 => TrackerStatus::Disabled,
+            1 => TrackerStatus::NotContacted,
+            2 => TrackerStatus::Working,
+            3 => TrackerStatus::Updating,
+            4 => TrackerStatus::NotWorking,
+            _ => TrackerStatus::Unknown,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for TrackerStatus {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Self::from_code(i64::deserialize(deserializer)?))
+    }
+}
+
+/// ## Info
+/// represents one tracker of a torrent, as returned by [`QbitApi::torrents_get_torrent_trackers_typed()`].
+///
+/// ## Fields
+/// | Field            | Type            | Description                                                       |
+/// |------------------|-----------------|-------------------------------------------------------------------|
+/// | `url`            | `String`        | Tracker url (or `** [DHT] **`, `** [PeX] **`, `** [LSD] **`)       |
+/// | `status`         | `TrackerStatus` | Tracker status                                                    |
+/// | `tier`           | `Option<u64>`   | Tracker priority tier. [`None`] for DHT, PeX and LSD               |
+/// | `num_peers`      | `Integer`       | Number of peers for current torrent, as reported by the tracker   |
+/// | `num_seeds`      | `Integer`       | Number of seeds for current torrent, as reported by the tracker   |
+/// | `num_leeches`    | `Integer`       | Number of leeches for current torrent, as reported by the tracker |
+/// | `num_downloaded` | `Integer`       | Number of completed downloads, as reported by the tracker. `-1` if unknown |
+/// | `msg`            | `String`        | Tracker message                                                   |
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct TrackerEntry {
+    pub url: String,
+    pub status: TrackerStatus,
+    #[serde(deserialize_with = "deserializers::optional_lenient_integer")]
+    pub tier: Option<u64>,
+    pub num_peers: i64,
+    pub num_seeds: i64,
+    pub num_leeches: i64,
+    pub num_downloaded: i64,
+    pub msg: String,
+}
+
+/// ## Info
+/// represents one web seed of a torrent, as returned by [`QbitApi::torrents_get_torrent_web_seeds_typed()`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct WebSeed {
+    pub url: String,
+}
+
+/// ## Info
+/// represents the download priority of a file in a torrent.
+///
+/// ## Variants
+/// | Variant         | Code | Description                          |
+/// |-----------------|------|--------------------------------------|
+/// | `DoNotDownload` | `0`  | The file won't be downloaded         |
+/// | `Normal`        | `1`  | Normal priority                      |
+/// | `High`          | `6`  | High priority                        |
+/// | `Maximal`       | `7`  | Maximal priority                     |
+/// | `Other`         |      | Any other code sent by the server    |
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum FilePriority {
+    DoNotDownload,
+    #[default]
+    Normal,
+    High,
+    Maximal,
+    Other(i64),
+}
+impl FilePriority {
+    /// ## Usage
+    /// returns the [`FilePriority`] corresponding to a priority code.
+    pub fn from_code(code: i64) -> Self {
+        match code {
+            0 => FilePriority::DoNotDownload,
+            1 => FilePriority::Normal,
+            6 => FilePriority::High,
+            7 => FilePriority::Maximal,
+            n => FilePriority::Other(n),
+        }
+    }
+
+    /// ## Usage
+    /// returns the priority code qbittorrent uses for this priority.
+    pub fn code(&self) -> i64 {
+        match self {
+            FilePriority::DoNotDownload => 0,
+            FilePriority::Normal => 1,
+            FilePriority::High => 6,
+            FilePriority::Maximal => 7,
+            FilePriority::Other(n) => *n,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for FilePriority {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Self::from_code(i64::deserialize(deserializer)?))
+    }
+}
+
+/// ## Info
+/// represents one file in a torrent, as returned by [`QbitApi::torrents_get_torrent_contents_typed()`].
+///
+/// ## Fields
+/// | Field          | Type           | Description                                                        |
+/// |----------------|----------------|--------------------------------------------------------------------|
+/// | `index`        | `Integer`      | File index                                                         |
+/// | `name`         | `String`       | File name (including relative path)                                |
+/// | `size`         | `Integer`      | File size (bytes)                                                  |
+/// | `progress`     | `Float`        | File progress, from `0.0` to `1.0`                                 |
+/// | `priority`     | `FilePriority` | File priority                                                      |
+/// | `is_seed`      | `Bool`         | `true` if the file is seeding/complete                             |
+/// | `piece_range`  | `(u64, u64)`   | The first and the last piece index of the file                     |
+/// | `availability` | `Float`        | Percentage of file pieces currently available (`-1` if unknown)    |
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct TorrentFileEntry {
+    pub index: u64,
+    pub name: String,
+    pub size: i64,
+    pub progress: f64,
+    pub priority: FilePriority,
+    pub is_seed: bool,
+    pub piece_range: (u64, u64),
+    pub availability: f64,
+}
+
+impl From<&TorrentFileEntry> for TorrentContent {
+    fn from(value: &TorrentFileEntry) -> Self {
+        TorrentContent {
+            id: value.index,
+            name: value.name.clone(),
+        }
+    }
+}
+
+impl QbitApi {
+    #[requires_hash]
+    /// ## Usage
+    /// Gets the properties of a specified torrent as a [`TorrentProperties`].
+    pub async fn torrents_get_torrent_generic_properties_typed(
+        &self,
+        hash: impl Borrow<TorrentHash>,
+    ) -> Result<TorrentProperties, Error> {
+        let value = self.torrents_get_torrent_generic_properties(hash).await?;
+        serde_json::from_value(value)
+            .map_err(|e| Error::build(ErrorType::JsonSerdeError(Box::new(e)), None))
+    }
+
+    #[requires_hash]
+    /// ## Usage
+    /// Gets all the trackers of a specified torrent as a [`Vec`] of [`TrackerEntry`]s.
+    pub async fn torrents_get_torrent_trackers_typed(
+        &self,
+        hash: impl Borrow<TorrentHash>,
+    ) -> Result<Vec<TrackerEntry>, Error> {
+        serde_json::from_str(self.torrents_get_torrent_trackers_raw(hash).await?.as_str())
+            .map_err(|e| Error::build(ErrorType::JsonSerdeError(Box::new(e)), None))
+    }
+
+    #[requires_hash]
+    /// ## Usage
+    /// Gets all the web seeds of a specified torrent as a [`Vec`] of [`WebSeed`]s.
+    pub async fn torrents_get_torrent_web_seeds_typed(
+        &self,
+        hash: impl Borrow<TorrentHash>,
+    ) -> Result<Vec<WebSeed>, Error> {
+        serde_json::from_str(self.torrents_get_torrent_web_seeds_raw(hash).await?.as_str())
+            .map_err(|e| Error::build(ErrorType::JsonSerdeError(Box::new(e)), None))
+    }
+
+    #[requires_hash]
+    /// ## Usage
+    /// Gets all the contents (files) of a torrent as a [`Vec`] of [`TorrentFileEntry`]s.
+    ///
+    /// ## Arguments
+    /// - indexes: the indexes of the files to get. if `None`, all the files are returned.
+    pub async fn torrents_get_torrent_contents_typed(
+        &self,
+        hash: impl Borrow<TorrentHash>,
+        indexes: impl Borrow<Option<Vec<String>>>,
+    ) -> Result<Vec<TorrentFileEntry>, Error> {
+        let values: Vec<Value> = serde_json::from_str(
+            self.torrents_get_torrent_contents_raw(hash, indexes)
+                .await?
+                .as_str(),
+        )
+        .map_err(|e| Error::build(ErrorType::JsonSerdeError(Box::new(e)), None))?;
+
+        // older versions of qbittorrent don't send the index, which is then the position in the list.
+        values
+            .into_iter()
+            .enumerate()
+            .map(|(position, mut value)| {
+                if let Value::Object(map) = &mut value {
+                    map.entry("index").or_insert(Value::from(position));
+                }
+                serde_json::from_value(value)
+                    .map_err(|e| Error::build(ErrorType::JsonSerdeError(Box::new(e)), None))
+            })
+            .collect()
+    }
+}
@@ -4,7 +4,7 @@ use proc_macros_qbittorrent_rust::{requires_hash, requires_mult_hashes};
 use serde_json::Value;
 
 use crate::{
-    core::api::QbitApi, error_handling::error_type::ErrorType, fn_hash_value_pair,
+    code, core::{api::QbitApi, version::ApiVersion}, error_handling::error_type::ErrorType, fn_hash_value_pair,
     fn_value_from_string, misc::sep_vec::SepVec, post_request_hash, request_error_focus,
     torrents_fn_mult_hashes, torrents_fn_mult_hashes_prios, url, Error,
 };
@@ -47,7 +47,7 @@ impl QbitApi {
     /// ## Usage
     /// Gets the properties of a specified torrent as a [`String`].
     pub async fn torrents_get_torrent_generic_properties_raw(
-        &mut self,
+        &self,
         hash: impl Borrow<TorrentHash>,
     ) -> Result<String, Error> {
         Ok(self
@@ -60,7 +60,7 @@ impl QbitApi {
     /// ## Usage
     /// Gets the properties of a specified torrent as a [`serde_json::value::Value`]
     pub async fn torrents_get_torrent_generic_properties(
-        &mut self,
+        &self,
         hash: impl Borrow<TorrentHash>,
     ) -> Result<Value, Error> {
         let mid: &TorrentHash = hash.borrow();
@@ -109,7 +109,7 @@ impl QbitApi {
     /// ## Usage
     /// Gets all the contents (files) of a torrent as a [`String`].
     pub async fn torrents_get_torrent_contents_raw(
-        &mut self,
+        &self,
         hash: impl Borrow<TorrentHash>,
         indexes: impl Borrow<Option<Vec<String>>>,
     ) -> Result<String, Error> {
@@ -132,7 +132,7 @@ impl QbitApi {
     /// ## Usage
     /// Gets all the contents (files) of a torrent as a [`serde_json::value::Value`].
     pub async fn torrents_get_torrent_contents(
-        &mut self,
+        &self,
         hash: impl Borrow<TorrentHash>,
         indexes: impl Borrow<Option<Vec<String>>>,
     ) -> Result<Value, Error> {
@@ -148,7 +148,7 @@ impl QbitApi {
     /// ## Usage
     /// Gets all the contents (files) of a torrent as a [`Vec`] of [`TorrentContent`]s.
     pub async fn torrents_get_files_ids(
-        &mut self,
+        &self,
         hash: impl Borrow<TorrentHash>,
     ) -> Vec<TorrentContent> {
         let x = self
@@ -210,7 +210,7 @@ impl QbitApi {
         /// 1 -> Now downloading;
         /// 2 -> Already downloaded;
     pub async fn torrents_get_torrent_pieces_states_vec(
-        &mut self,
+        &self,
         hash: impl Borrow<TorrentHash>,
     ) -> Result<Vec<u8>, Error> {
         let strr = self.torrents_get_torrent_pieces_states_raw(hash).await?;
@@ -237,7 +237,7 @@ impl QbitApi {
     /// ## Usage
     /// Gets all the hashes of the pieces of a specified torrent as a [`Vec`] of [`String`]s
     pub async fn torrents_get_torrent_pieces_hashes_vec(
-        &mut self,
+        &self,
         hash: impl Borrow<TorrentHash>,
     ) -> Result<Vec<String>, Error> {
         let strr = self.torrents_get_torrent_pieces_hashes_raw(hash).await?;
@@ -251,22 +251,77 @@ impl QbitApi {
         )
     }
 
+    #[requires_hash]
+    /// ## Usage
+    /// Exports the specified torrent, returning the content of its `.torrent` file.
+    ///
+    /// ## Errors
+    /// requires the WebAPI 2.8.14 (qbittorrent 4.5); older servers return [`ErrorType::UnsupportedByServer`].
+    pub async fn torrents_export(&self, hash: impl Borrow<TorrentHash>) -> Result<Vec<u8>, Error> {
+        self.require_version(ApiVersion::V2_8_14).await?;
+
+        let url = format!("{}/api/v2/torrents/export", self.authority);
+        let form = [("hash", hash.get_hash())];
+
+        let response = self
+            .send_with_reauth(|| self.reqwest_client.post(&url).form(&form))
+            .await?;
 
-    torrents_fn_mult_hashes!(
-        #[requires_mult_hashes]
-        /// ## Usage
-        /// Pauses the torrents specified.
-        torrents_pause_torrents,
-        "/torrents/pause"
-    );
+        match response.status().as_u16() {
+            200..=299 => Ok(response
+                .bytes()
+                .await
+                .map_err(|e| Error::build(ErrorType::ReqwestError(Box::new(e)), None))?
+                .to_vec()),
+            404 => Err(Error::build(ErrorType::TorrentHashNotFound, code!(response))),
+            409 => Err(Error::build(
+                ErrorType::MiscError("the torrent couldn't be exported (eg: its metadata isn't known yet).".to_string()),
+                code!(response),
+            )),
+            num => Err(Error::build(ErrorType::MiscNetError(num), Some(num))),
+        }
+    }
 
-    torrents_fn_mult_hashes!(
-        #[requires_mult_hashes]
-        /// ## Usage
-        /// Resumes the torrents specified.
-        torrents_resume_torrents, 
-        "/torrents/resume"
-    );
+
+    #[requires_mult_hashes]
+    /// ## Usage
+    /// Stops (pauses) the torrents specified.
+    ///
+    /// uses `/torrents/stop` on servers with the WebAPI 2.11 or newer (qbittorrent 5.0+), and `/torrents/pause` on older ones.
+    pub async fn torrents_stop(&self, hashes: impl Borrow<TorrentHashesDesc>) -> Result<(), Error> {
+        let path = if self.supports(ApiVersion::V2_11_0).await { "/torrents/stop" } else { "/torrents/pause" };
+        let url = url!(path, ("hashes", Some(hashes.borrow().get_string("|"))));
+
+        self.make_request(url, "torrents_stop").await?;
+
+        Ok(())
+    }
+
+    #[requires_mult_hashes]
+    /// ## Usage
+    /// Starts (resumes) the torrents specified.
+    ///
+    /// uses `/torrents/start` on servers with the WebAPI 2.11 or newer (qbittorrent 5.0+), and `/torrents/resume` on older ones.
+    pub async fn torrents_start(&self, hashes: impl Borrow<TorrentHashesDesc>) -> Result<(), Error> {
+        let path = if self.supports(ApiVersion::V2_11_0).await { "/torrents/start" } else { "/torrents/resume" };
+        let url = url!(path, ("hashes", Some(hashes.borrow().get_string("|"))));
+
+        self.make_request(url, "torrents_start").await?;
+
+        Ok(())
+    }
+
+    /// ## Usage
+    /// Pauses the torrents specified. alias of `torrents_stop`.
+    pub async fn torrents_pause_torrents(&self, hashes: impl Borrow<TorrentHashesDesc>) -> Result<(), Error> {
+        self.torrents_stop(hashes).await
+    }
+
+    /// ## Usage
+    /// Resumes the torrents specified. alias of `torrents_start`.
+    pub async fn torrents_resume_torrents(&self, hashes: impl Borrow<TorrentHashesDesc>) -> Result<(), Error> {
+        self.torrents_start(hashes).await
+    }
 
     #[requires_mult_hashes]
     /// ## Usage
@@ -275,7 +330,7 @@ impl QbitApi {
     /// ## Arguments
     /// - `delete_files`: if set to true, the downloaded data will also be deleted.
     pub async fn torrents_delete_torrents(
-        &mut self,
+        &self,
         hashes: impl Borrow<TorrentHashesDesc>,
         delete_files: bool,
     ) -> Result<(), Error> {
@@ -315,6 +370,7 @@ impl QbitApi {
     );
 
     torrents_fn_mult_hashes_prios!(
+        non_idempotent
         #[requires_mult_hashes]
         /// ## Usage
         /// Increases the priority of the torrents specified of one level.
@@ -323,7 +379,7 @@ impl QbitApi {
     );
 
     torrents_fn_mult_hashes_prios!(
-        
+        non_idempotent
         #[requires_mult_hashes]
         /// ## Usage
         /// Decreases the priority of the torrents specified of one level.
@@ -354,7 +410,7 @@ impl QbitApi {
     /// ## Arguments
     /// - trackers: a [`Vec`] of urls for the trackers.
     pub async fn torrents_add_trackers_to_torrent<U: Into<String> + Clone>(
-        &mut self,
+        &self,
         hash: impl Borrow<TorrentHash>,
         trackers: impl Borrow<Vec<U>>,
     ) -> Result<(), Error> {
@@ -389,7 +445,7 @@ impl QbitApi {
     /// - orig_url: the original url of the tracker.
     /// - new_url: the new url of the tracker.
     pub async fn torrents_edit_trackers(
-        &mut self,
+        &self,
         hash: impl Borrow<TorrentHash>,
         orig_url: impl AsRef<String>,
         new_url: impl AsRef<String>,
@@ -404,7 +460,7 @@ impl QbitApi {
         hashmap.insert("newUrl", new_url);
 
         request_error_focus!(
-            self,
+            non_idempotent self,
             torrents_edit_trackers,
             "/torrents/editTracker",
             hashmap,
@@ -431,7 +487,7 @@ impl QbitApi {
     /// ## Arguments
     /// - urls: a [`Vec`] of the urls of the trackers.
     pub async fn torrents_remove_trackers<S: Into<String> + Clone>(
-        &mut self,
+        &self,
         hash: impl Borrow<TorrentHash>,
         urls: impl Borrow<Vec<S>>,
     ) -> Result<(), Error> {
@@ -461,6 +517,112 @@ impl QbitApi {
         Ok(())
     }
 
+    #[requires_hash]
+    /// ## Usage
+    /// Adds web seeds to the specified torrent.
+    /// 
+    /// ## Arguments
+    /// - urls: a [`Vec`] of the urls of the web seeds.
+    ///
+    /// ## Errors
+    /// requires the WebAPI 2.11 (qbittorrent 5.0); older servers return [`ErrorType::UnsupportedByServer`].
+    pub async fn torrents_add_web_seeds<S: Into<String> + Clone>(
+        &self,
+        hash: impl Borrow<TorrentHash>,
+        urls: impl Borrow<Vec<S>>,
+    ) -> Result<(), Error> {
+        self.require_version(ApiVersion::V2_11_0).await?;
+
+        let urls = urls.borrow().iter().map(|x| Into::<String>::into(x.clone())).collect::<Vec<String>>();
+
+        let mut hashmap = HashMap::new();
+
+        hashmap.insert("hash", hash.get_hash());
+        hashmap.insert("urls", SepVec::new(urls, "|").to_string());
+
+        self.make_request_with_form("/torrents/addWebSeeds", "torrents_add_web_seeds", hashmap)
+            .await
+            .map_err(|e| match e.code {
+                Some(400) => Error::build(ErrorType::MiscError("one of the urls is not a valid URL".to_string()), e.code),
+                Some(404) => Error::build(ErrorType::TorrentHashNotFound, e.code),
+                _ => e,
+            })?;
+        Ok(())
+    }
+
+    #[requires_hash]
+    /// ## Usage
+    /// Edits a web seed of the specified torrent.
+    /// 
+    /// ## Arguments
+    /// - orig_url: the current url of the web seed.
+    /// - new_url: the new url of the web seed.
+    ///
+    /// ## Errors
+    /// requires the WebAPI 2.11 (qbittorrent 5.0); older servers return [`ErrorType::UnsupportedByServer`].
+    pub async fn torrents_edit_web_seed(
+        &self,
+        hash: impl Borrow<TorrentHash>,
+        orig_url: impl Into<String>,
+        new_url: impl Into<String>,
+    ) -> Result<(), Error> {
+        self.require_version(ApiVersion::V2_11_0).await?;
+
+        let mut hashmap = HashMap::new();
+
+        hashmap.insert("hash", hash.get_hash());
+        hashmap.insert("origUrl", orig_url.into());
+        hashmap.insert("newUrl", new_url.into());
+
+        self.make_request_with_form_non_idempotent("/torrents/editWebSeed", "torrents_edit_web_seed", hashmap)
+            .await
+            .map_err(|e| match e.code {
+                Some(400) => Error::build(ErrorType::MiscError("new_url is not a valid URL".to_string()), e.code),
+                Some(404) => Error::build(ErrorType::TorrentHashNotFound, e.code),
+                Some(409) => Error::build(
+                    ErrorType::MiscError(
+                        "new_url already exists for the torrent or orig_url couldn't be found.".to_string(),
+                    ),
+                    e.code,
+                ),
+                _ => e,
+            })?;
+        Ok(())
+    }
+
+    #[requires_hash]
+    /// ## Usage
+    /// Removes web seeds from the specified torrent.
+    /// 
+    /// ## Arguments
+    /// - urls: a [`Vec`] of the urls of the web seeds.
+    ///
+    /// ## Errors
+    /// requires the WebAPI 2.11 (qbittorrent 5.0); older servers return [`ErrorType::UnsupportedByServer`].
+    pub async fn torrents_remove_web_seeds<S: Into<String> + Clone>(
+        &self,
+        hash: impl Borrow<TorrentHash>,
+        urls: impl Borrow<Vec<S>>,
+    ) -> Result<(), Error> {
+        self.require_version(ApiVersion::V2_11_0).await?;
+
+        let urls = urls.borrow().iter().map(|x| Into::<String>::into(x.clone())).collect::<Vec<String>>();
+
+        let mut hashmap = HashMap::new();
+
+        hashmap.insert("hash", hash.get_hash());
+        hashmap.insert("urls", SepVec::new(urls, "|").to_string());
+
+        self.make_request_with_form("/torrents/removeWebSeeds", "torrents_remove_web_seeds", hashmap)
+            .await
+            .map_err(|e| match e.code {
+                Some(400) => Error::build(ErrorType::MiscError("one of the urls is not a valid URL".to_string()), e.code),
+                Some(404) => Error::build(ErrorType::TorrentHashNotFound, e.code),
+                _ => e,
+            })?;
+        Ok(())
+    }
+
     /// This method requires knowing the hashes of the torrents interested (`Vec<TorrentHash>`). You can get them using QbitApi::torrents_get_hashes().
     /// ## Usage
     /// Adds peers to multiple torrents.
@@ -469,7 +631,7 @@ impl QbitApi {
     /// - hashes: simply, a [`Vec`] of all the hashes of the torrents interested. 
     /// - peers: a [`Vec`] of peers, represented as [`String`]s; each peer must follow the format `host:port`
     pub async fn torrents_add_peers<S: Into<String> + Clone>(
-        &mut self,
+        &self,
         hashes: impl Borrow<Vec<TorrentHash>>,
         peers: impl Borrow<Vec<S>>,
     ) -> Result<(), Error> {
@@ -507,7 +669,7 @@ impl QbitApi {
     /// ## Arguments
     /// - ids: a [`Vec`] of [`TorrentContent`]s. You can get the ids you need by using `QbitApi::torrents_get_files_ids`.
     pub async fn torrents_set_file_priority(
-        &mut self,
+        &self,
         hash: impl Borrow<TorrentHash>,
         ids: impl Borrow<Vec<TorrentContent>>,
     ) -> Result<(), Error> {
@@ -531,7 +693,7 @@ impl QbitApi {
     /// The method returns a [`String`] formatted in the following way: `{"torrent_hash":download_limit_in_bytes, "torrent_hash2":download_limit_in_bytes2, ...}`
     /// the value of the download limit will be `0` if there's no limit applied.
     pub async fn torrents_get_torrent_download_limit_raw(
-        &mut self,
+        &self,
         hashes: impl Borrow<TorrentHashesDesc>,
     ) -> Result<String, Error> {
         let hashes: TorrentHashesDesc = hashes.borrow().clone();
@@ -556,7 +718,7 @@ impl QbitApi {
     /// The method returns a json [`Value`] with the following structure: `{"torrent_hash_string":download_limit_in_bytes_int, "torrent_hash_string2":download_limit_in_bytes_int2, ...}`
     /// the value of the download limit will be `0` if there's no limit applied.
     pub async fn torrents_get_torrent_download_limit(
-        &mut self,
+        &self,
         hash: impl Borrow<TorrentHashesDesc>,
     ) -> Result<Value, crate::Error> {
         Ok(serde_json::from_str(
@@ -574,7 +736,7 @@ impl QbitApi {
     /// ## Arguments
     /// - limit: download limit in bytes.
     pub async fn torrents_set_torrent_download_limit(
-        &mut self,
+        &self,
         hashes: impl Borrow<TorrentHashesDesc>,
         limit: u64,
     ) -> Result<(), Error> {
@@ -603,7 +765,7 @@ impl QbitApi {
     /// - seeding_time_limit: the maximum seeding time (minutes) for the torrent. `-2` means the global limit should be used, `-1` means no limit.
     /// - inactive_seeding_time_limit: the maximum amount of time (minutes) the torrent is allowed to seed while being inactive. `-2` means the global limit should be used, `-1` means no limit.
     pub async fn torrents_set_torrent_share_limit(
-        &mut self,
+        &self,
         hashes: impl Borrow<TorrentHashesDesc>,
         ratio_limit: f32,
         seeding_time_limit: i32,
@@ -640,7 +802,7 @@ impl QbitApi {
     /// The method returns a [`String`] formatted in the following way: `{"torrent_hash":upload_limit_in_bytes, "torrent_hash2":upload_limit_in_bytes2, ...}`
     /// the value of the upload limit will be `0` if there's no limit applied.
     pub async fn torrents_get_torrent_upload_limit_raw(
-        &mut self,
+        &self,
         hashes: impl Borrow<TorrentHashesDesc>,
     ) -> Result<String, Error> {
         let hashes: TorrentHashesDesc = hashes.borrow().clone();
@@ -666,7 +828,7 @@ impl QbitApi {
     /// the method returns a json [`Value`] with the following structure: `{"torrent_hash_string":upload_limit_in_bytes_int, "torrent_hash_string2":upload_limit_in_bytes_int2, ...}`
     /// the value of the upload limit will be `0` if there's no limit applied.
     pub async fn torrents_get_torrent_upload_limit(
-        &mut self,
+        &self,
         hash: impl Borrow<TorrentHashesDesc>,
     ) -> Result<Value, crate::Error> {
         Ok(serde_json::from_str(
@@ -684,7 +846,7 @@ impl QbitApi {
     /// ## Arguments
     /// - limit: upload limit in bytes.
     pub async fn torrents_set_torrent_upload_limit(
-        &mut self,
+        &self,
         hashes: impl Borrow<TorrentHashesDesc>,
         limit: u64,
     ) -> Result<(), Error> {
@@ -711,7 +873,7 @@ impl QbitApi {
     /// ## Arguments
     /// - location: the path where to download the torrents to.
     pub async fn torrents_set_torrent_download_location(
-        &mut self,
+        &self,
         hashes: impl Borrow<TorrentHashesDesc>,
         location: impl AsRef<String>,
     ) -> Result<(), Error> {
@@ -741,6 +903,70 @@ impl QbitApi {
         Ok(())
     }
 
+    #[requires_mult_hashes]
+    /// ## Usage
+    /// Sets the save path of the torrents specified, where the finished torrents are stored.
+    /// 
+    /// ## Arguments
+    /// - path: the new save path.
+    ///
+    /// ## Errors
+    /// requires the WebAPI 2.8.4 (qbittorrent 4.4); older servers return [`ErrorType::UnsupportedByServer`].
+    pub async fn torrents_set_save_path(
+        &self,
+        hashes: impl Borrow<TorrentHashesDesc>,
+        path: impl Into<String>,
+    ) -> Result<(), Error> {
+        self.torrents_set_path("/torrents/setSavePath", hashes.borrow(), path.into())
+            .await
+    }
+
+    #[requires_mult_hashes]
+    /// ## Usage
+    /// Sets the download path of the torrents specified, where the torrents are stored while downloading.
+    /// 
+    /// ## Arguments
+    /// - path: the new download path.
+    ///
+    /// ## Errors
+    /// requires the WebAPI 2.8.4 (qbittorrent 4.4); older servers return [`ErrorType::UnsupportedByServer`].
+    pub async fn torrents_set_download_path(
+        &self,
+        hashes: impl Borrow<TorrentHashesDesc>,
+        path: impl Into<String>,
+    ) -> Result<(), Error> {
+        self.torrents_set_path("/torrents/setDownloadPath", hashes.borrow(), path.into())
+            .await
+    }
+
+    async fn torrents_set_path(
+        &self,
+        url: &str,
+        hashes: &TorrentHashesDesc,
+        path: String,
+    ) -> Result<(), Error> {
+        self.require_version(ApiVersion::V2_8_4).await?;
+
+        let mut hashmap = HashMap::new();
+
+        hashmap.insert("id", hashes.get_string("|"));
+        hashmap.insert("path", path);
+
+        self.make_request_with_form(url, "torrents_set_path", hashmap)
+            .await
+            .map_err(|e| match e.code {
+                Some(400) => Error::build(ErrorType::MiscError("the path is empty".to_string()), e.code),
+                Some(403) => Error::build(
+                    ErrorType::MiscError("user does not have write access to directory".to_string()),
+                    e.code,
+                ),
+                Some(404) => Error::build(ErrorType::TorrentHashNotFound, e.code),
+                Some(409) => Error::build(ErrorType::MiscError("unable to create the directory".to_string()), e.code),
+                _ => e,
+            })?;
+        Ok(())
+    }
+
     #[requires_hash]
     /// ## Usage
     /// Sets the name of the specified torrent to a specified name.
@@ -748,7 +974,7 @@ impl QbitApi {
     /// ## Arguments
     /// - new_name: the new name for the torrent
     pub async fn torrents_set_torrent_name(
-        &mut self,
+        &self,
         hash: impl Borrow<TorrentHash>,
         new_name: impl Into<String>,
     ) -> Result<(), Error> {
@@ -786,7 +1012,7 @@ impl QbitApi {
     /// ## Arguments
     /// - category_name: the name of the category.
     pub async fn torrents_set_torrent_category(
-        &mut self,
+        &self,
         hash: impl Borrow<TorrentHashesDesc>,
         category_name: impl Into<String>,
     ) -> Result<(), Error> {
@@ -840,7 +1066,7 @@ impl QbitApi {
     ///     }
     /// 
     /// }
-    pub async fn torrents_get_all_categories_raw(&mut self) -> Result<String, Error> {
+    pub async fn torrents_get_all_categories_raw(&self) -> Result<String, Error> {
         self.make_request("/torrents/categories", "torrents_get_all_categories_raw")
             .await
     }
@@ -868,7 +1094,7 @@ impl QbitApi {
     ///     }
     /// 
     /// }
-    pub async fn torrents_get_all_categories(&mut self) -> Result<Value, crate::Error> {
+    pub async fn torrents_get_all_categories(&self) -> Result<Value, crate::Error> {
         Ok(
             serde_json::from_str(self.torrents_get_all_categories_raw().await?.as_str())
                 .map_err(|e| Error::build(ErrorType::JsonSerdeError(Box::new(e)), None))?,
@@ -882,7 +1108,7 @@ impl QbitApi {
     /// - category_name: the name of the category.
     /// - save_path: the location to which torrents with this category should be downloaded. If `None`, the default one is assumed.
     pub async fn torrents_add_new_category(
-        &mut self,
+        &self,
         category_name: impl Into<String>,
         save_path: Option<impl Into<String>>,
     ) -> Result<(), Error> {
@@ -897,7 +1123,7 @@ impl QbitApi {
                 hashmap.insert("category", cat);
                 hashmap.insert("savePath", path.into());
                 request_error_focus!(
-                    self,
+                    non_idempotent self,
                     torrents_add_new_category,
                     "/torrents/createCategory",
                     hashmap,
@@ -916,7 +1142,7 @@ impl QbitApi {
             None => {
                 hashmap.insert("category", cat);
                 request_error_focus!(
-                    self,
+                    non_idempotent self,
                     torrents_add_new_category,
                     "/torrents/createCategory",
                     hashmap,
@@ -941,7 +1167,7 @@ impl QbitApi {
     /// - category_name: the name of the category.
     /// - save_path: the new location to which torrents with this category should be downloaded. If `None`, the default one is assumed.
     pub async fn torrents_edit_category(
-        &mut self,
+        &self,
         category_name: impl Into<String>,
         save_path: Option<impl Into<String>>,
     ) -> Result<(), Error> {
@@ -999,7 +1225,7 @@ impl QbitApi {
     /// ## Arguments
     /// - categories_name: the names of the categories to be deleted.
     pub async fn torrents_remove_categories<S: Into<String> + Clone>(
-        &mut self,
+        &self,
         categories_name: impl Borrow<Vec<S>>,
     ) -> Result<(), Error> {
         let mut hashmap = HashMap::new();
@@ -1028,7 +1254,7 @@ impl QbitApi {
     ///     "Tag 2",
     ///     ...
     /// ]
-    pub async fn torrents_get_all_tags_raw(&mut self) -> Result<String, Error> {
+    pub async fn torrents_get_all_tags_raw(&self) -> Result<String, Error> {
         self.make_request("/torrents/tags", "torrents_get_all_tags_raw")
             .await
     }
@@ -1041,7 +1267,7 @@ impl QbitApi {
     ///     "Tag 2",
     ///     ...
     /// ]
-    pub async fn torrents_get_all_tags(&mut self) -> Result<Value, crate::Error> {
+    pub async fn torrents_get_all_tags(&self) -> Result<Value, crate::Error> {
         Ok(
             serde_json::from_str(self.torrents_get_all_tags_raw().await?.as_str())
                 .map_err(|e| Error::build(ErrorType::JsonSerdeError(Box::new(e)), None))?,
@@ -1051,7 +1277,7 @@ impl QbitApi {
     /// ## Usage
     /// Adds new tags.
     pub async fn torrents_add_new_tags<S: Into<String> + Clone>(
-        &mut self,
+        &self,
         tags_name: impl Borrow<Vec<S>>,
     ) -> Result<(), Error> {
         let mut hashmap: HashMap<&str, String> = HashMap::new();
@@ -1073,7 +1299,7 @@ impl QbitApi {
     /// ## Usage
     /// Removes existing tags.
     pub async fn torrents_remove_tags<S: Into<String> + Clone>(
-        &mut self,
+        &self,
         tags_name: impl Borrow<Vec<S>>,
     ) -> Result<(), Error> {
         let mut hashmap: HashMap<&str, String> = HashMap::new();
@@ -1092,6 +1318,74 @@ impl QbitApi {
         Ok(())
     }
 
+    #[requires_mult_hashes]
+    /// ## Usage
+    /// Adds tags to the specified torrents. tags that don't exist yet are created.
+    pub async fn torrents_add_tags<S: Into<String> + Clone>(
+        &self,
+        hashes: impl Borrow<TorrentHashesDesc>,
+        tags_name: impl Borrow<Vec<S>>,
+    ) -> Result<(), Error> {
+        self.torrents_tags_request("/torrents/addTags", "torrents_add_tags", hashes.borrow(), tags_name.borrow())
+            .await
+    }
+
+    #[requires_mult_hashes]
+    /// ## Usage
+    /// Removes tags from the specified torrents. the tags themselves aren't deleted, see `torrents_remove_tags` for that.
+    ///
+    /// if `tags_name` is empty, every tag is removed from the torrents.
+    pub async fn torrents_remove_tags_from_torrents<S: Into<String> + Clone>(
+        &self,
+        hashes: impl Borrow<TorrentHashesDesc>,
+        tags_name: impl Borrow<Vec<S>>,
+    ) -> Result<(), Error> {
+        self.torrents_tags_request(
+            "/torrents/removeTags",
+            "torrents_remove_tags_from_torrents",
+            hashes.borrow(),
+            tags_name.borrow(),
+        )
+        .await
+    }
+
+    #[requires_mult_hashes]
+    /// ## Usage
+    /// Replaces the tags of the specified torrents with `tags_name`. tags that don't exist yet are created.
+    ///
+    /// ## Errors
+    /// requires the WebAPI 2.11.4 (qbittorrent 5.1); older servers return [`ErrorType::UnsupportedByServer`].
+    pub async fn torrents_set_tags<S: Into<String> + Clone>(
+        &self,
+        hashes: impl Borrow<TorrentHashesDesc>,
+        tags_name: impl Borrow<Vec<S>>,
+    ) -> Result<(), Error> {
+        self.require_version(ApiVersion::V2_11_4).await?;
+
+        self.torrents_tags_request("/torrents/setTags", "torrents_set_tags", hashes.borrow(), tags_name.borrow())
+            .await
+    }
+
+    async fn torrents_tags_request<S: Into<String> + Clone>(
+        &self,
+        url: &str,
+        custom_error: &str,
+        hashes: &TorrentHashesDesc,
+        tags_name: &[S],
+    ) -> Result<(), Error> {
+        let mut hashmap: HashMap<&str, String> = HashMap::new();
+        let sep_vec: SepVec<String, &str> = SepVec::new(
+            tags_name.iter().map(|k| Into::<String>::into(k.clone())),
+            ",",
+        );
+        hashmap.insert("hashes", hashes.get_string("|"));
+        hashmap.insert("tags", sep_vec.to_string());
+
+        self.make_request_with_form(url, custom_error, hashmap).await?;
+
+        Ok(())
+    }
+
     #[requires_mult_hashes]
     /// ## Usage
     /// Sets the automatic torrent management as enabled or not for the specified torrents.
@@ -1099,7 +1393,7 @@ impl QbitApi {
     /// ## Arguments
     /// - enabled: whether the automatic torrent management should be set as enabled or not.
     pub async fn torrents_set_automatic_torrents_management(
-        &mut self,
+        &self,
         hashes: impl Borrow<TorrentHashesDesc>,
         enabled: bool,
     ) -> Result<(), Error> {
@@ -1121,7 +1415,7 @@ impl QbitApi {
     /// ## Usage
     /// Toggles the sequential download for the specified torrents.
     pub async fn torrents_toggle_sequential_download(
-        &mut self,
+        &self,
         hashes: impl Borrow<TorrentHashesDesc>,
     ) -> Result<(), Error> {
         let url = url!(
@@ -1136,7 +1430,7 @@ impl QbitApi {
             )
         );
 
-        self.make_request(url, "torrents_toggle_sequential_download")
+        self.make_request_non_idempotent(url, "torrents_toggle_sequential_download")
             .await?;
         Ok(())
     }
@@ -1145,7 +1439,7 @@ impl QbitApi {
     /// ## Usage
     /// Toggles the  first/last piece priority for the specified torrents.
     pub async fn torrents_toggle_first_last_piece_priority(
-        &mut self,
+        &self,
         hashes: impl Borrow<TorrentHashesDesc>,
     ) -> Result<(), Error> {
         let url = url!(
@@ -1160,7 +1454,7 @@ impl QbitApi {
             )
         );
 
-        self.make_request(url, "set_first_last_piece_priority")
+        self.make_request_non_idempotent(url, "set_first_last_piece_priority")
             .await?;
         Ok(())
     }
@@ -1172,7 +1466,7 @@ impl QbitApi {
     /// ## Arguments
     /// - enabled: whether force start should be set as enabled or not.
     pub async fn torrents_set_force_start(
-        &mut self,
+        &self,
         hashes: impl Borrow<TorrentHashesDesc>,
         enabled: bool,
     ) -> Result<(), Error> {
@@ -1196,7 +1490,7 @@ impl QbitApi {
     /// ## Arguments
     /// - enabled: whether super seeding should be set as enabled or not.
     pub async fn torrents_set_super_seeding(
-        &mut self,
+        &self,
         hashes: impl Borrow<TorrentHashesDesc>,
         enabled: bool,
     ) -> Result<(), Error> {
@@ -1221,11 +1515,13 @@ impl QbitApi {
     /// - old_path: The old path of the torrent.
     /// - new_path: The new path to use for the file.
     pub async fn torrents_rename_file(
-        &mut self,
+        &self,
         hash: impl Borrow<TorrentHash>,
         old_path: impl Into<String>,
         new_path: impl Into<String>,
     ) -> Result<(), Error> {
+        self.require_version(ApiVersion::V2_7_0).await?;
+
         let mut hashmap = HashMap::new();
 
         hashmap.insert("hash", hash.get_hash());
@@ -1233,7 +1529,7 @@ impl QbitApi {
         hashmap.insert("newPath", new_path.into());
 
         request_error_focus!(
-            self,
+            non_idempotent self,
             torrents_rename_file,
             "/torrents/renameFile",
             hashmap,
@@ -1260,11 +1556,13 @@ impl QbitApi {
     /// - old_path: The old path of the torrent.
     /// - new_path: The new path to use for the file.
     pub async fn torrents_rename_folder(
-        &mut self,
+        &self,
         hash: impl Borrow<TorrentHash>,
         old_path: impl Into<String>,
         new_path: impl Into<String>,
     ) -> Result<(), Error> {
+        self.require_version(ApiVersion::V2_7_0).await?;
+
         let mut hashmap = HashMap::new();
 
         hashmap.insert("hash", hash.get_hash());
@@ -1272,7 +1570,7 @@ impl QbitApi {
         hashmap.insert("newPath", new_path.into());
 
         request_error_focus!(
-            self,
+            non_idempotent self,
             torrents_rename_folder,
             "/torrents/renameFolder",
             hashmap,
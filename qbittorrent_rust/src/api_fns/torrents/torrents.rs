@@ -1,7 +1,13 @@
+use std::path::Path;
+
+use crate::Error;
+
+use super::{magnet::MagnetLink, metainfo::Metainfo};
+
 
 /// ## Info
 /// Describes the type of the torrent, either: 
-/// a URL, such as a magnet, but any URL is accepted;
+/// a URL, such as a magnet, but any URL is accepted (see [`MagnetLink`] to parse or build magnets);
 /// a torrent file (.torrent), it represents a file path.
 #[derive(Debug, Clone)]
 pub enum TorrentType<S: Clone + Into<String>> {
@@ -13,6 +19,7 @@ pub enum TorrentType<S: Clone + Into<String>> {
 pub(crate) enum TorrentInner {
     Url(String),
     RawTorrent(String),
+    Metainfo(Box<Metainfo>),
 }
 
 /// ## Info
@@ -35,7 +42,52 @@ impl Torrent {
         }
     }
 
+    /// ## Usage
+    /// creates a new [`Torrent`] from an already parsed `.torrent` file. its content is uploaded as is.
+    pub fn from_metainfo(metainfo: Metainfo) -> Self {
+        Self{inner: TorrentInner::Metainfo(Box::new(metainfo))}
+    }
+
+    /// ## Usage
+    /// creates a new [`Torrent`] from a magnet link.
+    pub fn from_magnet(magnet: MagnetLink) -> Self {
+        Self{inner: TorrentInner::Url(magnet.to_string())}
+    }
+
+    /// ## Usage
+    /// reads and parses a `.torrent` file, so that an invalid file is found now instead of when adding it.
+    ///
+    /// ## Errors
+    /// the same as [`Metainfo::from_file`].
+    pub async fn from_torrent_file(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Ok(Self::from_metainfo(Metainfo::from_file(path).await?))
+    }
+
+    /// ## Usage
+    /// the parsed `.torrent` file, if the [`Torrent`] was made from one.
+    pub fn metainfo(&self) -> Option<&Metainfo> {
+        match &self.inner {
+            TorrentInner::Metainfo(metainfo) => Some(metainfo),
+            _ => None,
+        }
+    }
+
+    /// ## Usage
+    /// the magnet link, if the [`Torrent`] was made from one.
+    pub fn magnet_link(&self) -> Option<MagnetLink> {
+        match &self.inner {
+            TorrentInner::Url(url) => url.parse().ok(),
+            _ => None,
+        }
+    }
+
     pub(crate) fn get_inner(&self) -> TorrentInner {
         self.inner.clone()
     }
-}
\ No newline at end of file
+}
+
+impl From<MagnetLink> for Torrent {
+    fn from(magnet: MagnetLink) -> Self {
+        Self::from_magnet(magnet)
+    }
+}
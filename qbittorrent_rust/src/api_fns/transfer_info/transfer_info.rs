@@ -33,7 +33,7 @@ impl QbitApi {
 
     /// ## Usage
     /// Returns `1` if the alternative speed limits are enabled, `0` if they're not.
-    pub async fn transfer_get_alternative_speed_limits(&mut self) -> Result<u8, crate::Error> {
+    pub async fn transfer_get_alternative_speed_limits(&self) -> Result<u8, crate::Error> {
         let resp = Self::make_request(
             self,
             "/transfer/speedLimitsMode",
@@ -50,6 +50,7 @@ impl QbitApi {
     }
 
     post_request_no_return! {
+        non_idempotent
         /// ## Usage
         /// Toggles the alternative speed limits.
         transfer_toggle_alternative_speed_limits,
@@ -58,7 +59,7 @@ impl QbitApi {
 
     /// ## Usage
     /// Gets the global download limit in bytes per second; this value will be 0 if there's no limit.
-    pub async fn transfer_get_global_download_limit(&mut self) -> Result<u64, crate::Error> {
+    pub async fn transfer_get_global_download_limit(&self) -> Result<u64, crate::Error> {
         let str = Self::make_request(
             self,
             "/transfer/downloadLimit",
@@ -72,7 +73,7 @@ impl QbitApi {
 
     /// ## Usage
     /// Gets the global upload limit in bytes per second; this value will be 0 if there's no limit.
-    pub async fn transfer_get_global_upload_limit(&mut self) -> Result<u64, crate::Error> {
+    pub async fn transfer_get_global_upload_limit(&self) -> Result<u64, crate::Error> {
         let str = Self::make_request(
             self,
             "/transfer/uploadLimit",
@@ -90,7 +91,7 @@ impl QbitApi {
     /// ## Arguments
     /// - peers: a [`Vec`] of strings, where each element is structured as `host:port`
     pub async fn transfer_ban_peers<S: Into<String>>(
-        &mut self,
+        &self,
         peers: Vec<S>,
     ) -> Result<(), Error> {
         let x = SepVec::new(
@@ -118,7 +119,7 @@ impl QbitApi {
 
     /// ## Usage
     /// Gets the global transfer info as a json [`Value`].
-    pub async fn transfer_get_global_transfer_info(&mut self) -> Result<Value, Error> {
+    pub async fn transfer_get_global_transfer_info(&self) -> Result<Value, Error> {
         serde_json::from_str(
             Self::transfer_get_global_transfer_info_raw(self)
                 .await?
@@ -1,15 +1,23 @@
-use reqwest::{header::COOKIE, Client};
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue, COOKIE},
+    Certificate, Client, Proxy, RequestBuilder, Response, StatusCode,
+};
 use serde::Serialize;
-#[allow(unused_imports)]
 use serde_json::Value;
-use std::{collections::HashMap, hash::Hash, sync::Arc};
-use tokio::sync::{Mutex, RwLock};
+use std::{collections::HashMap, hash::Hash, sync::Arc, time::Duration};
+use tokio::sync::RwLock;
 
 use crate::{
     code, core::cookie::Cookie, error_handling::error_type::ErrorType, post_request_no_return,
 };
 
-use super::creds::Credentials;
+use super::{
+    auth::Auth,
+    cassette::{Cassette, CassetteMode},
+    rate_limit::{Limiter, RateLimit},
+    retry::RetryPolicy,
+    version::ApiVersion,
+};
 use crate::error_handling::errors::Error;
 
 ///## Description
@@ -23,13 +31,20 @@ use crate::error_handling::errors::Error;
 /// - if the method ends with `raw`, it means it'll return the raw json [`String`] from the response.
 /// - if the method doesn't have anything at its end, (or has `json` at the end, for cases where further clarity is needed), it'll return a serde_json [`Value`].
 /// - if it ends in any other way, it returns a custom type that represents that json (or parts of it) in a particular way.
+///
+/// ## Sharing
+/// every API method takes `&self`, and the session is shared between clones:
+/// a single [`QbitApi`] can be put in an [`Arc`] and used by many tasks at once.
 #[derive(Debug, Clone)]
 pub struct QbitApi {
     pub(crate) authority: String,
     pub(crate) cookie: Arc<RwLock<Cookie>>,
     pub(crate) reqwest_client: Client,
-    credentials: Credentials,
-    cookie_hold: Arc<Mutex<bool>>,
+    auth: Auth,
+    retry_policy: RetryPolicy,
+    limiter: Arc<Limiter>,
+    cassette: Option<Arc<Cassette>>,
+    web_api_version: Arc<RwLock<Option<ApiVersion>>>,
 }
 
 impl QbitApi {
@@ -40,80 +55,255 @@ impl QbitApi {
     /// ## Arguments
     ///
     /// authority: the authority for the Qbittorrent WebUI API. eg: `"http://localhost:6011/"`
-    /// credentials: the credentials to the account.
+    /// auth: how to authenticate; either an [`Auth`], or the [`Credentials`](super::creds::Credentials) to the account.
     ///
     /// ## Example
-    /// ```
+    /// ```no_run
+    /// # use qbittorrent_rust::core::{api::QbitApi, auth::Auth, creds::Credentials};
+    /// # async fn example() {
     /// let qbit_api = QbitApi::new("http://localhost:6011/", Credentials::new("user_name", "password")).await.unwrap();
+    /// let local_qbit_api = QbitApi::new("http://localhost:6011/", Auth::Bypass).await.unwrap();
+    /// # }
     /// ```
-    pub async fn new(authority: impl AsRef<str>, credentials: Credentials) -> Result<Self, Error> {
-        let s = authority.as_ref();
-        let authority = Into::<String>::into(s) as String;
-        let authority = authority
-            .chars()
-            .rev()
-            .skip_while(|s| *s as u8 == 47)
-            .clone()
-            .map(|k| k.to_string())
-            .collect::<Vec<String>>()
-            .into_iter()
-            .rev()
-            .collect::<String>();
-        let reqwest_client = Client::new();
-        let cookie = Arc::new(RwLock::new(
-            Cookie::new(&authority, &reqwest_client, &credentials).await?,
-        ));
-        return Ok(QbitApi {
-            authority,
-            cookie,
-            reqwest_client,
-            credentials,
-            cookie_hold: Arc::new(Mutex::new(false)),
-        });
-    }
-
-    pub(crate) async fn get_cookie(&mut self) -> Result<String, Error> {
-        let read_lock = self.cookie.read().await;
-
-        let res: String;
-
-        if read_lock.is_expired() {
-            let hold_cookie = self.cookie_hold.clone();
-            let mut hold_cookie = hold_cookie.lock().await;
-            if read_lock.is_expired() && *hold_cookie == false {
-                *hold_cookie = true;
-                drop(read_lock);
-                let cookie = self.cookie.clone();
-                let mut cookie = cookie.write().await;
-                cookie
-                    .reset(&self.authority, &self.reqwest_client, &self.credentials)
-                    .await?;
-                res = (&*cookie.cookie).to_string();
-                drop(cookie);
-                *hold_cookie = false;
-            } else {
-                res = self.cookie.clone().read().await.cookie.clone();
+    pub async fn new(authority: impl AsRef<str>, auth: impl Into<Auth>) -> Result<Self, Error> {
+        Self::builder(authority).auth(auth).build().await
+    }
+
+    /// ## Usage
+    ///
+    /// creates a [`QbitApiBuilder`], to customize the HTTP client (timeouts, TLS, proxy...) before connecting.
+    ///
+    /// ## Example
+    /// ```no_run
+    /// # use std::time::Duration;
+    /// # use qbittorrent_rust::core::{api::QbitApi, creds::Credentials};
+    /// # async fn example() {
+    /// let qbit_api = QbitApi::builder("https://qbit.example.com/")
+    ///     .auth(Credentials::new("user_name", "password"))
+    ///     .timeout(Duration::from_secs(30))
+    ///     .build()
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    pub fn builder(authority: impl AsRef<str>) -> QbitApiBuilder {
+        QbitApiBuilder::new(authority)
+    }
+
+    /// ## Usage
+    /// reads `web_ui_session_timeout` from the app preferences, so that the session is renewed before the server expires it.
+    ///
+    /// this is already done by [`QbitApi::new`]; call it again if the setting was changed outside of this [`QbitApi`].
+    pub async fn refresh_session_timeout(&self) -> Result<(), Error> {
+        let preferences = self.app_get_preferences().await?;
+
+        if let Some(secs) = preferences
+            .get("web_ui_session_timeout")
+            .and_then(Value::as_u64)
+        {
+            self.set_session_timeout(secs).await;
+        }
+
+        Ok(())
+    }
+
+    /// ## Usage
+    /// the WebAPI version of the server, read when connecting. `None` if it couldn't be read.
+    pub async fn web_api_version(&self) -> Option<ApiVersion> {
+        *self.web_api_version.read().await
+    }
+
+    /// ## Usage
+    /// reads the WebAPI version of the server again, eg: after qbittorrent was updated.
+    ///
+    /// this is already done by [`QbitApi::new`].
+    pub async fn refresh_web_api_version(&self) -> Result<ApiVersion, Error> {
+        let version: ApiVersion = self.app_web_api_version().await?.parse()?;
+        *self.web_api_version.write().await = Some(version);
+
+        Ok(version)
+    }
+
+    /// ## Usage
+    /// returns `true` if the server is known to have at least the `required` WebAPI version.
+    pub async fn supports(&self, required: ApiVersion) -> bool {
+        self.web_api_version().await.is_some_and(|version| version >= required)
+    }
+
+    /// fails with [`ErrorType::UnsupportedByServer`] if the server is known to be older than `required`.
+    /// when the version is unknown, the request is left to the server.
+    pub(crate) async fn require_version(&self, required: ApiVersion) -> Result<(), Error> {
+        match self.web_api_version().await {
+            Some(actual) if actual < required => Err(Error::build(
+                ErrorType::UnsupportedByServer { required, actual },
+                None,
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// ## Usage
+    /// sets which failed requests are retried, and how. see [`RetryPolicy`].
+    pub fn set_retry_policy(&mut self, retry_policy: RetryPolicy) {
+        self.retry_policy = retry_policy;
+    }
+
+    pub(crate) async fn set_session_timeout(&self, secs: u64) {
+        self.cookie
+            .write()
+            .await
+            .set_session_timeout(Duration::from_secs(secs));
+    }
+
+    pub(crate) async fn get_cookie(&self) -> Result<Option<String>, Error> {
+        let cookie = self.cookie.read().await;
+        if !cookie.is_expired() {
+            return Ok(cookie.cookie.clone());
+        }
+        drop(cookie);
+
+        // `reset` checks the expiration again, since another task may have renewed the session in the meantime.
+        let mut cookie = self.cookie.write().await;
+        cookie
+            .reset(&self.authority, &self.reqwest_client, &self.auth)
+            .await?;
+
+        Ok(cookie.cookie.clone())
+    }
+
+    /// logs in again, unless another request already replaced the `rejected` cookie in the meantime.
+    async fn renew_cookie(&self, rejected: &Option<String>) -> Result<Option<String>, Error> {
+        let mut cookie = self.cookie.write().await;
+
+        if cookie.cookie == *rejected {
+            cookie
+                .force_reset(&self.authority, &self.reqwest_client, &self.auth)
+                .await?;
+        }
+
+        Ok(cookie.cookie.clone())
+    }
+
+    /// sends the request made by `build` with the session cookie attached, retrying according to the [`RetryPolicy`].
+    ///
+    /// if the server answers with `403 Forbidden` and refuses the session too (eg: it timed out early, or qbittorrent was restarted),
+    /// logs in again and retries once, if the [`Auth`] allows logging in at all.
+    /// `build` is called once per attempt, since a sent request can't be reused.
+    pub(crate) async fn send_with_reauth<F: Fn() -> RequestBuilder>(
+        &self,
+        build: F,
+    ) -> Result<Response, Error> {
+        self.send_with_retry(build, true).await
+    }
+
+    /// same as `send_with_reauth`, for requests that mustn't be repeated unless the [`RetryPolicy`] allows it.
+    pub(crate) async fn send_non_idempotent<F: Fn() -> RequestBuilder>(
+        &self,
+        build: F,
+    ) -> Result<Response, Error> {
+        self.send_with_retry(build, false).await
+    }
+
+    /// `send_with_reauth` or `send_non_idempotent`, depending on `idempotent`.
+    pub(crate) async fn send_with_retry<F: Fn() -> RequestBuilder>(
+        &self,
+        build: F,
+        idempotent: bool,
+    ) -> Result<Response, Error> {
+        let retry_policy = self.retry_policy.clone();
+        let attempts = retry_policy.attempts_for(idempotent);
+
+        let mut attempt = 1;
+        loop {
+            let result = self.send_once(&build).await;
+
+            if attempt >= attempts || !retry_policy.should_retry(&result) {
+                return result;
             }
-            drop(hold_cookie);
-        } else {
-            drop(read_lock);
-            res = self.cookie.clone().read().await.cookie.clone();
+
+            tokio::time::sleep(retry_policy.delay(attempt)).await;
+            attempt += 1;
+        }
+    }
+
+    async fn send_once<F: Fn() -> RequestBuilder>(&self, build: &F) -> Result<Response, Error> {
+        let sid = self.get_cookie().await?;
+
+        let response = self.send_with_sid(build(), &sid).await?;
+
+        if response.status() != StatusCode::FORBIDDEN || !self.cookie.read().await.is_renewable() {
+            return Ok(response);
+        }
+
+        // some endpoints answer 403 for their own reasons, eg: setSavePath without write access to the directory.
+        // sending those again with a new session would only repeat them, so the session is checked first.
+        if self.session_is_accepted(&sid).await? {
+            return Ok(response);
+        }
+
+        let sid = self.renew_cookie(&sid).await?;
+
+        self.send_with_sid(build(), &sid).await
+    }
+
+    /// whether the server still accepts the session `sid`, asking it for something that it only refuses without one.
+    async fn session_is_accepted(&self, sid: &Option<String>) -> Result<bool, Error> {
+        let url = format!("{}/api/v2/app/version", self.authority);
+        let response = self.send_with_sid(self.reqwest_client.post(&url), sid).await?;
+
+        Ok(response.status() != StatusCode::FORBIDDEN)
+    }
+
+    async fn send_with_sid(
+        &self,
+        builder: RequestBuilder,
+        sid: &Option<String>,
+    ) -> Result<Response, Error> {
+        let mut builder = self.auth.apply(builder);
+
+        if let Some(sid) = sid {
+            builder = builder.header(COOKIE, format!("SID={}", sid));
         }
-        Ok(res)
+
+        let permit = self.limiter.acquire().await;
+        let response = match &self.cassette {
+            Some(cassette) => cassette.send(&self.reqwest_client, builder).await,
+            None => builder
+                .send()
+                .await
+                .map_err(|e| Error::build(ErrorType::ReqwestError(Box::new(e)), None)),
+        };
+        drop(permit);
+
+        response
     }
 
     pub(crate) async fn make_request<T: Into<String>, S: Into<String>>(
-        &mut self,
+        &self,
         url: T,
         custom_error: S,
     ) -> Result<String, crate::Error> {
-        let resp = self
-            .reqwest_client
-            .post(format!("{}/api/v2{}", self.authority, url.into()))
-            .header(COOKIE, format!("SID={}", self.get_cookie().await?))
-            .send()
-            .await
-            .map_err(|e| Error::build(ErrorType::ReqwestError(Box::new(e)), None))?;
+        self.post(url, custom_error, true).await
+    }
+
+    /// same as `make_request`, for requests that mustn't be repeated unless the [`RetryPolicy`] allows it,
+    /// eg: the ones toggling a setting.
+    pub(crate) async fn make_request_non_idempotent<T: Into<String>, S: Into<String>>(
+        &self,
+        url: T,
+        custom_error: S,
+    ) -> Result<String, crate::Error> {
+        self.post(url, custom_error, false).await
+    }
+
+    async fn post<T: Into<String>, S: Into<String>>(
+        &self,
+        url: T,
+        custom_error: S,
+        idempotent: bool,
+    ) -> Result<String, crate::Error> {
+        let url = format!("{}/api/v2{}", self.authority, url.into());
+        let resp = self.send_with_retry(|| self.reqwest_client.post(&url), idempotent).await?;
 
         if resp.status().is_success() {
             let text = resp
@@ -140,22 +330,48 @@ impl QbitApi {
         N: Into<String>,
         X: Into<String>,
     >(
-        &mut self,
+        &self,
+        url: N,
+        custom_error: X,
+        hashmap: HashMap<T, S, U>,
+    ) -> Result<String, crate::Error> {
+        self.post_form(url, custom_error, hashmap, true).await
+    }
+
+    /// same as `make_request_with_form`, for requests that mustn't be repeated unless the [`RetryPolicy`] allows it,
+    /// eg: the ones creating something, which a lost response would make happen twice.
+    pub(crate) async fn make_request_with_form_non_idempotent<
+        T: std::cmp::Eq + Hash + Serialize,
+        S: Serialize,
+        U,
+        N: Into<String>,
+        X: Into<String>,
+    >(
+        &self,
+        url: N,
+        custom_error: X,
+        hashmap: HashMap<T, S, U>,
+    ) -> Result<String, crate::Error> {
+        self.post_form(url, custom_error, hashmap, false).await
+    }
+
+    async fn post_form<
+        T: std::cmp::Eq + Hash + Serialize,
+        S: Serialize,
+        U,
+        N: Into<String>,
+        X: Into<String>,
+    >(
+        &self,
         url: N,
         custom_error: X,
         hashmap: HashMap<T, S, U>,
+        idempotent: bool,
     ) -> Result<String, crate::Error> {
+        let url = format!("{}/api/v2{}", self.authority, url.into());
         let response = self
-            .reqwest_client
-            .post(format!("{}/api/v2{}", self.authority, url.into()))
-            .header(
-                reqwest::header::COOKIE,
-                format!("SID={}", self.get_cookie().await?),
-            )
-            .form(&hashmap)
-            .send()
-            .await
-            .map_err(|e| Error::build(ErrorType::ReqwestError(Box::new(e)), None))?;
+            .send_with_retry(|| self.reqwest_client.post(&url).form(&hashmap), idempotent)
+            .await?;
 
         if response.status().is_success() {
             let text = response
@@ -178,22 +394,15 @@ impl QbitApi {
         N: Into<String>,
         X: Into<String>,
     >(
-        &mut self,
+        &self,
         url: N,
         custom_error: X,
         hashmap: HashMap<T, S, U>,
     ) -> Result<String, crate::Error> {
+        let url = format!("{}/api/v2{}", self.authority, url.into());
         let response = self
-            .reqwest_client
-            .post(format!("{}/api/v2{}", self.authority, url.into()))
-            .header(
-                reqwest::header::COOKIE,
-                format!("SID={}", self.get_cookie().await?),
-            )
-            .form(&hashmap)
-            .send()
-            .await
-            .map_err(|e| Error::build(ErrorType::ReqwestError(Box::new(e)), None))?;
+            .send_with_reauth(|| self.reqwest_client.post(&url).form(&hashmap))
+            .await?;
 
         // Handle the response
         if response.status().is_success() {
@@ -220,3 +429,209 @@ impl QbitApi {
 
     post_request_no_return!(logout, "/auth/logout");
 }
+
+/// ## Description
+/// builds a [`QbitApi`], optionally configuring the HTTP client it uses.
+///
+/// either pass a ready-made [`Client`] with `client`, or use the other methods to configure the one the builder makes;
+/// when a [`Client`] is passed, the other client settings are ignored.
+#[derive(Debug)]
+pub struct QbitApiBuilder {
+    authority: String,
+    auth: Auth,
+    client: Option<Client>,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    accept_invalid_certs: bool,
+    root_certificates: Vec<Certificate>,
+    proxy: Option<Proxy>,
+    user_agent: Option<String>,
+    default_headers: HeaderMap,
+    header_error: Option<String>,
+    retry_policy: RetryPolicy,
+    rate_limit: RateLimit,
+    cassette: Option<CassetteMode>,
+}
+
+impl QbitApiBuilder {
+    /// # Usage
+    /// creates a new instance of [`QbitApiBuilder`]. the authentication defaults to [`Auth::Bypass`].
+    pub fn new(authority: impl AsRef<str>) -> Self {
+        Self {
+            authority: trim_authority(authority.as_ref()),
+            auth: Auth::Bypass,
+            client: None,
+            timeout: None,
+            connect_timeout: None,
+            accept_invalid_certs: false,
+            root_certificates: Vec::new(),
+            proxy: None,
+            user_agent: None,
+            default_headers: HeaderMap::new(),
+            header_error: None,
+            retry_policy: RetryPolicy::none(),
+            rate_limit: RateLimit::new(),
+            cassette: None,
+        }
+    }
+
+    /// sets how to authenticate; either an [`Auth`], or the [`Credentials`](super::creds::Credentials) to the account.
+    pub fn auth(mut self, auth: impl Into<Auth>) -> Self {
+        self.auth = auth.into();
+        self
+    }
+
+    /// uses an already configured [`Client`]. any other client setting of the builder is ignored.
+    pub fn client(mut self, client: Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// sets the timeout of each request, from when it's sent until the response body is read.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// sets the timeout for connecting to the server.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// accepts any TLS certificate, including self-signed and expired ones.
+    ///
+    /// # WARNING
+    /// this makes the connection vulnerable to man-in-the-middle attacks. prefer `add_root_certificate` where possible.
+    pub fn danger_accept_invalid_certs(mut self, accept: bool) -> Self {
+        self.accept_invalid_certs = accept;
+        self
+    }
+
+    /// trusts an additional root certificate, eg: the one of a private CA.
+    pub fn add_root_certificate(mut self, certificate: Certificate) -> Self {
+        self.root_certificates.push(certificate);
+        self
+    }
+
+    /// sends every request through a proxy. both HTTP(S) and SOCKS5 proxies are supported, eg: `Proxy::all("socks5://127.0.0.1:1080")`.
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// sets the `User-Agent` header sent with every request.
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// adds a header sent with every request. an invalid name or value makes `build` fail.
+    pub fn default_header(mut self, name: impl AsRef<str>, value: impl AsRef<str>) -> Self {
+        match (
+            HeaderName::from_bytes(name.as_ref().as_bytes()),
+            HeaderValue::from_str(value.as_ref()),
+        ) {
+            (Ok(name), Ok(value)) => {
+                self.default_headers.insert(name, value);
+            }
+            _ => {
+                self.header_error
+                    .get_or_insert(format!("invalid default header: {}", name.as_ref()));
+            }
+        }
+        self
+    }
+
+    /// sets which failed requests are retried, and how. defaults to [`RetryPolicy::none`].
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// limits how fast and how many requests are sent, across the [`QbitApi`] and all of its clones. see [`RateLimit`].
+    pub fn rate_limit(mut self, rate_limit: RateLimit) -> Self {
+        self.rate_limit = rate_limit;
+        self
+    }
+
+    /// records every HTTP exchange to a cassette file, or replays them from one. see [`CassetteMode`].
+    pub fn cassette(mut self, mode: CassetteMode) -> Self {
+        self.cassette = Some(mode);
+        self
+    }
+
+    /// # Usage
+    /// builds the [`QbitApi`], authenticating to the server.
+    pub async fn build(self) -> Result<QbitApi, Error> {
+        if let Some(e) = self.header_error {
+            return Err(Error::build(ErrorType::MiscError(e), None));
+        }
+
+        let reqwest_client = match self.client {
+            Some(client) => client,
+            None => {
+                let mut client = Client::builder()
+                    .danger_accept_invalid_certs(self.accept_invalid_certs)
+                    .default_headers(self.default_headers);
+
+                if let Some(timeout) = self.timeout {
+                    client = client.timeout(timeout);
+                }
+
+                if let Some(timeout) = self.connect_timeout {
+                    client = client.connect_timeout(timeout);
+                }
+
+                for certificate in self.root_certificates {
+                    client = client.add_root_certificate(certificate);
+                }
+
+                if let Some(proxy) = self.proxy {
+                    client = client.proxy(proxy);
+                }
+
+                if let Some(user_agent) = self.user_agent {
+                    client = client.user_agent(user_agent);
+                }
+
+                client
+                    .build()
+                    .map_err(|e| Error::build(ErrorType::ReqwestError(Box::new(e)), None))?
+            }
+        };
+
+        let cassette = match self.cassette {
+            Some(mode) => Some(Arc::new(Cassette::new(mode, &self.authority).await?)),
+            None => None,
+        };
+
+        let cookie = Arc::new(RwLock::new(
+            Cookie::new(&self.authority, &reqwest_client, &self.auth, cassette.clone()).await?,
+        ));
+        let api = QbitApi {
+            authority: self.authority,
+            cookie,
+            reqwest_client,
+            auth: self.auth,
+            retry_policy: self.retry_policy,
+            limiter: Arc::new(Limiter::new(self.rate_limit)),
+            cassette,
+            web_api_version: Arc::new(RwLock::new(None)),
+        };
+
+        // an unknown version only means the oldest endpoints are used.
+        let _ = api.refresh_web_api_version().await;
+
+        // not being able to read the preferences isn't fatal: the default timeout is kept,
+        // and a session the server rejects early gets renewed anyway.
+        let _ = api.refresh_session_timeout().await;
+
+        Ok(api)
+    }
+}
+
+/// removes the trailing slashes of the authority, since every path starts with one.
+fn trim_authority(authority: &str) -> String {
+    authority.trim_end_matches('/').to_string()
+}
@@ -0,0 +1,105 @@
+use reqwest::RequestBuilder;
+
+use super::creds::Credentials;
+
+/// ## Description
+/// describes how a [`QbitApi`](super::api::QbitApi) authenticates to the qbittorrent WebUI API.
+///
+/// a [`Credentials`] can be used wherever an [`Auth`] is expected, and becomes [`Auth::Credentials`].
+#[derive(Debug, Clone)]
+pub enum Auth {
+    /// logs in with a username and a password, renewing the session when needed.
+    Credentials(Credentials),
+    /// doesn't authenticate at all: for instances with `bypass_local_auth` or `bypass_auth_subnet_whitelist` enabled.
+    Bypass,
+    /// uses a session id (`SID` cookie) obtained elsewhere.
+    /// the session can't be renewed, so requests will fail once the server expires it.
+    Sid(String),
+    /// for instances behind an authenticating reverse proxy.
+    Proxy(ProxyAuth),
+}
+
+impl Auth {
+    /// the credentials used to log into qbittorrent, if this kind of authentication logs in at all.
+    pub(crate) fn credentials(&self) -> Option<&Credentials> {
+        match self {
+            Auth::Credentials(credentials) => Some(credentials),
+            Auth::Proxy(proxy) => proxy.credentials.as_ref(),
+            Auth::Bypass | Auth::Sid(_) => None,
+        }
+    }
+
+    /// adds whatever the reverse proxy (if any) needs to let the request through.
+    pub(crate) fn apply(&self, builder: RequestBuilder) -> RequestBuilder {
+        match self {
+            Auth::Proxy(proxy) => proxy.apply(builder),
+            _ => builder,
+        }
+    }
+}
+
+impl From<Credentials> for Auth {
+    fn from(credentials: Credentials) -> Self {
+        Auth::Credentials(credentials)
+    }
+}
+
+/// ## Description
+/// represents the authentication to a reverse proxy in front of the qbittorrent WebUI.
+///
+/// ## Example
+/// ```
+/// # use qbittorrent_rust::core::{auth::ProxyAuth, creds::Credentials};
+/// let auth = ProxyAuth::new()
+///     .basic_auth("proxy_user", Some("proxy_password"))
+///     .header("X-Api-Key", "key")
+///     .credentials(Credentials::new("user_name", "password"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ProxyAuth {
+    pub(crate) headers: Vec<(String, String)>,
+    pub(crate) basic_auth: Option<(String, Option<String>)>,
+    pub(crate) credentials: Option<Credentials>,
+}
+
+impl ProxyAuth {
+    /// # Usage
+    /// creates a new instance of [`ProxyAuth`], which sends nothing to the proxy and doesn't log into qbittorrent.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// # Usage
+    /// adds a header sent with every request, eg: an API key or a token.
+    pub fn header<T: Into<String>, S: Into<String>>(mut self, name: T, value: S) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// # Usage
+    /// sets the HTTP basic auth sent with every request.
+    pub fn basic_auth<T: Into<String>>(mut self, username: T, password: Option<T>) -> Self {
+        self.basic_auth = Some((username.into(), password.map(Into::into)));
+        self
+    }
+
+    /// # Usage
+    /// sets the credentials to log into qbittorrent with, once past the proxy.
+    /// if not set, qbittorrent is expected to bypass its own authentication.
+    pub fn credentials(mut self, credentials: Credentials) -> Self {
+        self.credentials = Some(credentials);
+        self
+    }
+
+    fn apply(&self, mut builder: RequestBuilder) -> RequestBuilder {
+        for (name, value) in &self.headers {
+            builder = builder.header(name, value);
+        }
+
+        if let Some((username, password)) = &self.basic_auth {
+            builder = builder.basic_auth(username, password.as_ref());
+        }
+
+        builder
+    }
+}
@@ -0,0 +1,318 @@
+use std::path::PathBuf;
+
+use http_body_util::BodyExt;
+use reqwest::{
+    header::CONTENT_TYPE,
+    Body, Client, Request, RequestBuilder, Response,
+};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+use crate::error_handling::{error_type::ErrorType, errors::Error};
+
+/// the path of the login request, whose body holds the credentials.
+const LOGIN_PATH: &str = "/api/v2/auth/login";
+
+/// what the secrets are replaced with in a cassette.
+const REDACTED: &str = "REDACTED";
+
+/// ## Description
+/// describes whether a [`QbitApi`](super::api::QbitApi) records its HTTP exchanges, or replays recorded ones.
+///
+/// the exchanges are kept in a json "cassette" file.
+/// requests are matched on their method, path (without the authority) and body, never on the session cookie;
+/// form bodies are compared regardless of the order of their fields, and multipart bodies regardless of their boundary.
+///
+/// only json is supported. the credentials and the session id aren't written to the cassette:
+/// the body of the login request is left out, and the `SID` of its `Set-Cookie` header is replaced.
+/// when replaying, the login request is matched on its path alone, so any credentials can be used.
+///
+/// ## Example
+/// ```no_run
+/// # use qbittorrent_rust::core::{api::QbitApi, cassette::CassetteMode, creds::Credentials};
+/// # async fn example() -> Result<(), qbittorrent_rust::Error> {
+/// let api = QbitApi::builder("http://localhost:6011/")
+///     .auth(Credentials::new("username", "password"))
+///     .cassette(CassetteMode::Replay("tests/cassettes/add_torrent.json".into()))
+///     .build()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub enum CassetteMode {
+    /// sends the requests to the server, and writes every exchange to the file (which is replaced).
+    Record(PathBuf),
+    /// doesn't contact the server: each request gets the response of the first unused matching exchange in the file.
+    Replay(PathBuf),
+}
+
+/// a recorded body. bodies that aren't valid UTF-8 (eg: `.torrent` files) are kept as bytes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RecordedBody {
+    Text(String),
+    Bytes(Vec<u8>),
+}
+
+impl RecordedBody {
+    fn new(bytes: Vec<u8>) -> Self {
+        match String::from_utf8(bytes) {
+            Ok(text) => RecordedBody::Text(text),
+            Err(e) => RecordedBody::Bytes(e.into_bytes()),
+        }
+    }
+
+    fn into_bytes(self) -> Vec<u8> {
+        match self {
+            RecordedBody::Text(text) => text.into_bytes(),
+            RecordedBody::Bytes(bytes) => bytes,
+        }
+    }
+}
+
+/// a request, as stored in a cassette.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    pub method: String,
+    /// path and query, relative to the authority. eg: `/api/v2/torrents/info?filter=all`.
+    pub path: String,
+    pub body: RecordedBody,
+}
+
+impl RecordedRequest {
+    /// whether a replayed request can get the response of this one.
+    /// the login request is matched on its method and path, since its body isn't recorded.
+    fn matches(&self, other: &RecordedRequest) -> bool {
+        if self.path == LOGIN_PATH {
+            self.method == other.method && self.path == other.path
+        } else {
+            self == other
+        }
+    }
+}
+
+/// a response, as stored in a cassette.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: RecordedBody,
+}
+
+impl RecordedResponse {
+    /// the response without the session id, to be written to the cassette.
+    fn redacted(&self) -> Self {
+        let headers = self
+            .headers
+            .iter()
+            .map(|(name, value)| match value.split_once('=') {
+                Some((cookie, rest)) if name.eq_ignore_ascii_case("set-cookie") && cookie.trim() == "SID" => {
+                    let attributes = rest.split_once(';').map(|(_, a)| a);
+                    let value = match attributes {
+                        Some(attributes) => format!("SID={};{}", REDACTED, attributes),
+                        None => format!("SID={}", REDACTED),
+                    };
+                    (name.clone(), value)
+                }
+                _ => (name.clone(), value.clone()),
+            })
+            .collect();
+
+        Self {
+            status: self.status,
+            headers,
+            body: self.body.clone(),
+        }
+    }
+}
+
+/// a request with its response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+/// the state behind a [`CassetteMode`], shared between the clones of a [`QbitApi`](super::api::QbitApi).
+#[derive(Debug)]
+pub(crate) struct Cassette {
+    mode: CassetteMode,
+    authority: String,
+    /// the exchanges, each with whether it was already replayed.
+    interactions: Mutex<Vec<(Interaction, bool)>>,
+}
+
+impl Cassette {
+    pub(crate) async fn new(mode: CassetteMode, authority: &str) -> Result<Self, Error> {
+        let interactions = match &mode {
+            CassetteMode::Record(_) => Vec::new(),
+            CassetteMode::Replay(path) => {
+                let file = tokio::fs::read(path).await.map_err(|e| {
+                    Error::build(
+                        ErrorType::MiscError(format!("couldn't read the cassette {}: {}", path.display(), e)),
+                        None,
+                    )
+                })?;
+                serde_json::from_slice::<Vec<Interaction>>(&file)
+                    .map_err(|e| Error::build(ErrorType::JsonSerdeError(Box::new(e)), None))?
+                    .into_iter()
+                    .map(|interaction| (interaction, false))
+                    .collect()
+            }
+        };
+
+        Ok(Self {
+            mode,
+            authority: authority.to_string(),
+            interactions: Mutex::new(interactions),
+        })
+    }
+
+    /// sends the request (or replays its response), recording the exchange if needed.
+    pub(crate) async fn send(&self, client: &Client, builder: RequestBuilder) -> Result<Response, Error> {
+        let mut request = builder
+            .build()
+            .map_err(|e| Error::build(ErrorType::ReqwestError(Box::new(e)), None))?;
+        let recorded_request = self.record_request(&mut request).await?;
+
+        match &self.mode {
+            CassetteMode::Replay(_) => {
+                let mut interactions = self.interactions.lock().await;
+                let Some((interaction, used)) = interactions
+                    .iter_mut()
+                    .find(|(interaction, used)| !used && interaction.request.matches(&recorded_request))
+                else {
+                    return Err(Error::build(
+                        ErrorType::MiscError(format!(
+                            "the cassette has no response left for {} {}",
+                            recorded_request.method, recorded_request.path
+                        )),
+                        None,
+                    ));
+                };
+                *used = true;
+                replay(interaction.response.clone())
+            }
+            CassetteMode::Record(path) => {
+                let response = client
+                    .execute(request)
+                    .await
+                    .map_err(|e| Error::build(ErrorType::ReqwestError(Box::new(e)), None))?;
+
+                let status = response.status().as_u16();
+                let headers = response
+                    .headers()
+                    .iter()
+                    .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+                    .collect();
+                let body = response
+                    .bytes()
+                    .await
+                    .map_err(|e| Error::build(ErrorType::ReqwestError(Box::new(e)), None))?;
+
+                let recorded_response = RecordedResponse {
+                    status,
+                    headers,
+                    body: RecordedBody::new(body.to_vec()),
+                };
+
+                let mut interactions = self.interactions.lock().await;
+                interactions.push((
+                    Interaction {
+                        request: recorded_request,
+                        response: recorded_response.redacted(),
+                    },
+                    true,
+                ));
+
+                // the whole file is written every time, so that nothing is lost if the program stops early.
+                let cassette: Vec<&Interaction> = interactions.iter().map(|(interaction, _)| interaction).collect();
+                let json = serde_json::to_vec_pretty(&cassette)
+                    .map_err(|e| Error::build(ErrorType::JsonSerdeError(Box::new(e)), None))?;
+                tokio::fs::write(path, json).await.map_err(|e| {
+                    Error::build(
+                        ErrorType::MiscError(format!("couldn't write the cassette {}: {}", path.display(), e)),
+                        None,
+                    )
+                })?;
+
+                replay(recorded_response)
+            }
+        }
+    }
+
+    /// reads the body of the request (putting it back), and makes it comparable.
+    async fn record_request(&self, request: &mut Request) -> Result<RecordedRequest, Error> {
+        let body = match request.body_mut().take() {
+            Some(body) => {
+                let body = body
+                    .collect()
+                    .await
+                    .map_err(|e| Error::build(ErrorType::ReqwestError(Box::new(e)), None))?
+                    .to_bytes()
+                    .to_vec();
+                *request.body_mut() = Some(Body::from(body.clone()));
+                body
+            }
+            None => Vec::new(),
+        };
+
+        let content_type = request
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|c| c.to_str().ok())
+            .unwrap_or_default();
+
+        let body = if content_type.starts_with("application/x-www-form-urlencoded") {
+            // forms are often made from a `HashMap`, so the order of the fields changes between runs.
+            let text = String::from_utf8_lossy(&body);
+            let mut fields: Vec<&str> = text.split('&').collect();
+            fields.sort_unstable();
+            fields.join("&").into_bytes()
+        } else if let Some(boundary) = content_type.split_once("boundary=").map(|(_, b)| b.trim_matches('"')) {
+            replace(&body, boundary.as_bytes(), b"BOUNDARY")
+        } else {
+            body
+        };
+
+        let url = request.url().as_str();
+        let path = url.strip_prefix(self.authority.as_str()).unwrap_or(url).to_string();
+
+        let body = if path == LOGIN_PATH {
+            REDACTED.as_bytes().to_vec()
+        } else {
+            body
+        };
+
+        Ok(RecordedRequest {
+            method: request.method().to_string(),
+            path,
+            body: RecordedBody::new(body),
+        })
+    }
+}
+
+fn replay(recorded: RecordedResponse) -> Result<Response, Error> {
+    let mut response = http::Response::builder().status(recorded.status);
+    for (name, value) in &recorded.headers {
+        response = response.header(name, value);
+    }
+    let response = response
+        .body(recorded.body.into_bytes())
+        .map_err(|e| Error::build(ErrorType::MiscError(format!("invalid recorded response: {}", e)), None))?;
+
+    Ok(Response::from(response))
+}
+
+fn replace(haystack: &[u8], from: &[u8], to: &[u8]) -> Vec<u8> {
+    let mut result = Vec::with_capacity(haystack.len());
+    let mut rest = haystack;
+    while let Some(position) = rest.windows(from.len()).position(|window| window == from) {
+        result.extend_from_slice(&rest[..position]);
+        result.extend_from_slice(to);
+        rest = &rest[position + from.len()..];
+    }
+    result.extend_from_slice(rest);
+    result
+}
@@ -1,49 +1,88 @@
-use std::time::Instant;
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use reqwest::Client;
 
-use super::creds::Credentials;
+use super::{auth::Auth, cassette::Cassette, creds::Credentials};
 use crate::code;
 use crate::error_handling::error_type::ErrorType;
 use crate::error_handling::errors::Error;
 
+/// qBittorrent's default `web_ui_session_timeout`, used until the server's own value is known.
+pub(crate) const DEFAULT_SESSION_TIMEOUT: Duration = Duration::from_secs(3600);
+
+/// how long before the official expiration a session is already considered expired.
+const EXPIRY_MARGIN: Duration = Duration::from_secs(300);
+
 #[derive(Debug, Clone)]
 pub(crate) struct Cookie {
-    pub(crate) cookie: String,
+    /// the session id, or `None` when the server doesn't require one.
+    pub(crate) cookie: Option<String>,
     time_of_creation: Instant,
+    session_timeout: Duration,
+    renewable: bool,
+    cassette: Option<Arc<Cassette>>,
 }
 impl Cookie {
     /// makes a new instance of `Cookie`.
+    ///
+    /// logs in only if `auth` has credentials to log in with.
     pub(crate) async fn new(
         authority: &String,
         reqwest_client: &Client,
-        credentials: &Credentials,
+        auth: &Auth,
+        cassette: Option<Arc<Cassette>>,
     ) -> Result<Self, Error> {
         let now = Instant::now();
 
-        let cookie = Self::request_raw_cookie(authority, reqwest_client, credentials).await?;
+        let cookie = match (auth.credentials(), auth) {
+            (Some(credentials), _) => Some(
+                Self::request_raw_cookie(
+                    authority,
+                    reqwest_client,
+                    auth,
+                    credentials,
+                    cassette.as_deref(),
+                )
+                .await?,
+            ),
+            (None, Auth::Sid(sid)) => Some(sid.clone()),
+            (None, _) => None,
+        };
 
         Ok(Self {
             cookie,
             time_of_creation: now,
+            session_timeout: DEFAULT_SESSION_TIMEOUT,
+            renewable: auth.credentials().is_some(),
+            cassette,
         })
     }
 
     pub(crate) async fn request_raw_cookie(
         authority: &String,
         reqwest_client: &Client,
+        auth: &Auth,
         credentials: &Credentials,
+        cassette: Option<&Cassette>,
     ) -> Result<String, Error> {
-        let response = reqwest_client
-            .post(format!("{}/api/v2/auth/login", authority))
+        let request = auth
+            .apply(reqwest_client.post(format!("{}/api/v2/auth/login", authority)))
             .header(reqwest::header::REFERER, authority)
             .form(&[
                 ("username", credentials.username.clone()),
                 ("password", credentials.password.clone()),
-            ])
-            .send()
-            .await
-            .map_err(|e| Error::build(ErrorType::ReqwestError(Box::new(e)), None))?;
+            ]);
+
+        let response = match cassette {
+            Some(cassette) => cassette.send(reqwest_client, request).await?,
+            None => request
+                .send()
+                .await
+                .map_err(|e| Error::build(ErrorType::ReqwestError(Box::new(e)), None))?,
+        };
 
         let status = response.status();
 
@@ -80,17 +119,29 @@ impl Cookie {
     /// checks if the `Cookie` expired.
     ///
     /// # FUNCTIONING
-    /// returns `true` when the cookie is expired.
+    /// returns `true` when the cookie is expired. a `Cookie` that can't be renewed never expires.
     ///
     /// # WARNING
-    /// - this method relies on the elapsed seconds from the time of creation. make sure to something that messes with that.
-    /// - this will check that there is at least 1 minute of margin from the official expiration, to ensure any operation after this methis is called is still possible.
+    /// - this method relies on the elapsed seconds from the time of creation and on the session timeout of the server. a session invalidated by the server itself (eg: after a restart) won't be detected here.
+    /// - this will check that there is at least 5 minutes of margin (or half the session timeout, if shorter) from the official expiration, to ensure any operation after this method is called is still possible.
     pub(crate) fn is_expired(&self) -> bool {
-        if self.time_of_creation.elapsed().as_secs() >= 3300 {
-            true
-        } else {
-            false
+        if !self.renewable {
+            return false;
         }
+
+        let margin = EXPIRY_MARGIN.min(self.session_timeout / 2);
+
+        self.time_of_creation.elapsed() >= self.session_timeout - margin
+    }
+
+    /// whether a new cookie can be requested, ie: whether there are credentials to log in with.
+    pub(crate) fn is_renewable(&self) -> bool {
+        self.renewable
+    }
+
+    /// sets the session timeout the server was configured with.
+    pub(crate) fn set_session_timeout(&mut self, session_timeout: Duration) {
+        self.session_timeout = session_timeout;
     }
 
     /// checks if the `Cookie` is expired, and if it is, requests a new one.
@@ -98,12 +149,41 @@ impl Cookie {
         &mut self,
         authority: &String,
         reqwest_client: &Client,
-        credentials: &Credentials,
+        auth: &Auth,
     ) -> Result<(), Error> {
         if self.is_expired() {
-            self.cookie = Self::request_raw_cookie(authority, reqwest_client, credentials).await?;
+            self.force_reset(authority, reqwest_client, auth).await?;
         }
 
         Ok(())
     }
+
+    /// requests a new cookie regardless of the expiration, eg: when the server rejected the current one.
+    /// does nothing if `auth` has no credentials to log in with.
+    pub(crate) async fn force_reset(
+        &mut self,
+        authority: &String,
+        reqwest_client: &Client,
+        auth: &Auth,
+    ) -> Result<(), Error> {
+        let Some(credentials) = auth.credentials() else {
+            return Ok(());
+        };
+
+        let now = Instant::now();
+
+        self.cookie = Some(
+            Self::request_raw_cookie(
+                authority,
+                reqwest_client,
+                auth,
+                credentials,
+                self.cassette.as_deref(),
+            )
+            .await?,
+        );
+        self.time_of_creation = now;
+
+        Ok(())
+    }
 }
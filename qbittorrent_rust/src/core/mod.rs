@@ -1,3 +1,8 @@
+pub mod auth;
+pub mod cassette;
 pub mod creds;
 pub mod api;
-pub mod cookie;
\ No newline at end of file
+pub mod cookie;
+pub mod rate_limit;
+pub mod retry;
+pub mod version;
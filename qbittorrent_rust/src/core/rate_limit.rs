@@ -0,0 +1,123 @@
+use std::{sync::Arc, time::Duration};
+
+use tokio::{
+    sync::{Mutex, OwnedSemaphorePermit, Semaphore},
+    time::Instant,
+};
+
+/// ## Description
+/// limits how fast and how many requests a [`QbitApi`](super::api::QbitApi) sends.
+///
+/// the limits are shared by a [`QbitApi`](super::api::QbitApi) and all of its clones.
+/// by default nothing is limited.
+///
+/// ## Example
+/// ```no_run
+/// # use qbittorrent_rust::core::{api::QbitApi, rate_limit::RateLimit};
+/// # async fn example() -> Result<(), qbittorrent_rust::Error> {
+/// let api = QbitApi::builder("http://localhost:6011/")
+///     .rate_limit(RateLimit::new().requests_per_second(20.0).max_in_flight(8))
+///     .build()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RateLimit {
+    requests_per_second: Option<f64>,
+    burst: Option<u32>,
+    max_in_flight: Option<usize>,
+}
+
+impl RateLimit {
+    /// # Usage
+    /// creates a new instance of [`RateLimit`], which doesn't limit anything.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// sets how many requests per second can be sent on average. values `<= 0` are ignored.
+    pub fn requests_per_second(mut self, requests_per_second: f64) -> Self {
+        self.requests_per_second = Some(requests_per_second).filter(|r| *r > 0.0);
+        self
+    }
+
+    /// sets how many requests can be sent at once after a quiet period. defaults to 1.
+    pub fn burst(mut self, burst: u32) -> Self {
+        self.burst = Some(burst.max(1));
+        self
+    }
+
+    /// sets how many requests can be waiting for a response at the same time.
+    pub fn max_in_flight(mut self, max_in_flight: usize) -> Self {
+        self.max_in_flight = Some(max_in_flight.max(1));
+        self
+    }
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    rate: f64,
+    capacity: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+/// the state behind a [`RateLimit`], shared between the clones of a [`QbitApi`](super::api::QbitApi).
+#[derive(Debug, Default)]
+pub(crate) struct Limiter {
+    bucket: Option<Mutex<TokenBucket>>,
+    in_flight: Option<Arc<Semaphore>>,
+}
+
+impl Limiter {
+    pub(crate) fn new(rate_limit: RateLimit) -> Self {
+        let bucket = rate_limit.requests_per_second.map(|rate| {
+            let capacity = rate_limit.burst.unwrap_or(1) as f64;
+            Mutex::new(TokenBucket {
+                rate,
+                capacity,
+                tokens: capacity,
+                last_refill: Instant::now(),
+            })
+        });
+
+        Self {
+            bucket,
+            in_flight: rate_limit
+                .max_in_flight
+                .map(|max| Arc::new(Semaphore::new(max))),
+        }
+    }
+
+    /// waits until a request can be sent. the request counts as in flight until the returned permit is dropped.
+    pub(crate) async fn acquire(&self) -> Option<OwnedSemaphorePermit> {
+        // waiting for a free slot first means no token is spent while waiting for it.
+        let permit = match &self.in_flight {
+            Some(semaphore) => semaphore.clone().acquire_owned().await.ok(),
+            None => None,
+        };
+
+        if let Some(bucket) = &self.bucket {
+            loop {
+                let mut bucket = bucket.lock().await;
+
+                let now = Instant::now();
+                let refill = now.duration_since(bucket.last_refill).as_secs_f64() * bucket.rate;
+                bucket.tokens = (bucket.tokens + refill).min(bucket.capacity);
+                bucket.last_refill = now;
+
+                if bucket.tokens >= 1.0 {
+                    bucket.tokens -= 1.0;
+                    break;
+                }
+
+                let wait = Duration::from_secs_f64((1.0 - bucket.tokens) / bucket.rate);
+                drop(bucket);
+                tokio::time::sleep(wait).await;
+            }
+        }
+
+        permit
+    }
+}
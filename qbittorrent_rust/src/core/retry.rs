@@ -0,0 +1,145 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    time::Duration,
+};
+
+use reqwest::Response;
+
+use crate::error_handling::{error_type::ErrorType, errors::Error};
+
+/// ## Description
+/// describes which failed requests a [`QbitApi`](super::api::QbitApi) retries, and how long it waits in between.
+///
+/// a [`QbitApi`](super::api::QbitApi) uses [`RetryPolicy::none`] unless told otherwise;
+/// [`RetryPolicy::default`] retries connection errors, timeouts and 5xx responses up to 3 times in total.
+///
+/// the wait before each new attempt doubles every time (starting from `base_delay`, capped at `max_delay`),
+/// and is randomly shortened by up to half, so that many clients don't all retry at the same moment.
+///
+/// ## Example
+/// ```no_run
+/// # use std::time::Duration;
+/// # use qbittorrent_rust::core::{api::QbitApi, auth::Auth, retry::RetryPolicy};
+/// # async fn example() -> Result<(), qbittorrent_rust::Error> {
+/// # let mut api = QbitApi::new("http://localhost:6011/", Auth::Bypass).await?;
+/// api.set_retry_policy(RetryPolicy::default().max_attempts(5).base_delay(Duration::from_secs(1)));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    retry_connect: bool,
+    retry_timeout: bool,
+    retry_server_error: bool,
+    retry_non_idempotent: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(250),
+            max_delay: Duration::from_secs(10),
+            retry_connect: true,
+            retry_timeout: true,
+            retry_server_error: true,
+            retry_non_idempotent: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// # Usage
+    /// a policy that never retries.
+    pub fn none() -> Self {
+        Self::default().max_attempts(1)
+    }
+
+    /// sets the maximum number of attempts, the first one included. `1` (or `0`) disables retrying.
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// sets the wait before the second attempt.
+    pub fn base_delay(mut self, base_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self
+    }
+
+    /// sets the longest wait between two attempts.
+    pub fn max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// whether to retry when the connection can't be established or gets dropped.
+    pub fn retry_connect(mut self, retry: bool) -> Self {
+        self.retry_connect = retry;
+        self
+    }
+
+    /// whether to retry requests that timed out.
+    pub fn retry_timeout(mut self, retry: bool) -> Self {
+        self.retry_timeout = retry;
+        self
+    }
+
+    /// whether to retry when the server answers with a 5xx status code.
+    pub fn retry_server_error(mut self, retry: bool) -> Self {
+        self.retry_server_error = retry;
+        self
+    }
+
+    /// whether to also retry requests that aren't safe to repeat, like `torrents_add_torrent`, `search_start` or the toggles.
+    ///
+    /// # WARNING
+    /// if the server handled the first attempt but the response got lost, the action will happen twice.
+    pub fn retry_non_idempotent(mut self, retry: bool) -> Self {
+        self.retry_non_idempotent = retry;
+        self
+    }
+
+    /// how many attempts a request may take.
+    pub(crate) fn attempts_for(&self, idempotent: bool) -> u32 {
+        if idempotent || self.retry_non_idempotent {
+            self.max_attempts.max(1)
+        } else {
+            1
+        }
+    }
+
+    /// whether the outcome of an attempt is worth another one.
+    pub(crate) fn should_retry(&self, result: &Result<Response, Error>) -> bool {
+        match result {
+            Ok(response) => self.retry_server_error && response.status().is_server_error(),
+            Err(Error {
+                err_type: ErrorType::ReqwestError(e),
+                ..
+            }) => match e.downcast_ref::<reqwest::Error>() {
+                Some(e) if e.is_timeout() => self.retry_timeout,
+                Some(e) if e.is_connect() || e.is_request() => self.retry_connect,
+                _ => false,
+            },
+            Err(_) => false,
+        }
+    }
+
+    /// the wait after the `attempt`-th failed attempt (starting from 1).
+    pub(crate) fn delay(&self, attempt: u32) -> Duration {
+        let exponential = self
+            .base_delay
+            .saturating_mul(2_u32.saturating_pow(attempt.saturating_sub(1)))
+            .min(self.max_delay);
+
+        // a fresh `RandomState` is randomly seeded, which is all the randomness needed here.
+        let random = RandomState::new().build_hasher().finish();
+        let jitter = exponential.mul_f64((random % 1000) as f64 / 2000.0);
+
+        exponential - jitter
+    }
+}
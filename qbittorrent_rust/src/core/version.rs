@@ -0,0 +1,84 @@
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+use crate::error_handling::{error_type::ErrorType, errors::Error};
+
+/// ## Description
+/// a WebAPI version, as returned by [`QbitApi::app_web_api_version`](super::api::QbitApi::app_web_api_version). eg: `2.9.3`.
+///
+/// this isn't the version of qbittorrent itself: qbittorrent 4.6 serves the WebAPI 2.9, qbittorrent 5.0 the WebAPI 2.11.
+///
+/// ## Example
+/// ```
+/// # use qbittorrent_rust::core::version::ApiVersion;
+/// # fn main() -> Result<(), qbittorrent_rust::Error> {
+/// let version: ApiVersion = "2.11.2".parse()?;
+/// assert!(version >= ApiVersion::V2_11_0);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ApiVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl ApiVersion {
+    /// `/torrents/renameFolder` is added, and `/torrents/renameFile` takes `oldPath` and `newPath` instead of a file id.
+    pub const V2_7_0: ApiVersion = ApiVersion::new(2, 7, 0);
+    /// `/torrents/setSavePath` and `/torrents/setDownloadPath` are added (qbittorrent 4.4).
+    pub const V2_8_4: ApiVersion = ApiVersion::new(2, 8, 4);
+    /// `/torrents/export` is added (qbittorrent 4.5).
+    pub const V2_8_14: ApiVersion = ApiVersion::new(2, 8, 14);
+    /// `/rss/setFeedURL` is added (qbittorrent 4.6).
+    pub const V2_9_1: ApiVersion = ApiVersion::new(2, 9, 1);
+    /// `/torrents/count` is added (qbittorrent 4.6).
+    pub const V2_9_3: ApiVersion = ApiVersion::new(2, 9, 3);
+    /// `/torrents/pause` and `/torrents/resume` are renamed to `/torrents/stop` and `/torrents/start`,
+    /// and the `paused*` torrent states become `stopped*` (qbittorrent 5.0).
+    pub const V2_11_0: ApiVersion = ApiVersion::new(2, 11, 0);
+    /// `/torrents/setTags` and `/rss/setFeedRefreshInterval` are added (qbittorrent 5.1).
+    pub const V2_11_4: ApiVersion = ApiVersion::new(2, 11, 4);
+
+    /// # Usage
+    /// creates a new instance of [`ApiVersion`].
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self { major, minor, patch }
+    }
+}
+
+impl FromStr for ApiVersion {
+    type Err = Error;
+
+    /// parses versions like `2.11.2`, `v2.8` or `2`; the missing parts are `0`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            Error::build(
+                ErrorType::MiscError(format!("invalid WebAPI version: {:?}", s)),
+                None,
+            )
+        };
+
+        let trimmed = s.trim().trim_start_matches('v');
+        let mut parts = trimmed.split('.').map(|part| part.parse::<u32>().map_err(|_| invalid()));
+
+        let major = parts.next().ok_or_else(invalid)??;
+        let minor = parts.next().transpose()?.unwrap_or(0);
+        let patch = parts.next().transpose()?.unwrap_or(0);
+
+        if parts.next().is_some() {
+            return Err(invalid());
+        }
+
+        Ok(Self::new(major, minor, patch))
+    }
+}
+
+impl Display for ApiVersion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
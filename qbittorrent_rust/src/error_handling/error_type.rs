@@ -1,18 +1,32 @@
+use crate::core::version::ApiVersion;
+
 /// ## Description
 /// describes the type of error which occurred.
 #[derive(Debug)]
 pub enum ErrorType {
     TorrentsNotSet,
     TorrentFilePathError,
+    /// the `.torrent` data isn't valid bencode, or isn't a valid torrent.
+    InvalidTorrentFile(String),
+    /// the magnet link is malformed, or has no BitTorrent info-hash.
+    InvalidMagnetLink(String),
     TorrentHashNotFound,
     TorrenQueueingNotEnabled,
     WrongCreds,
     TooManyFailedAttempts,
     ParameterNotExpected,
+    /// the server's WebAPI version is older than the one the endpoint requires.
+    UnsupportedByServer { required: ApiVersion, actual: ApiVersion },
+    /// an rss item already exists at the path, or a feed already has the url. holds qbittorrent's message.
+    RssItemAlreadyExists(String),
+    /// the rss item, feed or parent folder doesn't exist. holds qbittorrent's message.
+    RssItemNotFound(String),
+    /// qbittorrent refused the rss path for another reason, eg: moving the root folder, or a folder into itself.
+    RssInvalidPath(String),
     MiscNetError(u16),
     MiscError(String),
-    ReqwestError(Box<dyn std::error::Error>),
-    JsonSerdeError(Box<dyn std::error::Error>),
+    ReqwestError(Box<dyn std::error::Error + Send + Sync>),
+    JsonSerdeError(Box<dyn std::error::Error + Send + Sync>),
 }
 
 impl ErrorType {
@@ -20,11 +34,17 @@ impl ErrorType {
         match self {
             ErrorType::TorrentsNotSet => "no torrents were specified.".to_string(),
             ErrorType::TorrentFilePathError => "the path specified doesn't exist, is malformed, or the file it points to couldn't be read.".to_string(),
+            ErrorType::InvalidTorrentFile(e) => format!("the torrent file is invalid: {}", e),
+            ErrorType::InvalidMagnetLink(e) => format!("the magnet link is invalid: {}", e),
             ErrorType::TorrentHashNotFound => "the specified torrent hash couldn't be found.".to_string(),
             ErrorType::TorrenQueueingNotEnabled => "torrent queuing id not enabled.".to_string(),
             ErrorType::WrongCreds => "the credetials are wrong.".to_string(),
             ErrorType::TooManyFailedAttempts => "the user has been banned for an amount of time because of too many failed login attempts.".to_string(),
             ErrorType::ParameterNotExpected => "one or more of the parameters speciied were wrong".to_string(),
+            ErrorType::UnsupportedByServer { required, actual } => format!("the server doesn't support this: it requires the WebAPI {}, but the server has the WebAPI {}.", required, actual),
+            ErrorType::RssItemAlreadyExists(e) => format!("the rss item already exists: {}", e),
+            ErrorType::RssItemNotFound(e) => format!("the rss item doesn't exist: {}", e),
+            ErrorType::RssInvalidPath(e) => format!("the rss path was refused: {}", e),
             ErrorType::MiscError(e) => format!("Something went wrong. {}", e),
             ErrorType::ReqwestError(e) => format!("there was an error while handling networking. error: {}", e),
             ErrorType::JsonSerdeError(e) => format!("there was an error while handling JSON data. error: {}", e),
@@ -11,7 +11,7 @@
 //! ## Usage
 //! 
 //! The library's main structure is [`QbitApi`], which provides all the methods to the Qbittorrent WebUI API.
-//! There are 7 categories of methods, corresponding to the the various categories of requests in the Qbittorrent WebUI API documentation:
+//! There are 8 categories of methods, corresponding to the the various categories of requests in the Qbittorrent WebUI API documentation:
 //!
 //! | Name | Use |
 //! | ------ | ------ |
@@ -22,6 +22,7 @@
 //! | sync |  holds everything related to synchronization. |
 //! | search |  holds everything related to searching ans searching plugins. |
 //! | rss |  holds everything related to RSS. |
+//! | torrent_creator |  holds everything related to creating .torrent files on the server. |
 //!
 //! each method in [`QbitApi`] starts with its category, followed by the method's name, all in snake case. example: `torrents_add_torrent`
 
@@ -31,10 +32,14 @@ pub mod misc;
 pub mod error_handling;
 pub mod api_fns;
 pub mod macros;
+#[cfg(feature = "testing")]
+pub mod testing;
 
 pub use error_handling::errors::Error;
 pub use api_fns::application::app_preferences::*;
 pub use api_fns::log::logs::*;
-pub use api_fns::rss::rss::*;
-pub use api_fns::search::search::*;
-pub use api_fns::torrents::{add_torrent::*, info::*, torrent_managing_misc::*, torrents::*};
\ No newline at end of file
+pub use api_fns::rss::{rss::*, rss_items::*};
+pub use api_fns::search::{search::*, search_pick::*, search_plugins::*, search_session::*};
+pub use api_fns::torrent_creator::torrent_creator::*;
+pub use api_fns::sync::{sync_events::*, sync_state::*};
+pub use api_fns::torrents::{add_torrent::*, info::*, magnet::*, metainfo::*, properties::*, torrent_managing_misc::*, torrents::*};
\ No newline at end of file
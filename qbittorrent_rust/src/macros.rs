@@ -2,14 +2,14 @@
 macro_rules! post_request {
     ($(#[$meta:meta])* $func_name:ident, $path:expr) => {
         $(#[$meta])*
-        pub async fn $func_name(&mut self) -> Result <String, crate::Error> {
+        pub async fn $func_name(&self) -> Result <String, crate::Error> {
             Self::make_request(self, $path, format!("{}", stringify!($func_name))).await
         }
     };
 
     ($(#[$meta:meta])* $func_name:ident, $path:expr, $(($name_arg:tt, $type:ty)),+) => {
         $(#[$meta])*
-        pub async fn $func_name(&mut self, $($name_arg: $type),+) -> Result <String, crate::Error> {
+        pub async fn $func_name(&self, $($name_arg: $type),+) -> Result <String, crate::Error> {
             let mut form_data = std::collections::HashMap::new();
 
             $(
@@ -17,12 +17,8 @@ macro_rules! post_request {
             )+
 
 
-            let response = self.reqwest_client.post(format!("{}/api/v2{}", self.authority, $path))
-                .header(reqwest::header::COOKIE, format!("SID={}", self.get_cookie().await?))
-                .form(&form_data)
-                .send()
-                .await
-                .map_err(|e| crate::error_handling::flat_error::FlatError::ReqwestError(e.to_string()).unflatten_err())?;
+            let url = format!("{}/api/v2{}", self.authority, $path);
+            let response = self.send_with_reauth(|| self.reqwest_client.post(&url).form(&form_data)).await?;
 
             // Handle the response
             if response.status().is_success() {
@@ -36,9 +32,18 @@ macro_rules! post_request {
 
 #[macro_export]
 macro_rules! post_request_no_return {
+    // for requests that mustn't be retried, like toggles.
+    (non_idempotent $(#[$meta:meta])* $func_name:ident, $path:expr) => {
+        $(#[$meta])*
+        pub async fn $func_name(&self) -> Result <(), crate::Error> {
+            Self::make_request_non_idempotent(self, $path, format!("{}", stringify!($func_name))).await?;
+            Ok(())
+        }
+    };
+
     ($(#[$meta:meta])* $func_name:ident, $path:expr) => {
         $(#[$meta])* 
-        pub async fn $func_name(&mut self) -> Result <(), crate::Error> {
+        pub async fn $func_name(&self) -> Result <(), crate::Error> {
             Self::make_request(self, $path, format!("{}", stringify!($func_name))).await?;
             Ok(())
         }
@@ -46,7 +51,7 @@ macro_rules! post_request_no_return {
 
     ($(#[$meta:meta])*  $func_name:ident, $path:expr, $(($name_arg:tt, $type:ty)),+) => {
         $(#[$meta])*
-        pub async fn $func_name(&mut self, $($name_arg: $type),+) -> Result <(), crate::Error> {
+        pub async fn $func_name(&self, $($name_arg: $type),+) -> Result <(), crate::Error> {
             use crate::error_handling::error_type::ErrorType;
             let mut form_data = std::collections::HashMap::new();
 
@@ -55,12 +60,8 @@ macro_rules! post_request_no_return {
             )+
 
 
-            let response = self.reqwest_client.post(format!("{}/api/v2{}", self.authority, $path))
-                .header(reqwest::header::COOKIE, format!("SID={}", self.get_cookie().await?))
-                .form(&form_data)
-                .send()
-                .await
-                .map_err(|e| Error::build(ErrorType::ReqwestError(Box::new(e)), None))?;
+            let url = format!("{}/api/v2{}", self.authority, $path);
+            let response = self.send_with_reauth(|| self.reqwest_client.post(&url).form(&form_data)).await?;
 
             // Handle the response
             if response.status().is_success() {
@@ -119,7 +120,7 @@ macro_rules! url {
 macro_rules! post_request_hash {
     ($(#[$meta:meta])* $func_name:ident, $path:expr) => {
         $(#[$meta])*
-        pub async fn $func_name(&mut self, hash: impl Borrow<TorrentHash>) -> Result <String, crate::Error> {
+        pub async fn $func_name(&self, hash: impl Borrow<TorrentHash>) -> Result <String, crate::Error> {
             let mut hashmap = HashMap::new();
             hashmap.insert("hash", hash.get_hash());
             self.make_request_with_form_hash($path, stringify!($func_name), hashmap).await
@@ -131,7 +132,7 @@ macro_rules! post_request_hash {
 macro_rules! fn_value_from_string {
     ($(#[$meta:meta])* $func_name:ident, $other_func:ident) => {
         $(#[$meta])*
-        pub async fn $func_name(&mut self, hash: impl Borrow<TorrentHash>) -> Result <Value, crate::Error> {
+        pub async fn $func_name(&self, hash: impl Borrow<TorrentHash>) -> Result <Value, crate::Error> {
             Ok(serde_json::from_str(self.$other_func(hash).await?.as_str()).map_err(|e| Error::build(ErrorType::JsonSerdeError(Box::new(e)), None))?)
         }
     };
@@ -149,7 +150,7 @@ macro_rules! fn_hash_value_pair {
 macro_rules! torrents_fn_mult_hashes {
     ($(#[$meta:meta])* $func_name:ident, $url:expr) => {
         $(#[$meta])*
-        pub async fn $func_name(&mut self, hashes: impl Borrow<TorrentHashesDesc>) -> Result<(), Error> {
+        pub async fn $func_name(&self, hashes: impl Borrow<TorrentHashesDesc>) -> Result<(), Error> {
             let hashes_str = hashes.borrow().get_string("|");
             
             let url = url!($url, ("hashes", Some(hashes_str)));
@@ -165,7 +166,7 @@ macro_rules! torrents_fn_mult_hashes {
 #[macro_export]
 macro_rules! torrents_fn_mult_hashes_res {
     ($func_name:ident, $url:expr) => {
-        pub async fn $func_name(&mut self, hashes: impl Borrow<TorrentHashesDesc>) -> Result<String, Error> {
+        pub async fn $func_name(&self, hashes: impl Borrow<TorrentHashesDesc>) -> Result<String, Error> {
             let hashes_str = hashes.borrow().get_string("|");
             
             let url = url!($url, ("hashes", Some(hashes_str)));
@@ -178,14 +179,23 @@ macro_rules! torrents_fn_mult_hashes_res {
 
 #[macro_export]
 macro_rules! torrents_fn_mult_hashes_prios {
+    // for requests that mustn't be retried, like moving the torrents one level.
+    (non_idempotent $(#[$meta:meta])* $func_name:ident, $url:expr) => {
+        torrents_fn_mult_hashes_prios!(@request make_request_non_idempotent, $(#[$meta])* $func_name, $url);
+    };
+
     ($(#[$meta:meta])* $func_name:ident, $url:expr) => {
+        torrents_fn_mult_hashes_prios!(@request make_request, $(#[$meta])* $func_name, $url);
+    };
+
+    (@request $request:ident, $(#[$meta:meta])* $func_name:ident, $url:expr) => {
         $(#[$meta])*
-        pub async fn $func_name(&mut self, hashes: impl Borrow<TorrentHashesDesc>) -> Result<(), Error> {
+        pub async fn $func_name(&self, hashes: impl Borrow<TorrentHashesDesc>) -> Result<(), Error> {
             let hashes_str = hashes.borrow().get_string("|");
             
             let url = url!($url, ("hashes", Some(hashes_str)));
     
-            self.make_request(url, stringify!($func_name)).await.map_err(|e| {
+            self.$request(url, stringify!($func_name)).await.map_err(|e| {
                 if let Some(num) = e.code {
                     if num == 409 {
                         Error::build(ErrorType::TorrenQueueingNotEnabled, Some(num))
@@ -226,8 +236,17 @@ macro_rules! request_error_focus {
         })?;
     };
 
+    // for requests that mustn't be retried, like the ones creating something.
+    (non_idempotent $self:expr, $func_name:ident, $url:expr, $hashmap:expr, $(($status_code:expr, $error:expr)),+) => {
+        request_error_focus!(@request make_request_with_form_non_idempotent, $self, $func_name, $url, $hashmap, $(($status_code, $error)),+)
+    };
+
     ($self:expr, $func_name:ident, $url:expr, $hashmap:expr, $(($status_code:expr, $error:expr)),+) => {
-        $self.make_request_with_form($url, stringify!($func_name), $hashmap).await.map_err(|e| {
+        request_error_focus!(@request make_request_with_form, $self, $func_name, $url, $hashmap, $(($status_code, $error)),+)
+    };
+
+    (@request $request:ident, $self:expr, $func_name:ident, $url:expr, $hashmap:expr, $(($status_code:expr, $error:expr)),+) => {
+        $self.$request($url, stringify!($func_name), $hashmap).await.map_err(|e| {
             if let Some(num) = e.code {
                 match num {
                     $(
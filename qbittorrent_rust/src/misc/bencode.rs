@@ -0,0 +1,253 @@
+use std::{collections::BTreeMap, ops::Range};
+
+use crate::error_handling::{error_type::ErrorType, errors::Error};
+
+/// ## Info
+/// a bencoded value, the format of `.torrent` files.
+///
+/// byte strings are kept as bytes, since they aren't always UTF-8 (eg: the `pieces` of a torrent).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Bencode {
+    Integer(i64),
+    Bytes(Vec<u8>),
+    List(Vec<Bencode>),
+    Dict(BTreeMap<Vec<u8>, Bencode>),
+}
+
+impl Bencode {
+    /// ## Usage
+    /// decodes a bencoded value. the whole input must be a single value.
+    pub fn decode(data: &[u8]) -> Result<Self, Error> {
+        let mut decoder = Decoder::new(data);
+        let value = decoder.value()?;
+        decoder.end()?;
+        Ok(value)
+    }
+
+    /// ## Usage
+    /// encodes the value. dictionaries are written with their keys sorted, as bencode requires.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.encode_into(&mut out);
+        out
+    }
+
+    fn encode_into(&self, out: &mut Vec<u8>) {
+        match self {
+            Bencode::Integer(i) => out.extend_from_slice(format!("i{}e", i).as_bytes()),
+            Bencode::Bytes(bytes) => {
+                out.extend_from_slice(format!("{}:", bytes.len()).as_bytes());
+                out.extend_from_slice(bytes);
+            }
+            Bencode::List(list) => {
+                out.push(b'l');
+                for value in list {
+                    value.encode_into(out);
+                }
+                out.push(b'e');
+            }
+            Bencode::Dict(dict) => {
+                out.push(b'd');
+                for (key, value) in dict {
+                    Bencode::Bytes(key.clone()).encode_into(out);
+                    value.encode_into(out);
+                }
+                out.push(b'e');
+            }
+        }
+    }
+
+    /// the value of a key, if this is a dictionary.
+    pub fn get(&self, key: &str) -> Option<&Bencode> {
+        match self {
+            Bencode::Dict(dict) => dict.get(key.as_bytes()),
+            _ => None,
+        }
+    }
+
+    pub fn as_integer(&self) -> Option<i64> {
+        match self {
+            Bencode::Integer(i) => Some(*i),
+            _ => None,
+        }
+    }
+
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Bencode::Bytes(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+
+    /// the byte string as text. invalid UTF-8 is replaced with `�`.
+    pub fn as_string(&self) -> Option<String> {
+        self.as_bytes().map(|bytes| String::from_utf8_lossy(bytes).into_owned())
+    }
+
+    pub fn as_list(&self) -> Option<&[Bencode]> {
+        match self {
+            Bencode::List(list) => Some(list),
+            _ => None,
+        }
+    }
+
+    pub fn as_dict(&self) -> Option<&BTreeMap<Vec<u8>, Bencode>> {
+        match self {
+            Bencode::Dict(dict) => Some(dict),
+            _ => None,
+        }
+    }
+}
+
+/// reads bencoded values, keeping track of where they are in the input.
+pub(crate) struct Decoder<'a> {
+    data: &'a [u8],
+    position: usize,
+    depth: usize,
+}
+
+/// how deeply lists and dictionaries can be nested, so that a malicious file can't overflow the stack.
+const MAX_DEPTH: usize = 256;
+
+impl<'a> Decoder<'a> {
+    pub(crate) fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            position: 0,
+            depth: 0,
+        }
+    }
+
+    /// decodes a value, also returning the bytes it was read from.
+    pub(crate) fn value_with_span(&mut self) -> Result<(Bencode, Range<usize>), Error> {
+        let start = self.position;
+        let value = self.value()?;
+        Ok((value, start..self.position))
+    }
+
+    /// fails if there is anything after the decoded values.
+    pub(crate) fn end(&self) -> Result<(), Error> {
+        if self.position == self.data.len() {
+            Ok(())
+        } else {
+            Err(invalid(format!("unexpected data at byte {}", self.position)))
+        }
+    }
+
+    pub(crate) fn value(&mut self) -> Result<Bencode, Error> {
+        match self.peek()? {
+            b'i' => {
+                self.position += 1;
+                let integer = self.read_until(b'e')?;
+                let valid = !integer.is_empty()
+                    && integer != b"-0"
+                    && !(integer.len() > 1 && (integer.starts_with(b"0") || integer.starts_with(b"-0")));
+                let integer = std::str::from_utf8(integer)
+                    .ok()
+                    .filter(|_| valid)
+                    .and_then(|i| i.parse().ok())
+                    .ok_or_else(|| invalid(format!("invalid integer at byte {}", self.position)))?;
+                Ok(Bencode::Integer(integer))
+            }
+            b'0'..=b'9' => Ok(Bencode::Bytes(self.bytes()?.to_vec())),
+            b'l' => {
+                self.nest()?;
+                let mut list = Vec::new();
+                while self.peek()? != b'e' {
+                    list.push(self.value()?);
+                }
+                self.unnest();
+                Ok(Bencode::List(list))
+            }
+            b'd' => {
+                self.nest()?;
+                let mut dict = BTreeMap::new();
+                while self.peek()? != b'e' {
+                    let key = self.bytes()?.to_vec();
+                    let value = self.value()?;
+                    dict.insert(key, value);
+                }
+                self.unnest();
+                Ok(Bencode::Dict(dict))
+            }
+            byte => Err(invalid(format!(
+                "unexpected {:?} at byte {}",
+                byte as char, self.position
+            ))),
+        }
+    }
+
+    /// reads the start of a dictionary, for reading its entries one by one with `dict_key` and `value_with_span`.
+    pub(crate) fn dict_start(&mut self) -> Result<(), Error> {
+        if self.peek()? != b'd' {
+            return Err(invalid("expected a dictionary".to_string()));
+        }
+        self.position += 1;
+        Ok(())
+    }
+
+    /// reads the key of the next dictionary entry, or `None` at the end of the dictionary.
+    pub(crate) fn dict_key(&mut self) -> Result<Option<Vec<u8>>, Error> {
+        if self.peek()? == b'e' {
+            self.position += 1;
+            return Ok(None);
+        }
+        Ok(Some(self.bytes()?.to_vec()))
+    }
+
+    fn nest(&mut self) -> Result<(), Error> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(invalid("the data is nested too deeply".to_string()));
+        }
+        self.position += 1;
+        Ok(())
+    }
+
+    /// skips the closing `e` of a list or dictionary.
+    fn unnest(&mut self) {
+        self.depth -= 1;
+        self.position += 1;
+    }
+
+    fn bytes(&mut self) -> Result<&'a [u8], Error> {
+        let length = self.read_until(b':')?;
+        let length: usize = std::str::from_utf8(length)
+            .ok()
+            .filter(|l| !l.is_empty() && l.bytes().all(|b| b.is_ascii_digit()))
+            .and_then(|l| l.parse().ok())
+            .ok_or_else(|| invalid(format!("invalid string length at byte {}", self.position)))?;
+
+        let end = self
+            .position
+            .checked_add(length)
+            .filter(|end| *end <= self.data.len())
+            .ok_or_else(|| invalid("a string goes past the end of the data".to_string()))?;
+
+        let bytes = &self.data[self.position..end];
+        self.position = end;
+        Ok(bytes)
+    }
+
+    fn read_until(&mut self, delimiter: u8) -> Result<&'a [u8], Error> {
+        let rest = &self.data[self.position..];
+        let length = rest
+            .iter()
+            .position(|byte| *byte == delimiter)
+            .ok_or_else(|| invalid("unexpected end of data".to_string()))?;
+
+        self.position += length + 1;
+        Ok(&rest[..length])
+    }
+
+    fn peek(&self) -> Result<u8, Error> {
+        self.data
+            .get(self.position)
+            .copied()
+            .ok_or_else(|| invalid("unexpected end of data".to_string()))
+    }
+}
+
+fn invalid(reason: String) -> Error {
+    Error::build(ErrorType::InvalidTorrentFile(reason), None)
+}
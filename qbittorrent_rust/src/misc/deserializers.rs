@@ -0,0 +1,70 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer};
+
+/// qbittorrent reports an eta of 100 days (8640000 seconds) when it's infinite.
+const INFINITE_ETA: i64 = 8_640_000;
+
+/// deserializes a unix timestamp in seconds.
+pub(crate) fn timestamp<'de, D: Deserializer<'de>>(deserializer: D) -> Result<DateTime<Utc>, D::Error> {
+    let seconds = i64::deserialize(deserializer)?;
+    Ok(DateTime::from_timestamp(seconds, 0).unwrap_or_default())
+}
+
+/// deserializes a unix timestamp in seconds, where `0` or negative values mean "never".
+pub(crate) fn optional_timestamp<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<DateTime<Utc>>, D::Error> {
+    let seconds = i64::deserialize(deserializer)?;
+    if seconds <= 0 {
+        Ok(None)
+    } else {
+        Ok(DateTime::from_timestamp(seconds, 0))
+    }
+}
+
+/// deserializes an amount of seconds. negative values are treated as `0`.
+pub(crate) fn seconds<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+    let seconds = i64::deserialize(deserializer)?;
+    Ok(Duration::from_secs(seconds.max(0) as u64))
+}
+
+/// deserializes an eta in seconds, where the infinite eta becomes [`None`].
+pub(crate) fn eta<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Duration>, D::Error> {
+    let seconds = i64::deserialize(deserializer)?;
+    if !(0..INFINITE_ETA).contains(&seconds) {
+        Ok(None)
+    } else {
+        Ok(Some(Duration::from_secs(seconds as u64)))
+    }
+}
+
+/// deserializes a comma separated list, such as the tags of a torrent.
+pub(crate) fn comma_separated<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    let string = String::deserialize(deserializer)?;
+    Ok(string
+        .split(',')
+        .map(|item| item.trim())
+        .filter(|item| !item.is_empty())
+        .map(|item| item.to_string())
+        .collect())
+}
+
+/// deserializes an integer that older versions of qbittorrent may send as a string (eg: the tier of a tracker).
+/// empty strings and negative values become [`None`].
+pub(crate) fn optional_lenient_integer<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<u64>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Lenient {
+        Number(i64),
+        String(String),
+    }
+
+    Ok(match Lenient::deserialize(deserializer)? {
+        Lenient::Number(n) => u64::try_from(n).ok(),
+        Lenient::String(s) => s.trim().parse::<u64>().ok(),
+    })
+}
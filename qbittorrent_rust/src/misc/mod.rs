@@ -1,2 +1,4 @@
 #[allow(dead_code)]
-pub mod sep_vec;
\ No newline at end of file
+pub mod sep_vec;
+pub(crate) mod deserializers;
+pub mod bencode;
@@ -0,0 +1,316 @@
+use std::collections::HashMap;
+
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+
+/// a file sent in a `multipart/form-data` body.
+#[derive(Debug, Clone)]
+pub struct UploadedFile {
+    /// name of the form field.
+    pub field: String,
+    /// name of the file, as sent by the client.
+    pub file_name: String,
+    /// content of the file.
+    pub data: Vec<u8>,
+}
+
+/// an HTTP request received by the [`MockServer`](super::MockServer).
+#[derive(Debug, Clone)]
+pub struct MockRequest {
+    /// HTTP method, eg: `POST`.
+    pub method: String,
+    /// path without the query string, eg: `/api/v2/torrents/info`.
+    pub path: String,
+    /// header names are lowercase.
+    pub headers: HashMap<String, String>,
+    /// the parameters from both the query string and the form body.
+    /// parameters sent more than once (eg: `urls` in a multipart body) are joined with a newline.
+    pub params: HashMap<String, String>,
+    /// the files of a `multipart/form-data` body.
+    pub files: Vec<UploadedFile>,
+}
+
+impl MockRequest {
+    /// the value of a parameter.
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params.get(name).map(String::as_str)
+    }
+
+    /// the value of the `SID` cookie, if sent.
+    pub fn sid(&self) -> Option<&str> {
+        self.headers.get("cookie")?.split(';').find_map(|cookie| {
+            let (name, value) = cookie.trim().split_once('=')?;
+            (name == "SID").then_some(value)
+        })
+    }
+
+    fn insert_param(&mut self, name: String, value: String) {
+        self.params
+            .entry(name)
+            .and_modify(|existing| {
+                existing.push('\n');
+                existing.push_str(&value);
+            })
+            .or_insert(value);
+    }
+}
+
+/// an HTTP response sent by the [`MockServer`](super::MockServer).
+#[derive(Debug, Clone)]
+pub struct MockResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl MockResponse {
+    /// a `200 OK` response with a plain text body.
+    pub fn text(body: impl Into<String>) -> Self {
+        Self::status_text(200, body)
+    }
+
+    /// a response with the given status and a plain text body.
+    pub fn status_text(status: u16, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            headers: vec![("Content-Type".to_string(), "text/plain; charset=UTF-8".to_string())],
+            body: body.into().into_bytes(),
+        }
+    }
+
+    /// a `200 OK` response with a json body.
+    pub fn json(body: &serde_json::Value) -> Self {
+        Self {
+            status: 200,
+            headers: vec![("Content-Type".to_string(), "application/json".to_string())],
+            body: body.to_string().into_bytes(),
+        }
+    }
+
+    /// a `200 OK` response with a binary body.
+    pub fn bytes(body: Vec<u8>, content_type: &str) -> Self {
+        Self {
+            status: 200,
+            headers: vec![("Content-Type".to_string(), content_type.to_string())],
+            body,
+        }
+    }
+
+    /// an empty `200 OK` response.
+    pub fn ok() -> Self {
+        Self::text("")
+    }
+
+    /// adds a header to the response.
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            401 => "Unauthorized",
+            403 => "Forbidden",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            409 => "Conflict",
+            415 => "Unsupported Media Type",
+            500 => "Internal Server Error",
+            503 => "Service Unavailable",
+            _ => "",
+        }
+    }
+}
+
+/// reads a single request from the connection. returns `None` if the connection is closed or the request is malformed.
+pub(crate) async fn read_request(stream: &mut BufReader<TcpStream>) -> Option<MockRequest> {
+    let mut line = String::new();
+    if stream.read_line(&mut line).await.ok()? == 0 {
+        return None;
+    }
+
+    let mut parts = line.split_whitespace();
+    let method = parts.next()?.to_string();
+    let target = parts.next()?.to_string();
+
+    let mut headers = HashMap::new();
+    loop {
+        let mut line = String::new();
+        stream.read_line(&mut line).await.ok()?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        let (name, value) = line.split_once(':')?;
+        headers.insert(name.trim().to_lowercase(), value.trim().to_string());
+    }
+
+    let body = if headers
+        .get("transfer-encoding")
+        .is_some_and(|t| t.eq_ignore_ascii_case("chunked"))
+    {
+        read_chunked(stream).await?
+    } else {
+        let length = headers
+            .get("content-length")
+            .and_then(|l| l.parse::<usize>().ok())
+            .unwrap_or(0);
+        let mut body = vec![0; length];
+        stream.read_exact(&mut body).await.ok()?;
+        body
+    };
+
+    let (path, query) = match target.split_once('?') {
+        Some((path, query)) => (path.to_string(), Some(query.to_string())),
+        None => (target, None),
+    };
+
+    let mut request = MockRequest {
+        method,
+        path,
+        headers,
+        params: HashMap::new(),
+        files: Vec::new(),
+    };
+
+    if let Some(query) = query {
+        for (name, value) in parse_urlencoded(&query) {
+            request.insert_param(name, value);
+        }
+    }
+
+    let content_type = request.headers.get("content-type").cloned().unwrap_or_default();
+    if content_type.starts_with("application/x-www-form-urlencoded") {
+        for (name, value) in parse_urlencoded(&String::from_utf8_lossy(&body)) {
+            request.insert_param(name, value);
+        }
+    } else if content_type.starts_with("multipart/form-data") {
+        let boundary = content_type.split_once("boundary=")?.1.trim_matches('"').to_string();
+        parse_multipart(&mut request, &body, &boundary);
+    }
+
+    Some(request)
+}
+
+async fn read_chunked(stream: &mut BufReader<TcpStream>) -> Option<Vec<u8>> {
+    let mut body = Vec::new();
+    loop {
+        let mut line = String::new();
+        stream.read_line(&mut line).await.ok()?;
+        let size = usize::from_str_radix(line.trim().split(';').next()?, 16).ok()?;
+
+        let mut chunk = vec![0; size + 2];
+        stream.read_exact(&mut chunk).await.ok()?;
+        if size == 0 {
+            return Some(body);
+        }
+        body.extend_from_slice(&chunk[..size]);
+    }
+}
+
+pub(crate) async fn write_response(stream: &mut BufReader<TcpStream>, response: &MockResponse) -> std::io::Result<()> {
+    let mut head = format!("HTTP/1.1 {} {}\r\n", response.status, response.reason());
+    for (name, value) in &response.headers {
+        head.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    head.push_str(&format!("Content-Length: {}\r\nConnection: keep-alive\r\n\r\n", response.body.len()));
+
+    let stream = stream.get_mut();
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(&response.body).await?;
+    stream.flush().await
+}
+
+/// decodes `application/x-www-form-urlencoded` data.
+pub(crate) fn parse_urlencoded(data: &str) -> Vec<(String, String)> {
+    data.split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(name), percent_decode(value))
+        })
+        .collect()
+}
+
+pub(crate) fn percent_decode(data: &str) -> String {
+    let bytes = data.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                match std::str::from_utf8(&bytes[i + 1..i + 3])
+                    .ok()
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                {
+                    Some(byte) => {
+                        decoded.push(byte);
+                        i += 2;
+                    }
+                    None => decoded.push(b'%'),
+                }
+            }
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn parse_multipart(request: &mut MockRequest, body: &[u8], boundary: &str) {
+    let delimiter = format!("--{}", boundary);
+    let delimiter = delimiter.as_bytes();
+
+    let mut rest = body;
+    while let Some(start) = find(rest, delimiter) {
+        rest = &rest[start + delimiter.len()..];
+        if rest.starts_with(b"--") {
+            break;
+        }
+        let rest_of_part = rest.strip_prefix(b"\r\n").unwrap_or(rest);
+        let Some(end) = find(rest_of_part, delimiter) else {
+            break;
+        };
+
+        let part = &rest_of_part[..end];
+        let part = part.strip_suffix(b"\r\n").unwrap_or(part);
+        rest = &rest_of_part[end..];
+
+        let Some(head_end) = find(part, b"\r\n\r\n") else {
+            continue;
+        };
+        let head = String::from_utf8_lossy(&part[..head_end]);
+        let data = &part[head_end + 4..];
+
+        let disposition = head
+            .lines()
+            .find(|line| line.to_lowercase().starts_with("content-disposition"))
+            .unwrap_or_default();
+        let Some(name) = disposition_param(disposition, "name") else {
+            continue;
+        };
+
+        match disposition_param(disposition, "filename") {
+            Some(file_name) => request.files.push(UploadedFile {
+                field: name,
+                file_name,
+                data: data.to_vec(),
+            }),
+            None => request.insert_param(name, String::from_utf8_lossy(data).into_owned()),
+        }
+    }
+}
+
+fn disposition_param(disposition: &str, name: &str) -> Option<String> {
+    disposition.split(';').find_map(|param| {
+        let (key, value) = param.trim().split_once('=')?;
+        (key == name).then(|| value.trim_matches('"').to_string())
+    })
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
@@ -0,0 +1,15 @@
+//! # Testing
+//!
+//! an in-process mock of the qbittorrent WebUI API, to test code using [`QbitApi`](crate::core::api::QbitApi) without a real qbittorrent.
+//! requires the `testing` feature.
+//!
+//! start a [`MockServer`], point a [`QbitApi`](crate::core::api::QbitApi) at [`MockServer::url`],
+//! and use [`MockServer::state`] to set up the server or check what the requests did to it.
+
+mod http;
+mod server;
+mod state;
+
+pub use http::{MockRequest, MockResponse, UploadedFile};
+pub use server::MockServer;
+pub use state::{MockCreationTask, MockFile, MockSearchJob, MockState, MockTorrent};
@@ -0,0 +1,121 @@
+use std::{net::SocketAddr, sync::Arc};
+
+use tokio::{
+    io::BufReader,
+    net::TcpListener,
+    sync::{Mutex, MutexGuard},
+    task::JoinHandle,
+};
+
+use super::{
+    http::{read_request, write_response},
+    state::MockState,
+};
+use crate::core::creds::Credentials;
+
+/// ## Description
+/// an in-process mock of the qbittorrent WebUI API, listening on a random local port.
+///
+/// it logs in with `admin`/`adminadmin` by default, rejects requests without a valid `SID` cookie with `403 Forbidden`,
+/// and keeps torrents, categories, tags, RSS and search in a [`MockState`], which tests can read and change.
+/// unknown endpoints answer `404 Not Found`.
+///
+/// the server stops when the [`MockServer`] is dropped.
+///
+/// ## Example
+/// ```
+/// # use qbittorrent_rust::{core::api::QbitApi, testing::MockServer};
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), qbittorrent_rust::Error> {
+/// let server = MockServer::start().await;
+/// let api = QbitApi::new(server.url(), server.credentials()).await?;
+///
+/// api.torrents_add_new_category("movies", Some("/movies")).await?;
+/// assert!(server.state().await.categories.contains_key("movies"));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct MockServer {
+    address: SocketAddr,
+    credentials: Credentials,
+    state: Arc<Mutex<MockState>>,
+    task: JoinHandle<()>,
+}
+
+impl MockServer {
+    /// # Usage
+    /// starts a [`MockServer`] accepting the username `admin` and the password `adminadmin`.
+    pub async fn start() -> Self {
+        Self::with_credentials("admin", "adminadmin").await
+    }
+
+    /// # Usage
+    /// starts a [`MockServer`] accepting the given username and password.
+    ///
+    /// # Panics
+    /// if no local port can be bound.
+    pub async fn with_credentials(username: impl Into<String>, password: impl Into<String>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("the mock server couldn't bind a local port");
+        let address = listener.local_addr().expect("the mock server has no local address");
+        let (username, password) = (username.into(), password.into());
+        let credentials = Credentials::new(username.clone(), password.clone());
+        let state = Arc::new(Mutex::new(MockState::new(username, password)));
+
+        let task_state = state.clone();
+        let task = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let state = task_state.clone();
+                tokio::spawn(async move {
+                    let mut stream = BufReader::new(stream);
+                    while let Some(request) = read_request(&mut stream).await {
+                        let (response, delay) = {
+                            let mut state = state.lock().await;
+                            state.in_flight += 1;
+                            state.max_in_flight = state.max_in_flight.max(state.in_flight);
+                            (state.handle(&request), state.response_delay)
+                        };
+                        if !delay.is_zero() {
+                            tokio::time::sleep(delay).await;
+                        }
+                        state.lock().await.in_flight -= 1;
+
+                        if write_response(&mut stream, &response).await.is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+
+        Self {
+            address,
+            credentials,
+            state,
+            task,
+        }
+    }
+
+    /// the authority to pass to [`QbitApi::new`](crate::core::api::QbitApi::new), eg: `http://127.0.0.1:41234`.
+    pub fn url(&self) -> String {
+        format!("http://{}", self.address)
+    }
+
+    /// the credentials the server was started with.
+    pub fn credentials(&self) -> Credentials {
+        self.credentials.clone()
+    }
+
+    /// the state of the server. the server waits while the guard is held.
+    pub async fn state(&self) -> MutexGuard<'_, MockState> {
+        self.state.lock().await
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
@@ -0,0 +1,1678 @@
+use std::{
+    collections::{hash_map::DefaultHasher, BTreeMap, BTreeSet, HashSet, VecDeque},
+    hash::{Hash, Hasher},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde_json::{json, Map, Value};
+
+use super::http::{MockRequest, MockResponse};
+use crate::{
+    api_fns::torrents::{info::TorrentHash, magnet::MagnetLink, metainfo::Metainfo},
+    core::version::ApiVersion,
+};
+
+/// a file of a [`MockTorrent`].
+#[derive(Debug, Clone)]
+pub struct MockFile {
+    pub name: String,
+    pub size: i64,
+    pub progress: f64,
+    pub priority: i64,
+}
+
+/// a torrent known to the [`MockServer`](super::MockServer).
+#[derive(Debug, Clone)]
+pub struct MockTorrent {
+    pub hash: String,
+    pub name: String,
+    pub state: String,
+    pub save_path: String,
+    pub download_path: String,
+    pub category: String,
+    pub tags: BTreeSet<String>,
+    pub added_on: i64,
+    pub progress: f64,
+    pub priority: i64,
+    pub dl_limit: i64,
+    pub up_limit: i64,
+    pub ratio_limit: f64,
+    pub seeding_time_limit: i64,
+    pub auto_tmm: bool,
+    pub force_start: bool,
+    pub seq_dl: bool,
+    pub f_l_piece_prio: bool,
+    pub super_seeding: bool,
+    pub magnet_uri: String,
+    pub trackers: Vec<String>,
+    pub web_seeds: Vec<String>,
+    pub files: Vec<MockFile>,
+}
+
+impl MockTorrent {
+    /// a torrent with a single 1 MiB file named after the torrent, stalled while downloading.
+    pub fn new(hash: impl Into<String>, name: impl Into<String>) -> Self {
+        let hash = hash.into();
+        let name = name.into();
+        Self {
+            magnet_uri: format!("magnet:?xt=urn:btih:{}&dn={}", hash, name),
+            files: vec![MockFile {
+                name: name.clone(),
+                size: 1 << 20,
+                progress: 0.0,
+                priority: 1,
+            }],
+            hash,
+            name,
+            state: "stalledDL".to_string(),
+            save_path: "/downloads".to_string(),
+            download_path: String::new(),
+            category: String::new(),
+            tags: BTreeSet::new(),
+            added_on: now(),
+            progress: 0.0,
+            priority: 0,
+            dl_limit: -1,
+            up_limit: -1,
+            ratio_limit: -2.0,
+            seeding_time_limit: -2,
+            auto_tmm: false,
+            force_start: false,
+            seq_dl: false,
+            f_l_piece_prio: false,
+            super_seeding: false,
+            trackers: Vec::new(),
+            web_seeds: Vec::new(),
+        }
+    }
+
+    /// a bencoded `.torrent` file describing the torrent.
+    fn export(&self) -> Vec<u8> {
+        let mut files = String::new();
+        for file in &self.files {
+            files.push_str(&format!("d6:lengthi{}e4:pathl{}:{}ee", file.size, file.name.len(), file.name));
+        }
+        format!(
+            "d8:announce0:4:infod5:filesl{}e4:name{}:{}12:piece lengthi{}e6:pieces0:ee",
+            files,
+            self.name.len(),
+            self.name,
+            16384
+        )
+        .into_bytes()
+    }
+
+    fn size(&self) -> i64 {
+        self.files.iter().filter(|f| f.priority > 0).map(|f| f.size).sum()
+    }
+
+    fn total_size(&self) -> i64 {
+        self.files.iter().map(|f| f.size).sum()
+    }
+
+    fn is_paused(&self) -> bool {
+        self.state.starts_with("paused") || self.state.starts_with("stopped")
+    }
+
+    fn is_complete(&self) -> bool {
+        self.progress >= 1.0
+    }
+
+    /// the torrent as listed by `/torrents/info`.
+    pub fn info(&self) -> Value {
+        let size = self.size();
+        let completed = (size as f64 * self.progress) as i64;
+        // split in two, since a single `json!` this big exceeds the recursion limit.
+        let mut info = json!({
+            "hash": self.hash,
+            "infohash_v1": self.hash,
+            "infohash_v2": "",
+            "name": self.name,
+            "added_on": self.added_on,
+            "amount_left": size - completed,
+            "auto_tmm": self.auto_tmm,
+            "availability": if self.is_complete() { -1.0 } else { 0.0 },
+            "category": self.category,
+            "completed": completed,
+            "completion_on": if self.is_complete() { self.added_on } else { -1 },
+            "content_path": format!("{}/{}", self.save_path, self.name),
+            "dl_limit": self.dl_limit,
+            "dlspeed": 0,
+            "download_path": self.download_path,
+            "downloaded": completed,
+            "downloaded_session": 0,
+            "eta": 8_640_000,
+            "f_l_piece_prio": self.f_l_piece_prio,
+            "force_start": self.force_start,
+            "is_private": false,
+            "last_activity": 0,
+            "magnet_uri": self.magnet_uri,
+        });
+        let rest = json!({
+            "max_ratio": -1,
+            "max_seeding_time": -1,
+            "num_complete": 0,
+            "num_incomplete": 0,
+            "num_leechs": 0,
+            "num_seeds": 0,
+            "priority": self.priority,
+            "progress": self.progress,
+            "ratio": 0,
+            "ratio_limit": self.ratio_limit,
+            "reannounce": 0,
+            "save_path": self.save_path,
+            "seeding_time": 0,
+            "seeding_time_limit": self.seeding_time_limit,
+            "seen_complete": -1,
+            "seq_dl": self.seq_dl,
+            "size": size,
+            "state": self.state,
+            "super_seeding": self.super_seeding,
+            "tags": self.tags.iter().cloned().collect::<Vec<_>>().join(", "),
+            "time_active": 0,
+            "total_size": self.total_size(),
+            "tracker": self.trackers.first().cloned().unwrap_or_default(),
+            "trackers_count": self.trackers.len(),
+            "up_limit": self.up_limit,
+            "uploaded": 0,
+            "uploaded_session": 0,
+            "upspeed": 0,
+        });
+
+        if let (Some(info), Value::Object(rest)) = (info.as_object_mut(), rest) {
+            info.extend(rest);
+        }
+        info
+    }
+
+    fn properties(&self) -> Value {
+        json!({
+            "save_path": self.save_path,
+            "creation_date": self.added_on,
+            "piece_size": 16384,
+            "comment": "",
+            "total_wasted": 0,
+            "total_uploaded": 0,
+            "total_uploaded_session": 0,
+            "total_downloaded": 0,
+            "total_downloaded_session": 0,
+            "up_limit": self.up_limit,
+            "dl_limit": self.dl_limit,
+            "time_elapsed": 0,
+            "seeding_time": 0,
+            "nb_connections": 0,
+            "nb_connections_limit": 100,
+            "share_ratio": 0,
+            "addition_date": self.added_on,
+            "completion_date": if self.is_complete() { self.added_on } else { -1 },
+            "created_by": "",
+            "dl_speed_avg": 0,
+            "dl_speed": 0,
+            "eta": 8_640_000,
+            "last_seen": -1,
+            "peers": 0,
+            "peers_total": 0,
+            "pieces_have": (self.pieces() as f64 * self.progress) as i64,
+            "pieces_num": self.pieces(),
+            "reannounce": 0,
+            "seeds": 0,
+            "seeds_total": 0,
+            "total_size": self.total_size(),
+            "up_speed_avg": 0,
+            "up_speed": 0,
+            "download_path": self.download_path,
+            "hash": self.hash,
+            "infohash_v1": self.hash,
+            "infohash_v2": "",
+            "name": self.name,
+        })
+    }
+
+    fn pieces(&self) -> i64 {
+        (self.total_size() + 16383) / 16384
+    }
+
+    fn trackers(&self) -> Value {
+        let mut trackers: Vec<Value> = ["** [DHT] **", "** [PeX] **", "** [LSD] **"]
+            .iter()
+            .map(|url| {
+                json!({
+                    "url": url, "status": 2, "tier": -1, "num_peers": 0, "num_seeds": 0,
+                    "num_leeches": 0, "num_downloaded": 0, "msg": ""
+                })
+            })
+            .collect();
+        trackers.extend(self.trackers.iter().enumerate().map(|(tier, url)| {
+            json!({
+                "url": url, "status": 1, "tier": tier, "num_peers": 0, "num_seeds": 0,
+                "num_leeches": 0, "num_downloaded": 0, "msg": ""
+            })
+        }));
+        Value::Array(trackers)
+    }
+
+    fn files_json(&self) -> Value {
+        let mut first_piece = 0;
+        let files = self
+            .files
+            .iter()
+            .enumerate()
+            .map(|(index, file)| {
+                let pieces = ((file.size + 16383) / 16384).max(1);
+                let range = [first_piece, first_piece + pieces - 1];
+                first_piece += pieces;
+                json!({
+                    "index": index,
+                    "name": file.name,
+                    "size": file.size,
+                    "progress": file.progress,
+                    "priority": file.priority,
+                    "is_seed": file.progress >= 1.0,
+                    "piece_range": range,
+                    "availability": 0,
+                })
+            })
+            .collect();
+        Value::Array(files)
+    }
+}
+
+/// a search job of the [`MockServer`](super::MockServer).
+#[derive(Debug, Clone)]
+pub struct MockSearchJob {
+    pub pattern: String,
+    pub status: String,
+    pub results: Vec<Value>,
+    /// the results not found yet: each `/search/results` request finds one more, then the job stops.
+    pub pending: VecDeque<Value>,
+}
+
+/// a torrent creation task of the [`MockServer`](super::MockServer). tasks are created already `Finished`.
+#[derive(Debug, Clone)]
+pub struct MockCreationTask {
+    pub source_path: String,
+    pub private: bool,
+    pub trackers: Vec<String>,
+    /// `Queued`, `Running`, `Finished` or `Failed`.
+    pub status: String,
+    pub error_message: String,
+}
+
+impl MockCreationTask {
+    fn status_json(&self, id: &str) -> Value {
+        json!({
+            "taskID": id,
+            "sourcePath": self.source_path,
+            "pieceSize": 16384,
+            "private": self.private,
+            "timeAdded": "Thu Jan 1 00:00:00 2026",
+            "status": self.status,
+            "progress": if self.status == "Finished" { 100 } else { 0 },
+            "errorMessage": self.error_message,
+            "trackers": self.trackers,
+        })
+    }
+
+    /// a bencoded `.torrent` file for the source path.
+    fn torrent(&self) -> Vec<u8> {
+        let name = self.source_path.rsplit('/').next().unwrap_or_default();
+        let announce = self.trackers.first().cloned().unwrap_or_default();
+        format!(
+            "d8:announce{}:{}4:infod6:lengthi0e4:name{}:{}12:piece lengthi16384e6:pieces0:7:privatei{}eee",
+            announce.len(),
+            announce,
+            name.len(),
+            name,
+            self.private as u8
+        )
+        .into_bytes()
+    }
+}
+
+/// ## Description
+/// the in-memory model behind a [`MockServer`](super::MockServer).
+///
+/// every field is public, so tests can set up the server and check what happened to it.
+#[derive(Debug)]
+pub struct MockState {
+    pub username: String,
+    pub password: String,
+    /// when `true`, requests without a valid session are accepted, like with `bypass_local_auth`.
+    pub bypass_auth: bool,
+    /// the session ids currently logged in.
+    pub sessions: HashSet<String>,
+    pub version: String,
+    pub web_api_version: String,
+    pub preferences: Map<String, Value>,
+    pub torrents: BTreeMap<String, MockTorrent>,
+    /// category name → save path.
+    pub categories: BTreeMap<String, String>,
+    pub tags: BTreeSet<String>,
+    pub global_dl_limit: i64,
+    pub global_up_limit: i64,
+    pub alternative_speed_limits: bool,
+    pub banned_peers: Vec<String>,
+    /// the RSS tree, as returned by `/rss/items?withData=true`. folders are objects without an `uid`.
+    pub rss_items: Map<String, Value>,
+    pub rss_rules: Map<String, Value>,
+    pub search_plugins: Vec<Value>,
+    /// how many `/search/plugins` requests an installed plugin stays unlisted for, like qbittorrent installing it in the background.
+    pub plugin_install_polls: usize,
+    /// the plugins being installed, with the number of `/search/plugins` requests left before they're listed.
+    pub installing_plugins: Vec<(usize, Value)>,
+    /// what search jobs find: every entry whose `fileName` contains the pattern (case insensitive).
+    pub search_catalog: Vec<Value>,
+    pub search_jobs: BTreeMap<u64, MockSearchJob>,
+    /// task id → task. only served when `web_api_version` is at least 2.11.
+    pub creation_tasks: BTreeMap<String, MockCreationTask>,
+    /// every request received, in order, as `(method, path)`.
+    pub requests: Vec<(String, String)>,
+    /// statuses to answer the next authenticated requests with, in order, instead of handling them. eg: `503` to test retries.
+    pub failures: VecDeque<u16>,
+    /// how long every answer is held back, without holding the state. eg: to have many requests in flight.
+    pub response_delay: Duration,
+    /// how many requests are being answered right now.
+    pub in_flight: usize,
+    /// the most requests that were ever being answered at the same time.
+    pub max_in_flight: usize,
+    pub shut_down: bool,
+    rid: u64,
+    next_search_id: u64,
+    next_session: u64,
+}
+
+impl MockState {
+    pub(crate) fn new(username: String, password: String) -> Self {
+        let preferences = json!({
+            "locale": "en",
+            "save_path": "/downloads",
+            "temp_path_enabled": false,
+            "temp_path": "/downloads/temp",
+            "queueing_enabled": true,
+            "max_active_downloads": 3,
+            "max_active_torrents": 5,
+            "max_active_uploads": 3,
+            "dl_limit": 0,
+            "up_limit": 0,
+            "web_ui_port": 8080,
+            "web_ui_username": username,
+            "web_ui_session_timeout": 3600,
+            "web_ui_max_auth_fail_count": 5,
+            "web_ui_ban_duration": 3600,
+            "bypass_local_auth": false,
+            "bypass_auth_subnet_whitelist_enabled": false,
+            "bypass_auth_subnet_whitelist": "",
+            "rss_processing_enabled": false,
+            "rss_auto_downloading_enabled": false,
+        });
+
+        Self {
+            username,
+            password,
+            bypass_auth: false,
+            sessions: HashSet::new(),
+            version: "v4.6.7".to_string(),
+            web_api_version: "2.9.3".to_string(),
+            preferences: preferences.as_object().cloned().unwrap_or_default(),
+            torrents: BTreeMap::new(),
+            categories: BTreeMap::new(),
+            tags: BTreeSet::new(),
+            global_dl_limit: 0,
+            global_up_limit: 0,
+            alternative_speed_limits: false,
+            banned_peers: Vec::new(),
+            rss_items: Map::new(),
+            rss_rules: Map::new(),
+            search_plugins: vec![json!({
+                "enabled": true,
+                "fullName": "Mock search",
+                "name": "mock",
+                "supportedCategories": [{"id": "all", "name": "All categories"}],
+                "url": "http://mock.invalid",
+                "version": "1.0",
+            })],
+            plugin_install_polls: 0,
+            installing_plugins: Vec::new(),
+            search_catalog: Vec::new(),
+            search_jobs: BTreeMap::new(),
+            creation_tasks: BTreeMap::new(),
+            requests: Vec::new(),
+            failures: VecDeque::new(),
+            response_delay: Duration::ZERO,
+            in_flight: 0,
+            max_in_flight: 0,
+            shut_down: false,
+            rid: 0,
+            next_search_id: 1,
+            next_session: 1,
+        }
+    }
+
+    /// adds a torrent, replacing any torrent with the same hash.
+    pub fn insert_torrent(&mut self, torrent: MockTorrent) {
+        self.torrents.insert(torrent.hash.clone(), torrent);
+    }
+
+    /// logs every session out, like a restart of qbittorrent does.
+    pub fn expire_sessions(&mut self) {
+        self.sessions.clear();
+    }
+
+    pub(crate) fn handle(&mut self, request: &MockRequest) -> MockResponse {
+        self.requests.push((request.method.clone(), request.path.clone()));
+
+        let Some(endpoint) = request.path.strip_prefix("/api/v2/") else {
+            return not_found();
+        };
+
+        match endpoint {
+            "auth/login" => return self.login(request),
+            "auth/logout" => {
+                if let Some(sid) = request.sid() {
+                    self.sessions.remove(sid);
+                }
+                return MockResponse::ok();
+            }
+            _ => {}
+        }
+
+        let authorized = self.bypass_auth || request.sid().is_some_and(|sid| self.sessions.contains(sid));
+        if !authorized {
+            return MockResponse::status_text(403, "Forbidden");
+        }
+
+        if let Some(status) = self.failures.pop_front() {
+            return MockResponse::status_text(status, "Injected failure");
+        }
+
+        let (category, method) = endpoint.split_once('/').unwrap_or((endpoint, ""));
+        match category {
+            "app" => self.app(method, request),
+            "log" => MockResponse::json(&json!([])),
+            "sync" => self.sync(method, request),
+            "transfer" => self.transfer(method, request),
+            "torrents" => self.torrents(method, request),
+            "rss" => self.rss(method, request),
+            "search" => self.search(method, request),
+            "torrentcreator" if self.is_v5() => self.torrent_creator(method, request),
+            _ => not_found(),
+        }
+    }
+
+    fn torrent_creator(&mut self, method: &str, request: &MockRequest) -> MockResponse {
+        if method == "addTask" {
+            let Some(source_path) = request.param("sourcePath").filter(|p| !p.is_empty()) else {
+                return MockResponse::status_text(400, "Bad Request");
+            };
+            let id = fake_hash(&(source_path, self.creation_tasks.len()))[..16].to_string();
+            self.creation_tasks.insert(
+                id.clone(),
+                MockCreationTask {
+                    source_path: source_path.to_string(),
+                    private: request.param("private") == Some("true"),
+                    trackers: request
+                        .param("trackers")
+                        .unwrap_or_default()
+                        .split('|')
+                        .filter(|t| !t.is_empty())
+                        .map(str::to_string)
+                        .collect(),
+                    status: "Finished".to_string(),
+                    error_message: String::new(),
+                },
+            );
+            return MockResponse::json(&json!({ "taskID": id }));
+        }
+
+        let task_id = request.param("taskID").unwrap_or_default().to_string();
+        if method == "status" && task_id.is_empty() {
+            let tasks: Vec<Value> = self.creation_tasks.iter().map(|(id, task)| task.status_json(id)).collect();
+            return MockResponse::json(&Value::Array(tasks));
+        }
+
+        let Some(task) = self.creation_tasks.get(&task_id) else {
+            return not_found();
+        };
+        match method {
+            "status" => MockResponse::json(&json!([task.status_json(&task_id)])),
+            "torrentFile" if task.status != "Finished" => MockResponse::status_text(409, "Conflict"),
+            "torrentFile" => MockResponse::bytes(task.torrent(), "application/x-bittorrent"),
+            "deleteTask" => {
+                self.creation_tasks.remove(&task_id);
+                MockResponse::ok()
+            }
+            _ => not_found(),
+        }
+    }
+
+    fn login(&mut self, request: &MockRequest) -> MockResponse {
+        if request.param("username") != Some(self.username.as_str())
+            || request.param("password") != Some(self.password.as_str())
+        {
+            return MockResponse::text("Fails.");
+        }
+
+        let sid = fake_hash(&(self.next_session, &self.username))[..32].to_string();
+        self.next_session += 1;
+        self.sessions.insert(sid.clone());
+
+        MockResponse::text("Ok.").with_header("Set-Cookie", format!("SID={}; HttpOnly; path=/", sid))
+    }
+
+    fn app(&mut self, method: &str, request: &MockRequest) -> MockResponse {
+        match method {
+            "version" => MockResponse::text(self.version.clone()),
+            "webapiVersion" => MockResponse::text(self.web_api_version.clone()),
+            "buildInfo" => MockResponse::json(&json!({
+                "qt": "6.4.2", "libtorrent": "2.0.9.0", "boost": "1.83.0",
+                "openssl": "3.1.4", "zlib": "1.3", "bitness": 64
+            })),
+            "preferences" => MockResponse::json(&Value::Object(self.preferences.clone())),
+            "setPreferences" => {
+                let Some(Value::Object(changes)) = request.param("json").and_then(|j| serde_json::from_str(j).ok())
+                else {
+                    return MockResponse::status_text(400, "Bad Request");
+                };
+                self.preferences.extend(changes);
+                MockResponse::ok()
+            }
+            "defaultSavePath" => MockResponse::text(
+                self.preferences
+                    .get("save_path")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string(),
+            ),
+            "shutdown" => {
+                self.shut_down = true;
+                MockResponse::ok()
+            }
+            _ => not_found(),
+        }
+    }
+
+    fn sync(&mut self, method: &str, request: &MockRequest) -> MockResponse {
+        self.rid += 1;
+        match method {
+            "maindata" => {
+                let torrents: Map<String, Value> = self
+                    .torrents
+                    .values()
+                    .map(|t| {
+                        let mut info = t.info();
+                        if let Some(info) = info.as_object_mut() {
+                            info.remove("hash");
+                        }
+                        (t.hash.clone(), info)
+                    })
+                    .collect();
+                let categories: Map<String, Value> = self
+                    .categories
+                    .iter()
+                    .map(|(name, path)| (name.clone(), json!({"name": name, "savePath": path})))
+                    .collect();
+
+                MockResponse::json(&json!({
+                    "rid": self.rid,
+                    "full_update": true,
+                    "torrents": torrents,
+                    "categories": categories,
+                    "tags": self.tags,
+                    "server_state": self.transfer_info(),
+                }))
+            }
+            "torrentPeers" => match request.param("hash") {
+                Some(hash) if self.torrents.contains_key(hash) => MockResponse::json(&json!({
+                    "rid": self.rid, "full_update": true, "show_flags": true, "peers": {}
+                })),
+                _ => not_found(),
+            },
+            _ => not_found(),
+        }
+    }
+
+    fn transfer_info(&self) -> Value {
+        json!({
+            "dl_info_speed": 0,
+            "dl_info_data": 0,
+            "up_info_speed": 0,
+            "up_info_data": 0,
+            "dl_rate_limit": self.global_dl_limit,
+            "up_rate_limit": self.global_up_limit,
+            "dht_nodes": 0,
+            "connection_status": "connected",
+            "use_alt_speed_limits": self.alternative_speed_limits,
+        })
+    }
+
+    fn transfer(&mut self, method: &str, request: &MockRequest) -> MockResponse {
+        match method {
+            "info" => MockResponse::json(&self.transfer_info()),
+            "speedLimitsMode" => MockResponse::text(if self.alternative_speed_limits { "1" } else { "0" }),
+            "toggleSpeedLimitsMode" => {
+                self.alternative_speed_limits = !self.alternative_speed_limits;
+                MockResponse::ok()
+            }
+            "downloadLimit" => MockResponse::text(self.global_dl_limit.to_string()),
+            "uploadLimit" => MockResponse::text(self.global_up_limit.to_string()),
+            "setDownloadLimit" | "setUploadLimit" => {
+                let Some(limit) = request.param("limit").and_then(|l| l.parse().ok()) else {
+                    return MockResponse::status_text(400, "Bad Request");
+                };
+                if method == "setDownloadLimit" {
+                    self.global_dl_limit = limit;
+                } else {
+                    self.global_up_limit = limit;
+                }
+                MockResponse::ok()
+            }
+            "banPeers" => {
+                self.banned_peers
+                    .extend(request.param("peers").unwrap_or_default().split('|').map(str::to_string));
+                MockResponse::ok()
+            }
+            _ => not_found(),
+        }
+    }
+
+    /// whether the server acts like qbittorrent 5, according to `web_api_version`.
+    fn is_v5(&self) -> bool {
+        self.web_api_version
+            .parse::<ApiVersion>()
+            .is_ok_and(|version| version >= ApiVersion::V2_11_0)
+    }
+
+    /// whether the server acts like qbittorrent 5.1, which added `/torrents/setTags`.
+    fn is_v5_1(&self) -> bool {
+        self.web_api_version
+            .parse::<ApiVersion>()
+            .is_ok_and(|version| version >= ApiVersion::V2_11_4)
+    }
+
+    /// the hashes selected by the `hashes` parameter, which can be `all`.
+    fn selected(&self, request: &MockRequest) -> Vec<String> {
+        match request.param("hashes").or(request.param("hash")).or(request.param("id")) {
+            Some("all") => self.torrents.keys().cloned().collect(),
+            Some(hashes) => hashes
+                .split('|')
+                .filter(|h| self.torrents.contains_key(*h))
+                .map(str::to_string)
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    fn for_selected(&mut self, request: &MockRequest, f: impl Fn(&mut MockTorrent)) -> MockResponse {
+        for hash in self.selected(request) {
+            if let Some(torrent) = self.torrents.get_mut(&hash) {
+                f(torrent);
+            }
+        }
+        MockResponse::ok()
+    }
+
+    fn torrent(&mut self, request: &MockRequest) -> Option<&mut MockTorrent> {
+        self.torrents.get_mut(request.param("hash")?)
+    }
+
+    fn torrents(&mut self, method: &str, request: &MockRequest) -> MockResponse {
+        match method {
+            "info" => MockResponse::json(&self.torrent_list(request)),
+            "properties" | "trackers" | "webseeds" | "files" | "pieceStates" | "pieceHashes" => {
+                let Some(torrent) = self.torrent(request) else {
+                    return not_found();
+                };
+                MockResponse::json(&match method {
+                    "properties" => torrent.properties(),
+                    "trackers" => torrent.trackers(),
+                    "webseeds" => torrent.web_seeds.iter().map(|url| json!({"url": url})).collect(),
+                    "files" => torrent.files_json(),
+                    "pieceStates" => {
+                        let state = if torrent.is_complete() { 2 } else { 0 };
+                        Value::Array(vec![json!(state); torrent.pieces() as usize])
+                    }
+                    _ => Value::Array(
+                        (0..torrent.pieces())
+                            .map(|piece| json!(fake_hash(&(&torrent.hash, piece))))
+                            .collect(),
+                    ),
+                })
+            }
+            "add" => self.add_torrents(request),
+            "delete" => {
+                for hash in self.selected(request) {
+                    self.torrents.remove(&hash);
+                }
+                MockResponse::ok()
+            }
+            // qbittorrent 5 renamed pause and resume, and the paused states.
+            "pause" | "stop" if (method == "stop") == self.is_v5() => {
+                let prefix = if self.is_v5() { "stopped" } else { "paused" };
+                self.for_selected(request, |t| {
+                    t.state = format!("{}{}", prefix, if t.is_complete() { "UP" } else { "DL" })
+                })
+            }
+            "resume" | "start" if (method == "start") == self.is_v5() => self.for_selected(request, |t| {
+                t.state = if t.is_complete() { "stalledUP" } else { "stalledDL" }.to_string()
+            }),
+            "recheck" | "reannounce" => MockResponse::ok(),
+            "addPeers" => {
+                if self.selected(request).is_empty() {
+                    return MockResponse::status_text(400, "Bad Request");
+                }
+                MockResponse::ok()
+            }
+            "addTrackers" => {
+                let urls: Vec<String> = request.param("urls").unwrap_or_default().lines().map(str::to_string).collect();
+                let Some(torrent) = self.torrent(request) else {
+                    return not_found();
+                };
+                for url in urls {
+                    if !torrent.trackers.contains(&url) {
+                        torrent.trackers.push(url);
+                    }
+                }
+                MockResponse::ok()
+            }
+            "editTracker" => {
+                let (orig, new) = (request.param("origUrl").map(str::to_string), request.param("newUrl").map(str::to_string));
+                let Some(torrent) = self.torrent(request) else {
+                    return not_found();
+                };
+                let (Some(orig), Some(new)) = (orig, new) else {
+                    return MockResponse::status_text(400, "Bad Request");
+                };
+                if torrent.trackers.contains(&new) {
+                    return MockResponse::status_text(409, "Conflict");
+                }
+                match torrent.trackers.iter_mut().find(|t| **t == orig) {
+                    Some(tracker) => {
+                        *tracker = new;
+                        MockResponse::ok()
+                    }
+                    None => MockResponse::status_text(409, "Conflict"),
+                }
+            }
+            "removeTrackers" => {
+                let urls: Vec<String> = request.param("urls").unwrap_or_default().split('|').map(str::to_string).collect();
+                let Some(torrent) = self.torrent(request) else {
+                    return not_found();
+                };
+                let before = torrent.trackers.len();
+                torrent.trackers.retain(|t| !urls.contains(t));
+                if torrent.trackers.len() == before {
+                    return MockResponse::status_text(409, "Conflict");
+                }
+                MockResponse::ok()
+            }
+            "increasePrio" | "decreasePrio" | "topPrio" | "bottomPrio" => {
+                if !self.queueing_enabled() {
+                    return MockResponse::status_text(409, "Conflict");
+                }
+                let selected = self.selected(request);
+                self.reprioritize(method, &selected);
+                MockResponse::ok()
+            }
+            "filePrio" => {
+                let priority: Option<i64> = request.param("priority").and_then(|p| p.parse().ok());
+                let ids: Vec<usize> = request
+                    .param("id")
+                    .unwrap_or_default()
+                    .split('|')
+                    .filter_map(|id| id.parse().ok())
+                    .collect();
+                let Some(torrent) = self.torrent(request) else {
+                    return not_found();
+                };
+                let Some(priority @ (0 | 1 | 6 | 7)) = priority else {
+                    return MockResponse::status_text(400, "Bad Request");
+                };
+                if ids.iter().any(|id| *id >= torrent.files.len()) {
+                    return MockResponse::status_text(409, "Conflict");
+                }
+                for id in ids {
+                    torrent.files[id].priority = priority;
+                }
+                MockResponse::ok()
+            }
+            "downloadLimit" | "uploadLimit" => {
+                let limits: Map<String, Value> = self
+                    .selected(request)
+                    .into_iter()
+                    .filter_map(|hash| {
+                        let torrent = self.torrents.get(&hash)?;
+                        let limit = if method == "downloadLimit" { torrent.dl_limit } else { torrent.up_limit };
+                        Some((hash, json!(limit)))
+                    })
+                    .collect();
+                MockResponse::json(&Value::Object(limits))
+            }
+            "setDownloadLimit" | "setUploadLimit" => {
+                let Some(limit) = request.param("limit").and_then(|l| l.parse::<i64>().ok()) else {
+                    return MockResponse::status_text(400, "Bad Request");
+                };
+                let download = method == "setDownloadLimit";
+                self.for_selected(request, |t| {
+                    if download {
+                        t.dl_limit = limit;
+                    } else {
+                        t.up_limit = limit;
+                    }
+                })
+            }
+            "setShareLimits" => {
+                let ratio = request.param("ratioLimit").and_then(|r| r.parse::<f64>().ok());
+                let time = request.param("seedingTimeLimit").and_then(|t| t.parse::<i64>().ok());
+                let (Some(ratio), Some(time)) = (ratio, time) else {
+                    return MockResponse::status_text(400, "Bad Request");
+                };
+                self.for_selected(request, |t| {
+                    t.ratio_limit = ratio;
+                    t.seeding_time_limit = time;
+                })
+            }
+            "setSavePath" | "setDownloadPath" => {
+                let path = request.param("path").unwrap_or_default().to_string();
+                if path.is_empty() && method == "setSavePath" {
+                    return MockResponse::status_text(400, "Bad Request");
+                }
+                if method == "setSavePath" {
+                    self.for_selected(request, |t| t.save_path = path.clone())
+                } else {
+                    self.for_selected(request, |t| t.download_path = path.clone())
+                }
+            }
+            "count" => MockResponse::text(self.torrents.len().to_string()),
+            "export" => match self.torrent(request) {
+                Some(torrent) => MockResponse::bytes(torrent.export(), "application/x-bittorrent"),
+                None => not_found(),
+            },
+            "addWebSeeds" | "editWebSeed" | "removeWebSeeds" if self.is_v5() => {
+                let urls: Vec<String> = request
+                    .param("urls")
+                    .unwrap_or_default()
+                    .split('|')
+                    .filter(|u| !u.is_empty())
+                    .map(str::to_string)
+                    .collect();
+                let (orig_url, new_url) = (request.param("origUrl"), request.param("newUrl"));
+                let Some(torrent) = self.torrent(request) else {
+                    return not_found();
+                };
+                match method {
+                    "addWebSeeds" => torrent.web_seeds.extend(urls),
+                    "removeWebSeeds" => torrent.web_seeds.retain(|url| !urls.contains(url)),
+                    _ => {
+                        let (Some(orig_url), Some(new_url)) = (orig_url, new_url) else {
+                            return MockResponse::status_text(400, "Bad Request");
+                        };
+                        if torrent.web_seeds.iter().any(|url| url == new_url) {
+                            return MockResponse::status_text(409, "Conflict");
+                        }
+                        let Some(url) = torrent.web_seeds.iter_mut().find(|url| *url == orig_url) else {
+                            return MockResponse::status_text(409, "Conflict");
+                        };
+                        *url = new_url.to_string();
+                    }
+                }
+                MockResponse::ok()
+            }
+            "setLocation" => {
+                let location = request.param("location").unwrap_or_default().to_string();
+                if location.is_empty() {
+                    return MockResponse::status_text(400, "Bad Request");
+                }
+                self.for_selected(request, |t| t.save_path = location.clone())
+            }
+            "rename" => {
+                let name = request.param("name").unwrap_or_default().to_string();
+                let Some(torrent) = self.torrent(request) else {
+                    return not_found();
+                };
+                if name.is_empty() {
+                    return MockResponse::status_text(409, "Conflict");
+                }
+                torrent.name = name;
+                MockResponse::ok()
+            }
+            "setCategory" => {
+                let category = request.param("category").unwrap_or_default().to_string();
+                if !category.is_empty() && !self.categories.contains_key(&category) {
+                    return MockResponse::status_text(409, "Conflict");
+                }
+                self.for_selected(request, |t| t.category = category.clone())
+            }
+            "categories" => {
+                let categories: Map<String, Value> = self
+                    .categories
+                    .iter()
+                    .map(|(name, path)| (name.clone(), json!({"name": name, "savePath": path})))
+                    .collect();
+                MockResponse::json(&Value::Object(categories))
+            }
+            "createCategory" | "editCategory" => {
+                let name = request.param("category").unwrap_or_default().to_string();
+                let path = request.param("savePath").unwrap_or_default().to_string();
+                if name.is_empty() {
+                    return MockResponse::status_text(400, "Bad Request");
+                }
+                let exists = self.categories.contains_key(&name);
+                if exists == (method == "createCategory") {
+                    return MockResponse::status_text(409, "Conflict");
+                }
+                self.categories.insert(name, path);
+                MockResponse::ok()
+            }
+            "removeCategories" => {
+                let names: Vec<String> = request.param("categories").unwrap_or_default().lines().map(str::to_string).collect();
+                for name in &names {
+                    self.categories.remove(name);
+                }
+                for torrent in self.torrents.values_mut() {
+                    if names.contains(&torrent.category) {
+                        torrent.category.clear();
+                    }
+                }
+                MockResponse::ok()
+            }
+            "tags" => MockResponse::json(&json!(self.tags)),
+            "createTags" => {
+                self.tags.extend(split_list(request.param("tags")));
+                MockResponse::ok()
+            }
+            "deleteTags" => {
+                let tags = split_list(request.param("tags"));
+                for tag in &tags {
+                    self.tags.remove(tag);
+                }
+                for torrent in self.torrents.values_mut() {
+                    torrent.tags.retain(|t| !tags.contains(t));
+                }
+                MockResponse::ok()
+            }
+            "addTags" | "removeTags" | "setTags" => {
+                if method == "setTags" && !self.is_v5_1() {
+                    return not_found();
+                }
+                let tags = split_list(request.param("tags"));
+                if method != "removeTags" {
+                    self.tags.extend(tags.iter().cloned());
+                }
+                self.for_selected(request, |t| match method {
+                    "addTags" => t.tags.extend(tags.iter().cloned()),
+                    "removeTags" if tags.is_empty() => t.tags.clear(),
+                    "removeTags" => t.tags.retain(|tag| !tags.contains(tag)),
+                    _ => t.tags = tags.iter().cloned().collect(),
+                })
+            }
+            "setAutoManagement" => {
+                let enable = request.param("enable") == Some("true");
+                self.for_selected(request, |t| t.auto_tmm = enable)
+            }
+            "setForceStart" => {
+                let value = request.param("value") == Some("true");
+                self.for_selected(request, |t| t.force_start = value)
+            }
+            "setSuperSeeding" => {
+                let value = request.param("value") == Some("true");
+                self.for_selected(request, |t| t.super_seeding = value)
+            }
+            "toggleSequentialDownload" => self.for_selected(request, |t| t.seq_dl = !t.seq_dl),
+            "toggleFirstLastPiecePrio" => self.for_selected(request, |t| t.f_l_piece_prio = !t.f_l_piece_prio),
+            "renameFile" | "renameFolder" => {
+                let (old, new) = (
+                    request.param("oldPath").unwrap_or_default().to_string(),
+                    request.param("newPath").unwrap_or_default().to_string(),
+                );
+                let Some(torrent) = self.torrent(request) else {
+                    return not_found();
+                };
+                if old.is_empty() || new.is_empty() {
+                    return MockResponse::status_text(400, "Bad Request");
+                }
+                if torrent.files.iter().any(|f| f.name == new) {
+                    return MockResponse::status_text(409, "Conflict");
+                }
+                let mut renamed = false;
+                for file in &mut torrent.files {
+                    if method == "renameFile" && file.name == old {
+                        file.name = new.clone();
+                        renamed = true;
+                    } else if method == "renameFolder" {
+                        if let Some(rest) = file.name.strip_prefix(&format!("{}/", old)) {
+                            file.name = format!("{}/{}", new, rest);
+                            renamed = true;
+                        }
+                    }
+                }
+                if renamed {
+                    MockResponse::ok()
+                } else {
+                    MockResponse::status_text(409, "Conflict")
+                }
+            }
+            _ => not_found(),
+        }
+    }
+
+    fn queueing_enabled(&self) -> bool {
+        self.preferences
+            .get("queueing_enabled")
+            .and_then(Value::as_bool)
+            .unwrap_or(false)
+    }
+
+    fn reprioritize(&mut self, method: &str, selected: &[String]) {
+        let mut queue: Vec<String> = self
+            .torrents
+            .values()
+            .filter(|t| t.priority > 0)
+            .map(|t| (t.priority, t.hash.clone()))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .map(|(_, hash)| hash)
+            .collect();
+
+        for hash in selected {
+            let Some(position) = queue.iter().position(|h| h == hash) else {
+                continue;
+            };
+            let hash = queue.remove(position);
+            let position = match method {
+                "increasePrio" => position.saturating_sub(1),
+                "decreasePrio" => (position + 1).min(queue.len()),
+                "topPrio" => 0,
+                _ => queue.len(),
+            };
+            queue.insert(position, hash);
+        }
+
+        for (position, hash) in queue.iter().enumerate() {
+            if let Some(torrent) = self.torrents.get_mut(hash) {
+                torrent.priority = position as i64 + 1;
+            }
+        }
+    }
+
+    fn torrent_list(&self, request: &MockRequest) -> Value {
+        let hashes: Option<Vec<&str>> = request.param("hashes").map(|h| h.split('|').collect());
+
+        let mut torrents: Vec<&MockTorrent> = self
+            .torrents
+            .values()
+            .filter(|t| hashes.as_ref().is_none_or(|h| h.contains(&t.hash.as_str())))
+            .filter(|t| request.param("category").is_none_or(|c| t.category == c))
+            .filter(|t| match request.param("tag") {
+                None => true,
+                Some("") => t.tags.is_empty(),
+                Some(tag) => t.tags.contains(tag),
+            })
+            .filter(|t| match request.param("filter").unwrap_or("all") {
+                "downloading" => !t.is_complete() && !t.is_paused(),
+                "seeding" => t.is_complete() && !t.is_paused(),
+                "completed" => t.is_complete(),
+                "paused" | "stopped" => t.is_paused(),
+                "resumed" | "running" => !t.is_paused(),
+                "active" => matches!(t.state.as_str(), "downloading" | "uploading" | "forcedDL" | "forcedUP"),
+                "inactive" => !matches!(t.state.as_str(), "downloading" | "uploading" | "forcedDL" | "forcedUP"),
+                "stalled" => t.state.starts_with("stalled"),
+                "stalled_uploading" => t.state == "stalledUP",
+                "stalled_downloading" => t.state == "stalledDL",
+                "errored" => matches!(t.state.as_str(), "error" | "missingFiles"),
+                _ => true,
+            })
+            .collect();
+
+        let mut list: Vec<Value> = torrents.drain(..).map(MockTorrent::info).collect();
+
+        if let Some(key) = request.param("sort") {
+            list.sort_by(|a, b| compare_json(&a[key], &b[key]));
+        }
+        if request.param("reverse") == Some("true") {
+            list.reverse();
+        }
+
+        let offset = request.param("offset").and_then(|o| o.parse::<i64>().ok()).unwrap_or(0);
+        let offset = if offset < 0 {
+            (list.len() as i64 + offset).max(0) as usize
+        } else {
+            offset as usize
+        };
+        let limit = request
+            .param("limit")
+            .and_then(|l| l.parse::<usize>().ok())
+            .filter(|l| *l > 0)
+            .unwrap_or(usize::MAX);
+
+        Value::Array(list.into_iter().skip(offset).take(limit).collect())
+    }
+
+    fn add_torrents(&mut self, request: &MockRequest) -> MockResponse {
+        let mut added = Vec::new();
+
+        for url in request.param("urls").unwrap_or_default().lines().map(str::trim).filter(|u| !u.is_empty()) {
+            let torrent = match url.parse::<MagnetLink>() {
+                Ok(magnet) => {
+                    let TorrentHash { name, hash } = magnet.torrent_hash();
+                    MockTorrent::new(hash, name)
+                }
+                Err(_) => {
+                    let name = url.rsplit('/').next().unwrap_or(url).trim_end_matches(".torrent");
+                    MockTorrent::new(fake_hash(&url), name)
+                }
+            };
+            added.push(torrent);
+        }
+
+        for file in request.files.iter().filter(|f| f.field == "torrents") {
+            match Metainfo::from_bytes(file.data.clone()) {
+                Ok(metainfo) => added.push(MockTorrent::new(metainfo.hash(), metainfo.name)),
+                Err(_) => {
+                    let message = format!("Error: '{}' is not a valid torrent file.", file.file_name);
+                    return MockResponse::status_text(415, message);
+                }
+            }
+        }
+
+        // torrents that are already there aren't added again, and count as failures.
+        let total = added.len();
+        added.retain(|torrent| !self.torrents.contains_key(&torrent.hash));
+        let added_ids: Vec<String> = added.iter().map(|torrent| torrent.hash.clone()).collect();
+
+        if added.is_empty() && !self.is_v5_1() {
+            return MockResponse::text("Fails.");
+        }
+
+        let category = request.param("category").unwrap_or_default().to_string();
+        if !category.is_empty() && !self.categories.contains_key(&category) {
+            self.categories.insert(category.clone(), String::new());
+        }
+        let tags = split_list(request.param("tags"));
+        self.tags.extend(tags.iter().cloned());
+
+        let single = added.len() == 1;
+        for mut torrent in added {
+            if let Some(path) = request.param("savepath").filter(|p| !p.is_empty()) {
+                torrent.save_path = path.to_string();
+            }
+            if let Some(name) = request.param("rename").filter(|n| single && !n.is_empty()) {
+                torrent.name = name.to_string();
+            }
+            torrent.category = category.clone();
+            torrent.tags = tags.iter().cloned().collect();
+            if matches!(request.param("paused").or(request.param("stopped")), Some("true")) {
+                torrent.state = if self.is_v5() { "stoppedDL" } else { "pausedDL" }.to_string();
+            }
+            if request.param("skip_checking") == Some("true") {
+                torrent.progress = 1.0;
+                torrent.state = if torrent.is_paused() { torrent.state.replace("DL", "UP") } else { "stalledUP".to_string() };
+            }
+            torrent.seq_dl = request.param("sequentialDownload") == Some("true");
+            torrent.f_l_piece_prio = request.param("firstLastPiecePrio") == Some("true");
+            torrent.auto_tmm = request.param("autoTMM") == Some("true");
+            if let Some(limit) = request.param("dlLimit").and_then(|l| l.parse().ok()) {
+                torrent.dl_limit = limit;
+            }
+            if let Some(limit) = request.param("upLimit").and_then(|l| l.parse().ok()) {
+                torrent.up_limit = limit;
+            }
+            if self.queueing_enabled() && !torrent.is_complete() {
+                torrent.priority = self.torrents.values().map(|t| t.priority).max().unwrap_or(0) + 1;
+            }
+            self.torrents.entry(torrent.hash.clone()).or_insert(torrent);
+        }
+
+        if self.is_v5_1() {
+            return MockResponse::json(&json!({
+                "success_count": added_ids.len(),
+                "failure_count": total - added_ids.len(),
+                "pending_count": 0,
+                "added_torrent_ids": added_ids,
+            }));
+        }
+        MockResponse::text("Ok.")
+    }
+
+    fn rss(&mut self, method: &str, request: &MockRequest) -> MockResponse {
+        match method {
+            "items" => {
+                let with_data = request.param("withData") == Some("true");
+                MockResponse::json(&rss_tree(&self.rss_items, with_data))
+            }
+            "addFolder" => {
+                let path = request.param("path").unwrap_or_default().to_string();
+                self.rss_insert(&path, Value::Object(Map::new()))
+            }
+            "addFeed" => {
+                let Some(url) = request.param("url").map(str::to_string) else {
+                    return MockResponse::status_text(400, "Bad Request");
+                };
+                let path = request.param("path").filter(|p| !p.is_empty()).unwrap_or(&url).to_string();
+                if rss_feed_urls(&self.rss_items).contains(&url) {
+                    return conflict(format!("RSS feed with given URL already exists: {}.", url));
+                }
+                let feed = json!({
+                    "uid": format!("{{{}}}", &fake_hash(&url)[..32]),
+                    "url": url,
+                    "title": "",
+                    "lastBuildDate": "",
+                    "isLoading": false,
+                    "hasError": false,
+                    "articles": [],
+                });
+                self.rss_insert(&path, feed)
+            }
+            "removeItem" => {
+                let path = request.param("path").unwrap_or_default().to_string();
+                if path.is_empty() {
+                    return conflict("Cannot remove root folder.".to_string());
+                }
+                match self.rss_remove(&path) {
+                    Some(_) => MockResponse::ok(),
+                    None => conflict(format!("Item doesn't exist: {}.", path)),
+                }
+            }
+            "moveItem" => {
+                let from = request.param("itemPath").unwrap_or_default().to_string();
+                let to = request.param("destPath").unwrap_or_default().to_string();
+                if from.is_empty() {
+                    return conflict("Cannot move root folder.".to_string());
+                }
+                if to.starts_with(&format!("{}\\", from)) {
+                    return conflict("Couldn't move folder into itself.".to_string());
+                }
+                let Some(item) = self.rss_remove(&from) else {
+                    return conflict(format!("Item doesn't exist: {}.", from));
+                };
+                let response = self.rss_insert(&to, item.clone());
+                if response.status != 200 {
+                    self.rss_insert(&from, item);
+                }
+                response
+            }
+            "markAsRead" => {
+                let path = request.param("itemPath").unwrap_or_default().to_string();
+                let article = request.param("articleId").map(str::to_string);
+                // like qbittorrent, paths that don't exist are ignored.
+                if let Some(item) = self.rss_get_mut(&path) {
+                    mark_as_read(item, article.as_deref());
+                }
+                MockResponse::ok()
+            }
+            "refreshItem" => MockResponse::ok(),
+            "setFeedURL" => {
+                let path = request.param("path").unwrap_or_default().to_string();
+                let url = request.param("url").unwrap_or_default().to_string();
+                if rss_feed_urls(&self.rss_items).contains(&url) {
+                    return conflict(format!("RSS feed with given URL already exists: {}.", url));
+                }
+                match self.rss_get_mut(&path).filter(|item| item.get("uid").is_some()) {
+                    Some(feed) => {
+                        feed["url"] = json!(url);
+                        MockResponse::ok()
+                    }
+                    None => conflict(format!("Feed doesn't exist: {}.", path)),
+                }
+            }
+            "setFeedRefreshInterval" if self.is_v5_1() => {
+                let path = request.param("path").unwrap_or_default().to_string();
+                let Some(interval) = request.param("refreshInterval").and_then(|i| i.parse::<u64>().ok()) else {
+                    return MockResponse::status_text(400, "Bad Request");
+                };
+                match self.rss_get_mut(&path).filter(|item| item.get("uid").is_some()) {
+                    Some(feed) => {
+                        feed["refreshInterval"] = json!(interval);
+                        MockResponse::ok()
+                    }
+                    None => conflict(format!("Feed doesn't exist: {}.", path)),
+                }
+            }
+            "setRule" => {
+                let name = request.param("ruleName").unwrap_or_default().to_string();
+                let Some(definition) = request.param("ruleDef").and_then(|d| serde_json::from_str::<Value>(d).ok())
+                else {
+                    return MockResponse::status_text(400, "Bad Request");
+                };
+                self.rss_rules.insert(name, definition);
+                MockResponse::ok()
+            }
+            "renameRule" => {
+                let name = request.param("ruleName").unwrap_or_default();
+                let new_name = request.param("newRuleName").unwrap_or_default().to_string();
+                if let Some(rule) = self.rss_rules.remove(name) {
+                    self.rss_rules.insert(new_name, rule);
+                }
+                MockResponse::ok()
+            }
+            "removeRule" => {
+                self.rss_rules.remove(request.param("ruleName").unwrap_or_default());
+                MockResponse::ok()
+            }
+            "rules" => MockResponse::json(&Value::Object(self.rss_rules.clone())),
+            "matchingArticles" => MockResponse::json(&json!({})),
+            _ => not_found(),
+        }
+    }
+
+    fn rss_get_mut(&mut self, path: &str) -> Option<&mut Value> {
+        let mut parts = path.split('\\');
+        let mut item = self.rss_items.get_mut(parts.next()?)?;
+        for part in parts {
+            item = item.as_object_mut()?.get_mut(part)?;
+        }
+        Some(item)
+    }
+
+    fn rss_insert(&mut self, path: &str, item: Value) -> MockResponse {
+        let (parent, name) = match path.rsplit_once('\\') {
+            Some((parent, name)) => (Some(parent), name),
+            None => (None, path),
+        };
+        let folder = match parent {
+            None => Some(&mut self.rss_items),
+            Some(parent) => self
+                .rss_get_mut(parent)
+                .filter(|p| p.get("uid").is_none())
+                .and_then(Value::as_object_mut),
+        };
+        match folder {
+            _ if name.is_empty() => conflict(format!("Incorrect RSS Item path: {}.", path)),
+            Some(folder) if folder.contains_key(name) => {
+                conflict(format!("RSS item with given path already exists: {}.", path))
+            }
+            Some(folder) => {
+                folder.insert(name.to_string(), item);
+                MockResponse::ok()
+            }
+            None => conflict(format!("Parent folder doesn't exist: {}.", parent.unwrap_or_default())),
+        }
+    }
+
+    fn rss_remove(&mut self, path: &str) -> Option<Value> {
+        match path.rsplit_once('\\') {
+            None => self.rss_items.remove(path),
+            Some((parent, name)) => self.rss_get_mut(parent)?.as_object_mut()?.remove(name),
+        }
+    }
+
+    /// lists a plugin, replacing the one with the same name.
+    fn install_search_plugin(&mut self, plugin: Value) {
+        self.search_plugins.retain(|p| p["name"] != plugin["name"]);
+        self.search_plugins.push(plugin);
+    }
+
+    fn search(&mut self, method: &str, request: &MockRequest) -> MockResponse {
+        match method {
+            "plugins" => {
+                for (polls, _) in &mut self.installing_plugins {
+                    *polls = polls.saturating_sub(1);
+                }
+                let (installed, installing) = std::mem::take(&mut self.installing_plugins)
+                    .into_iter()
+                    .partition(|(polls, _)| *polls == 0);
+                self.installing_plugins = installing;
+                for (_, plugin) in installed {
+                    self.install_search_plugin(plugin);
+                }
+                MockResponse::json(&Value::Array(self.search_plugins.clone()))
+            }
+            "installPlugin" => {
+                for source in request.param("sources").unwrap_or_default().split('|').filter(|s| !s.is_empty()) {
+                    let name = source
+                        .rsplit(['/', '\\'])
+                        .next()
+                        .unwrap_or(source)
+                        .trim_end_matches(".py")
+                        .to_string();
+                    // like qbittorrent, local files are read for their `#VERSION:` line.
+                    let version = std::fs::read_to_string(source)
+                        .ok()
+                        .and_then(|code| {
+                            code.lines()
+                                .find_map(|line| line.strip_prefix("#VERSION:").map(|v| v.trim().to_string()))
+                        })
+                        .unwrap_or_else(|| "1.0".to_string());
+                    let plugin = json!({
+                        "enabled": true,
+                        "fullName": name,
+                        "name": name,
+                        "supportedCategories": [{"id": "all", "name": "All categories"}],
+                        "url": source,
+                        "version": version,
+                    });
+                    if self.plugin_install_polls == 0 {
+                        self.install_search_plugin(plugin);
+                    } else {
+                        self.installing_plugins.push((self.plugin_install_polls, plugin));
+                    }
+                }
+                MockResponse::ok()
+            }
+            "uninstallPlugin" => {
+                let names = split_pipe(request.param("names"));
+                self.search_plugins
+                    .retain(|p| !names.iter().any(|n| p["name"] == n.as_str()));
+                MockResponse::ok()
+            }
+            "enablePlugin" => {
+                let names = split_pipe(request.param("names"));
+                let enable = request.param("enable") == Some("true");
+                for plugin in &mut self.search_plugins {
+                    if names.iter().any(|n| plugin["name"] == n.as_str()) {
+                        plugin["enabled"] = json!(enable);
+                    }
+                }
+                MockResponse::ok()
+            }
+            "updatePlugins" => MockResponse::ok(),
+            "start" => {
+                let Some(pattern) = request.param("pattern").filter(|p| !p.is_empty()) else {
+                    return MockResponse::status_text(400, "Bad Request");
+                };
+                let running = self.search_jobs.values().filter(|job| job.status == "Running").count();
+                if running >= 5 {
+                    return MockResponse::status_text(409, "Conflict");
+                }
+                let needle = pattern.to_lowercase();
+                let pending: VecDeque<Value> = self
+                    .search_catalog
+                    .iter()
+                    .filter(|r| {
+                        r["fileName"]
+                            .as_str()
+                            .is_some_and(|n| n.to_lowercase().contains(&needle))
+                    })
+                    .cloned()
+                    .collect();
+                let id = self.next_search_id;
+                self.next_search_id += 1;
+                self.search_jobs.insert(
+                    id,
+                    MockSearchJob {
+                        pattern: pattern.to_string(),
+                        status: if pending.is_empty() { "Stopped" } else { "Running" }.to_string(),
+                        results: Vec::new(),
+                        pending,
+                    },
+                );
+                MockResponse::json(&json!({ "id": id }))
+            }
+            "status" => {
+                let id = request.param("id").and_then(|i| i.parse::<u64>().ok());
+                let statuses: Vec<Value> = self
+                    .search_jobs
+                    .iter()
+                    .filter(|(job_id, _)| id.is_none_or(|id| id == **job_id))
+                    .map(|(id, job)| json!({"id": id, "status": job.status, "total": job.results.len()}))
+                    .collect();
+                if id.is_some() && statuses.is_empty() {
+                    return not_found();
+                }
+                MockResponse::json(&Value::Array(statuses))
+            }
+            "results" => {
+                let Some(job) = request
+                    .param("id")
+                    .and_then(|i| i.parse::<u64>().ok())
+                    .and_then(|id| self.search_jobs.get_mut(&id))
+                else {
+                    return not_found();
+                };
+                if let Some(found) = job.pending.pop_front() {
+                    job.results.push(found);
+                }
+                if job.pending.is_empty() {
+                    job.status = "Stopped".to_string();
+                }
+                let offset = request.param("offset").and_then(|o| o.parse::<i64>().ok()).unwrap_or(0);
+                let offset = if offset < 0 {
+                    (job.results.len() as i64 + offset).max(0) as usize
+                } else {
+                    offset as usize
+                };
+                let limit = request
+                    .param("limit")
+                    .and_then(|l| l.parse::<usize>().ok())
+                    .filter(|l| *l > 0)
+                    .unwrap_or(usize::MAX);
+                MockResponse::json(&json!({
+                    "results": job.results.iter().skip(offset).take(limit).collect::<Vec<_>>(),
+                    "status": job.status,
+                    "total": job.results.len(),
+                }))
+            }
+            "stop" | "delete" => {
+                let Some(id) = request.param("id").and_then(|i| i.parse::<u64>().ok()) else {
+                    return not_found();
+                };
+                if method == "delete" {
+                    return match self.search_jobs.remove(&id) {
+                        Some(_) => MockResponse::ok(),
+                        None => not_found(),
+                    };
+                }
+                match self.search_jobs.get_mut(&id) {
+                    Some(job) => {
+                        job.status = "Stopped".to_string();
+                        job.pending.clear();
+                        MockResponse::ok()
+                    }
+                    None => not_found(),
+                }
+            }
+            _ => not_found(),
+        }
+    }
+}
+
+fn not_found() -> MockResponse {
+    MockResponse::status_text(404, "Not Found")
+}
+
+/// qbittorrent explains its 409s in the body.
+fn conflict(message: String) -> MockResponse {
+    MockResponse::status_text(409, message)
+}
+
+/// the urls of every rss feed, in every folder.
+fn rss_feed_urls(items: &Map<String, Value>) -> Vec<String> {
+    items
+        .values()
+        .flat_map(|item| match item.get("uid") {
+            Some(_) => item["url"].as_str().map(str::to_string).into_iter().collect(),
+            None => item.as_object().map(rss_feed_urls).unwrap_or_default(),
+        })
+        .collect()
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+/// a stable, 40 hex digits long "hash" of anything hashable. it isn't a real info hash.
+fn fake_hash(value: &impl Hash) -> String {
+    (0..3_u8)
+        .map(|salt| {
+            let mut hasher = DefaultHasher::new();
+            salt.hash(&mut hasher);
+            value.hash(&mut hasher);
+            format!("{:016x}", hasher.finish())
+        })
+        .collect::<String>()[..40]
+        .to_string()
+}
+
+/// the info hash and display name of a magnet link.
+/// splits a list of tags or categories, which qbittorrent accepts separated by commas.
+fn split_list(list: Option<&str>) -> Vec<String> {
+    list.unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn split_pipe(list: Option<&str>) -> Vec<String> {
+    list.unwrap_or_default()
+        .split('|')
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn compare_json(a: &Value, b: &Value) -> std::cmp::Ordering {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => a
+            .as_f64()
+            .unwrap_or_default()
+            .total_cmp(&b.as_f64().unwrap_or_default()),
+        (Value::String(a), Value::String(b)) => a.cmp(b),
+        (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
+        _ => std::cmp::Ordering::Equal,
+    }
+}
+
+/// the RSS tree, without the articles and feed details unless `with_data` is set.
+fn rss_tree(items: &Map<String, Value>, with_data: bool) -> Value {
+    let tree = items
+        .iter()
+        .map(|(name, item)| {
+            let item = match item.as_object() {
+                Some(feed) if feed.contains_key("uid") => {
+                    if with_data {
+                        item.clone()
+                    } else {
+                        json!({"uid": feed["uid"], "url": feed["url"]})
+                    }
+                }
+                Some(folder) => rss_tree(folder, with_data),
+                None => item.clone(),
+            };
+            (name.clone(), item)
+        })
+        .collect();
+    Value::Object(tree)
+}
+
+fn mark_as_read(item: &mut Value, article: Option<&str>) {
+    if item.get("uid").is_none() {
+        if let Some(folder) = item.as_object_mut() {
+            for child in folder.values_mut() {
+                mark_as_read(child, None);
+            }
+        }
+        return;
+    }
+
+    if let Some(articles) = item.get_mut("articles").and_then(Value::as_array_mut) {
+        for entry in articles {
+            if article.is_none_or(|id| entry["id"] == id) {
+                entry["isRead"] = json!(true);
+            }
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use qbittorrent_rust::{error_handling::error_type::ErrorType, MagnetLink, Metainfo, Torrent};
+
+const HASH: &str = "dd8255ecdc7ca55fb0bbf81323d87062db1f6d1c";
+const V2_HASH: &str = "7382be18bdc363c4edde037ee8150101ff229fdd07f2c2e1af4aba8bf5e43b02";
+
+#[test]
+fn magnet_links_are_parsed() {
+    let magnet: MagnetLink = "magnet:?xt=urn:btih:DD8255ECDC7CA55FB0BBF81323D87062DB1F6D1C&dn=Big+Buck%20Bunny\
+        &tr=udp%3A%2F%2Ft1.invalid%3A1337&tr.1=http://t2.invalid/announce&ws=http%3A%2F%2Fseed.invalid%2F\
+        &xl=276445467&so=0,2,4-6&x.pe=10.0.0.1:6881"
+        .parse()
+        .unwrap();
+
+    assert_eq!(magnet.info_hash_v1.as_deref(), Some(HASH));
+    assert_eq!(magnet.info_hash_v2, None);
+    assert_eq!(magnet.display_name.as_deref(), Some("Big Buck Bunny"));
+    assert_eq!(magnet.trackers, ["udp://t1.invalid:1337", "http://t2.invalid/announce"]);
+    assert_eq!(magnet.web_seeds, ["http://seed.invalid/"]);
+    assert_eq!(magnet.exact_length, Some(276445467));
+    assert_eq!(magnet.select_only, [0..=0, 2..=2, 4..=6]);
+
+    let torrent_hash = magnet.torrent_hash();
+    assert_eq!((torrent_hash.name.as_str(), torrent_hash.hash.as_str()), ("Big Buck Bunny", HASH));
+}
+
+#[test]
+fn base32_hashes_are_converted_to_hex() {
+    let magnet: MagnetLink = "magnet:?xt=urn:btih:3WBFL3G4PSSV7MF37AJSHWDQMLNR63I4".parse().unwrap();
+
+    assert_eq!(magnet.hash(), HASH);
+    assert_eq!(MagnetLink::from_hash("3wbfl3g4pssv7mf37ajshwdqmlnr63i4").unwrap().hash(), HASH);
+}
+
+#[test]
+fn v2_magnet_links_use_the_truncated_hash() {
+    let magnet: MagnetLink = format!("magnet:?xt=urn:btmh:1220{}", V2_HASH).parse().unwrap();
+
+    assert_eq!(magnet.info_hash_v1, None);
+    assert_eq!(magnet.info_hash_v2.as_deref(), Some(V2_HASH));
+    assert_eq!(magnet.hash(), &V2_HASH[..40]);
+    assert_eq!(magnet.torrent_hash().name, &V2_HASH[..40]);
+    assert_eq!(MagnetLink::from_hash(V2_HASH).unwrap(), magnet);
+}
+
+#[test]
+fn magnet_links_are_built_and_round_trip() {
+    let mut magnet = MagnetLink::from_hash(HASH).unwrap();
+    magnet.info_hash_v2 = Some(V2_HASH.to_string());
+    magnet.display_name = Some("a & b".to_string());
+    magnet.trackers = vec!["udp://t1.invalid:1337/announce?key=1&x=2".to_string()];
+    magnet.web_seeds = vec!["http://seed.invalid/".to_string()];
+    magnet.exact_length = Some(42);
+    magnet.select_only = vec![1..=1, 3..=5];
+
+    let uri = magnet.to_string();
+    assert_eq!(
+        uri,
+        format!(
+            "magnet:?xt=urn:btih:{}&xt=urn:btmh:1220{}&dn=a+%26+b&xl=42\
+            &tr=udp%3A%2F%2Ft1.invalid%3A1337%2Fannounce%3Fkey%3D1%26x%3D2&ws=http%3A%2F%2Fseed.invalid%2F&so=1,3-5",
+            HASH, V2_HASH
+        )
+    );
+    assert_eq!(uri.parse::<MagnetLink>().unwrap(), magnet);
+
+    let torrent = Torrent::from(magnet.clone());
+    assert_eq!(torrent.magnet_link(), Some(magnet));
+}
+
+#[test]
+fn torrent_files_have_a_magnet_link() {
+    let data = b"d8:announce18:http://t.invalid/a4:infod6:lengthi5e4:name5:notes12:piece lengthi16384e6:pieces20:aaaaaaaaaaaaaaaaaaaaee";
+    let metainfo = Metainfo::from_bytes(&data[..]).unwrap();
+
+    let magnet = metainfo.magnet_link();
+
+    assert_eq!(magnet.hash(), metainfo.hash());
+    assert_eq!(magnet.display_name.as_deref(), Some("notes"));
+    assert_eq!(magnet.trackers, ["http://t.invalid/a"]);
+    assert_eq!(magnet.exact_length, Some(5));
+}
+
+#[test]
+fn invalid_magnet_links_are_rejected() {
+    let links = [
+        "http://example.org/file.torrent",
+        "magnet:?dn=no+hash",
+        "magnet:?xt=urn:btih:1234",
+        "magnet:?xt=urn:btih:zz8255ecdc7ca55fb0bbf81323d87062db1f6d1c",
+        "magnet:?xt=urn:btih:11111111111111111111111111111111",
+        "magnet:?xt=urn:btmh:1114aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+        "magnet:?xt=urn:btih:dd8255ecdc7ca55fb0bbf81323d87062db1f6d1c&xl=-1",
+        "magnet:?xt=urn:btih:dd8255ecdc7ca55fb0bbf81323d87062db1f6d1c&so=1-x",
+    ];
+
+    for link in links {
+        let error = link.parse::<MagnetLink>().unwrap_err();
+        assert!(matches!(error.err_type, ErrorType::InvalidMagnetLink(_)), "{}", link);
+    }
+    assert!(MagnetLink::from_hash("abc").is_err());
+}
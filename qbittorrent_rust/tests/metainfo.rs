@@ -0,0 +1,164 @@
+use std::collections::BTreeMap;
+
+use qbittorrent_rust::{
+    error_handling::error_type::ErrorType, misc::bencode::Bencode, Metainfo, MetainfoFile, MetainfoVersion,
+};
+
+fn dict(entries: Vec<(&str, Bencode)>) -> Bencode {
+    Bencode::Dict(entries.into_iter().map(|(k, v)| (k.as_bytes().to_vec(), v)).collect::<BTreeMap<_, _>>())
+}
+
+fn text(text: &str) -> Bencode {
+    Bencode::Bytes(text.as_bytes().to_vec())
+}
+
+fn list(items: Vec<Bencode>) -> Bencode {
+    Bencode::List(items)
+}
+
+#[test]
+fn v1_torrents_are_parsed() {
+    let info = dict(vec![
+        ("name", text("dataset")),
+        ("piece length", Bencode::Integer(16384)),
+        ("pieces", Bencode::Bytes(vec![0; 20])),
+        (
+            "files",
+            list(vec![
+                dict(vec![("length", Bencode::Integer(10)), ("path", list(vec![text("a.txt")]))]),
+                dict(vec![("length", Bencode::Integer(5)), ("path", list(vec![text("sub"), text("b.bin")]))]),
+            ]),
+        ),
+    ]);
+    let torrent = dict(vec![
+        ("announce", text("http://t1.invalid/announce")),
+        (
+            "announce-list",
+            list(vec![list(vec![text("http://t1.invalid/announce"), text("http://t2.invalid/announce")])]),
+        ),
+        ("comment", text("weather")),
+        ("creation date", Bencode::Integer(1700000000)),
+        ("info", info),
+    ]);
+
+    let metainfo = Metainfo::from_bytes(torrent.encode()).unwrap();
+
+    assert_eq!(metainfo.version, MetainfoVersion::V1);
+    assert_eq!(metainfo.hash(), "2681d14f9dc65f7c4c03cb85e9f1c488e7641c6d");
+    assert_eq!(metainfo.info_hash_v2, None);
+    assert_eq!(metainfo.name, "dataset");
+    assert_eq!(metainfo.piece_length, 16384);
+    assert_eq!(
+        metainfo.files,
+        [
+            MetainfoFile { path: "dataset/a.txt".to_string(), size: 10 },
+            MetainfoFile { path: "dataset/sub/b.bin".to_string(), size: 5 },
+        ]
+    );
+    assert_eq!(metainfo.total_size(), 15);
+    assert_eq!(metainfo.trackers, ["http://t1.invalid/announce", "http://t2.invalid/announce"]);
+    assert_eq!(metainfo.comment.as_deref(), Some("weather"));
+    assert_eq!(metainfo.creation_date, Some(1700000000));
+    assert!(!metainfo.private);
+}
+
+#[test]
+fn hybrid_torrents_have_both_hashes() {
+    let info = dict(vec![
+        ("name", text("hy")),
+        ("piece length", Bencode::Integer(16384)),
+        ("pieces", Bencode::Bytes(vec![0; 20])),
+        ("meta version", Bencode::Integer(2)),
+        ("private", Bencode::Integer(1)),
+        (
+            "files",
+            list(vec![
+                dict(vec![("length", Bencode::Integer(3)), ("path", list(vec![text("x")]))]),
+                dict(vec![
+                    ("attr", text("p")),
+                    ("length", Bencode::Integer(16381)),
+                    ("path", list(vec![text(".pad"), text("16381")])),
+                ]),
+            ]),
+        ),
+        (
+            "file tree",
+            dict(vec![(
+                "x",
+                dict(vec![(
+                    "",
+                    dict(vec![("length", Bencode::Integer(3)), ("pieces root", Bencode::Bytes(vec![0x11; 32]))]),
+                )]),
+            )]),
+        ),
+    ]);
+    let torrent = dict(vec![("info", info), ("url-list", text("http://seed.invalid/"))]);
+
+    let metainfo = Metainfo::from_bytes(torrent.encode()).unwrap();
+
+    assert_eq!(metainfo.version, MetainfoVersion::Hybrid);
+    assert_eq!(metainfo.info_hash_v1.as_deref(), Some("e597800609e1f1dae130306f5b4dfbcd90dde78f"));
+    assert_eq!(
+        metainfo.info_hash_v2.as_deref(),
+        Some("7382be18bdc363c4edde037ee8150101ff229fdd07f2c2e1af4aba8bf5e43b02")
+    );
+    assert_eq!(metainfo.files, [MetainfoFile { path: "hy/x".to_string(), size: 3 }]);
+    assert_eq!(metainfo.web_seeds, ["http://seed.invalid/"]);
+    assert!(metainfo.private);
+}
+
+#[test]
+fn v2_torrents_use_the_truncated_v2_hash() {
+    let leaf = |size| dict(vec![("", dict(vec![("length", Bencode::Integer(size))]))]);
+    let info = dict(vec![
+        ("name", text("v2only")),
+        ("piece length", Bencode::Integer(16384)),
+        ("meta version", Bencode::Integer(2)),
+        ("file tree", dict(vec![("d", dict(vec![("f1", leaf(7))])), ("g", leaf(2))])),
+    ]);
+
+    let metainfo = Metainfo::from_bytes(dict(vec![("info", info)]).encode()).unwrap();
+
+    assert_eq!(metainfo.version, MetainfoVersion::V2);
+    assert_eq!(metainfo.hash(), "09abd82b66f1b1da1cfb8a18b237466e594dee90");
+    assert_eq!(
+        metainfo.files,
+        [
+            MetainfoFile { path: "v2only/d/f1".to_string(), size: 7 },
+            MetainfoFile { path: "v2only/g".to_string(), size: 2 },
+        ]
+    );
+}
+
+#[test]
+fn v2_torrents_keep_the_folder_of_a_single_file() {
+    let leaf = |size| dict(vec![("", dict(vec![("length", Bencode::Integer(size))]))]);
+    let torrent = |name: &str, file: &str| {
+        let info = dict(vec![
+            ("name", text(name)),
+            ("piece length", Bencode::Integer(16384)),
+            ("meta version", Bencode::Integer(2)),
+            ("file tree", dict(vec![(file, leaf(5))])),
+        ]);
+        Metainfo::from_bytes(dict(vec![("info", info)]).encode()).unwrap()
+    };
+
+    assert_eq!(torrent("dir", "a.txt").files, [MetainfoFile { path: "dir/a.txt".to_string(), size: 5 }]);
+    assert_eq!(torrent("a.txt", "a.txt").files, [MetainfoFile { path: "a.txt".to_string(), size: 5 }]);
+}
+
+#[test]
+fn the_hash_is_computed_over_the_original_bytes() {
+    // the keys of the info dictionary aren't sorted, so re-encoding it would change the hash.
+    let metainfo = Metainfo::from_bytes(&b"d4:infod4:name1:a12:piece lengthi1e6:pieces0:6:lengthi1eee"[..]).unwrap();
+
+    assert_eq!(metainfo.hash(), "4cd6888fd69cf4b460561128ca1b07a395458e01");
+}
+
+#[test]
+fn invalid_torrents_are_rejected() {
+    for data in [&b""[..], b"d4:info", b"i01e", b"d4:infoi1ee", b"d4:infode", b"d4:infod4:name1:aee"] {
+        let error = Metainfo::from_bytes(data).unwrap_err();
+        assert!(matches!(error.err_type, ErrorType::InvalidTorrentFile(_)), "{:?}", data);
+    }
+}
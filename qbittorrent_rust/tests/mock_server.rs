@@ -0,0 +1,825 @@
+use std::{pin::Pin, time::Duration};
+
+use futures_core::Stream;
+
+use qbittorrent_rust::{
+    core::{api::QbitApi, auth::Auth, cassette::CassetteMode, creds::Credentials, rate_limit::RateLimit, retry::RetryPolicy, version::ApiVersion},
+    error_handling::error_type::ErrorType,
+    testing::{MockServer, MockTorrent},
+    AddTorrentOutcome, Categories, RssItem, DesiredSearchPlugin, Metainfo, SearchPluginManifest, SearchJobStatus, SearchPick, SearchPluginsSpec, SearchResult, SearchStatus, State, SyncEvent, SyncEventStream, Torrent, TorrentAddDescriptor, TorrentCreationState, TorrentCreationTask, TorrentFormat, TorrentHash, TorrentHashesDesc, TorrentListGetConfig, TorrentState,
+    TorrentType,
+};
+
+async fn connect(server: &MockServer) -> QbitApi {
+    QbitApi::new(server.url(), server.credentials()).await.unwrap()
+}
+
+#[tokio::test]
+async fn wrong_credentials_are_rejected() {
+    let server = MockServer::start().await;
+    let credentials = Credentials::new("admin", "wrong");
+
+    assert!(QbitApi::new(server.url(), credentials).await.is_err());
+}
+
+#[tokio::test]
+async fn requests_without_a_session_are_forbidden() {
+    let server = MockServer::start().await;
+    let api = QbitApi::new(server.url(), Auth::Bypass).await.unwrap();
+
+    let error = api.app_version().await.unwrap_err();
+    assert_eq!(error.code, Some(403));
+}
+
+#[tokio::test]
+async fn expired_sessions_are_renewed() {
+    let server = MockServer::start().await;
+    let api = connect(&server).await;
+
+    server.state().await.expire_sessions();
+
+    assert_eq!(api.app_version().await.unwrap(), "v4.6.7");
+}
+
+#[tokio::test]
+async fn forbidden_answers_of_a_valid_session_are_not_sent_again() {
+    let server = MockServer::start().await;
+    server.state().await.insert_torrent(MockTorrent::new("aaaa", "first"));
+    let api = connect(&server).await;
+
+    // like qbittorrent without write access to the directory.
+    server.state().await.failures.push_back(403);
+    let error = api.torrents_set_save_path(TorrentHashesDesc::All, "/readonly").await.unwrap_err();
+    assert_eq!(error.code, Some(403));
+
+    let state = server.state().await;
+    let count = |path: &str| state.requests.iter().filter(|(_, p)| p == path).count();
+    assert_eq!(count("/api/v2/auth/login"), 1);
+    assert_eq!(count("/api/v2/torrents/setSavePath"), 1);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn sessions_expiring_under_concurrent_requests_are_renewed_once() {
+    let server = MockServer::start().await;
+    // expired one second before the server's timeout, which is half of it.
+    server.state().await.preferences.insert("web_ui_session_timeout".to_string(), 2.into());
+    let api = connect(&server).await;
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+
+    // the requests all start at once, so that they find the session expired together.
+    let barrier = std::sync::Arc::new(tokio::sync::Barrier::new(16));
+    let calls: Vec<_> = (0..16)
+        .map(|_| {
+            let (api, barrier) = (api.clone(), barrier.clone());
+            tokio::spawn(async move {
+                barrier.wait().await;
+                api.app_version().await
+            })
+        })
+        .collect();
+    for call in calls {
+        let version = tokio::time::timeout(Duration::from_secs(10), call).await.expect("the requests deadlocked");
+        assert_eq!(version.unwrap().unwrap(), "v4.6.7");
+    }
+
+    let logins = server.state().await.requests.iter().filter(|(_, path)| path == "/api/v2/auth/login").count();
+    assert_eq!(logins, 2);
+}
+
+#[tokio::test]
+async fn failed_session_renewals_are_tried_again() {
+    let server = MockServer::start().await;
+    server.state().await.preferences.insert("web_ui_session_timeout".to_string(), 2.into());
+    let api = connect(&server).await;
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+
+    server.state().await.password = "changed".to_string();
+    let error = api.app_version().await.unwrap_err();
+    assert!(matches!(error.err_type, ErrorType::WrongCreds), "{:?}", error);
+
+    server.state().await.password = "adminadmin".to_string();
+    assert_eq!(api.app_version().await.unwrap(), "v4.6.7");
+
+    let logins = server.state().await.requests.iter().filter(|(_, path)| path == "/api/v2/auth/login").count();
+    assert_eq!(logins, 3);
+}
+
+#[tokio::test]
+async fn added_torrents_are_listed() {
+    let server = MockServer::start().await;
+    let api = connect(&server).await;
+
+    let magnet = "magnet:?xt=urn:btih:0123456789abcdef0123456789abcdef01234567&dn=ubuntu";
+    let descriptor = TorrentAddDescriptor::builder(vec![Torrent::new(TorrentType::Url(magnet))])
+        .savepath("/isos")
+        .build()
+        .unwrap();
+    let outcomes = api.torrents_add_torrent(&descriptor).await.unwrap();
+
+    let hash = TorrentHash::new("ubuntu", "0123456789abcdef0123456789abcdef01234567");
+    assert_eq!(outcomes, [AddTorrentOutcome::Added(hash)]);
+
+    let hashes = api.torrents_get_hashes().await.unwrap();
+    assert_eq!(hashes.len(), 1);
+    assert_eq!(hashes[0].name, "ubuntu");
+
+    let torrent = &server.state().await.torrents[&hashes[0].hash];
+    assert_eq!(torrent.save_path, "/isos");
+}
+
+#[tokio::test]
+async fn parsed_torrent_files_are_uploaded() {
+    let server = MockServer::start().await;
+    let api = connect(&server).await;
+
+    let data = b"d4:infod6:lengthi5e4:name5:notes12:piece lengthi16384e6:pieces20:aaaaaaaaaaaaaaaaaaaaee";
+    let metainfo = Metainfo::from_bytes(&data[..]).unwrap();
+    let hash = metainfo.hash();
+
+    let descriptor = TorrentAddDescriptor::builder(vec![Torrent::from_metainfo(metainfo)])
+        .category("docs")
+        .build()
+        .unwrap();
+    api.torrents_add_torrent(&descriptor).await.unwrap();
+
+    let torrent = &server.state().await.torrents[&hash];
+    assert_eq!((torrent.name.as_str(), torrent.category.as_str()), ("notes", "docs"));
+}
+
+#[tokio::test]
+async fn each_added_torrent_has_an_outcome() {
+    let server = MockServer::start().await;
+    server
+        .state()
+        .await
+        .insert_torrent(MockTorrent::new("0123456789abcdef0123456789abcdef01234567", "existing"));
+    let api = connect(&server).await;
+
+    let data = b"d4:infod6:lengthi5e4:name5:notes12:piece lengthi16384e6:pieces20:aaaaaaaaaaaaaaaaaaaaee";
+    let metainfo = Metainfo::from_bytes(&data[..]).unwrap();
+    let invalid_file = std::env::temp_dir().join(format!("qbit-invalid-{}.torrent", std::process::id()));
+    std::fs::write(&invalid_file, "not a torrent").unwrap();
+
+    let torrents = vec![
+        Torrent::new(TorrentType::Url("magnet:?xt=urn:btih:0123456789abcdef0123456789abcdef01234567")),
+        Torrent::new(TorrentType::Url("http://example.org/debian.torrent")),
+        Torrent::from_metainfo(metainfo.clone()),
+    ];
+    let outcomes = api.torrents_add_torrent(TorrentAddDescriptor::new(torrents).unwrap()).await.unwrap();
+
+    assert_eq!(
+        outcomes,
+        [
+            AddTorrentOutcome::AlreadyPresent(TorrentHash::new("existing", "0123456789abcdef0123456789abcdef01234567")),
+            AddTorrentOutcome::Pending(None),
+            AddTorrentOutcome::Added(metainfo.torrent_hash()),
+        ]
+    );
+
+    let torrents = vec![Torrent::from_metainfo(metainfo.clone()), Torrent::new(TorrentType::TorrentFile(invalid_file.to_str().unwrap()))];
+    let outcomes = api.torrents_add_torrent(TorrentAddDescriptor::new(torrents).unwrap()).await.unwrap();
+    std::fs::remove_file(&invalid_file).unwrap();
+
+    assert_eq!(outcomes[0], AddTorrentOutcome::AlreadyPresent(metainfo.torrent_hash()));
+    assert!(matches!(outcomes[1], AddTorrentOutcome::Rejected(_)));
+}
+
+#[tokio::test]
+async fn added_hashes_are_read_from_the_json_answer() {
+    let server = MockServer::start().await;
+    server.state().await.web_api_version = "2.11.4".to_string();
+    let api = connect(&server).await;
+
+    let descriptor = TorrentAddDescriptor::new(vec![Torrent::new(TorrentType::Url("http://example.org/debian.torrent"))]).unwrap();
+    let outcomes = api.torrents_add_torrent(&descriptor).await.unwrap();
+
+    let [AddTorrentOutcome::Added(hash)] = &outcomes[..] else {
+        panic!("unexpected outcomes: {:?}", outcomes);
+    };
+    assert_eq!(hash.name, "debian");
+
+    api.torrents_add_tags(TorrentHashesDesc::Hashes(vec![hash.clone()]), vec!["linux"]).await.unwrap();
+    assert!(server.state().await.torrents[&hash.hash].tags.contains("linux"));
+
+    let outcomes = api.torrents_add_torrent(&descriptor).await.unwrap();
+    assert!(matches!(outcomes[..], [AddTorrentOutcome::Rejected(_)]));
+}
+
+#[tokio::test]
+async fn torrents_can_be_paused_and_deleted() {
+    let server = MockServer::start().await;
+    server
+        .state()
+        .await
+        .insert_torrent(MockTorrent::new("aaaa", "first"));
+    let api = connect(&server).await;
+
+    let hashes = TorrentHashesDesc::Hashes(vec![TorrentHash::new("first", "aaaa")]);
+    api.torrents_pause_torrents(&hashes).await.unwrap();
+    assert_eq!(server.state().await.torrents["aaaa"].state, "pausedDL");
+
+    api.torrents_delete_torrents(&hashes, false).await.unwrap();
+    assert!(server.state().await.torrents.is_empty());
+}
+
+#[tokio::test]
+async fn qbittorrent_5_torrents_are_stopped() {
+    let server = MockServer::start().await;
+    {
+        let mut state = server.state().await;
+        state.web_api_version = "2.11.2".to_string();
+        state.insert_torrent(MockTorrent::new("aaaa", "first"));
+    }
+    let api = connect(&server).await;
+    assert_eq!(api.web_api_version().await, Some(ApiVersion::new(2, 11, 2)));
+
+    let hashes = TorrentHashesDesc::Hashes(vec![TorrentHash::new("first", "aaaa")]);
+    api.torrents_pause_torrents(&hashes).await.unwrap();
+    assert_eq!(server.state().await.torrents["aaaa"].state, "stoppedDL");
+
+    let stopped = TorrentListGetConfig::builder().filter(State::Paused).build();
+    let torrents = api.torrents_get_torrent_list(&stopped).await.unwrap();
+    assert_eq!(torrents.as_array().unwrap().len(), 1);
+    let state: TorrentState = serde_json::from_value(torrents[0]["state"].clone()).unwrap();
+    assert!(state.is_stopped());
+
+    api.torrents_start(&hashes).await.unwrap();
+    assert_eq!(server.state().await.torrents["aaaa"].state, "stalledDL");
+    assert!(server.state().await.requests.iter().all(|(_, path)| !path.ends_with("/pause")));
+}
+
+#[tokio::test]
+async fn unsupported_endpoints_are_refused() {
+    let server = MockServer::start().await;
+    server.state().await.web_api_version = "2.6.0".to_string();
+    let api = connect(&server).await;
+
+    let hash = TorrentHash::new("first", "aaaa");
+    let error = api.torrents_rename_folder(&hash, "a", "b").await.unwrap_err();
+    assert!(matches!(
+        error.err_type,
+        ErrorType::UnsupportedByServer { required: ApiVersion::V2_7_0, actual } if actual == ApiVersion::new(2, 6, 0)
+    ));
+    assert!(!server.state().await.requests.iter().any(|(_, path)| path.ends_with("/renameFolder")));
+}
+
+async fn next_event(events: &mut SyncEventStream) -> Option<Result<SyncEvent, qbittorrent_rust::Error>> {
+    std::future::poll_fn(|cx| Pin::new(&mut *events).poll_next(cx)).await
+}
+
+#[tokio::test]
+async fn only_idempotent_requests_are_retried() {
+    let server = MockServer::start().await;
+    server.state().await.search_catalog = vec![search_result("ubuntu")];
+    let mut api = connect(&server).await;
+    api.set_retry_policy(RetryPolicy::default().base_delay(Duration::from_millis(1)));
+
+    server.state().await.failures.push_back(503);
+    assert_eq!(api.app_version().await.unwrap(), "v4.6.7");
+
+    server.state().await.failures.push_back(503);
+    let error = api.search_start("ubuntu", SearchPluginsSpec::Enabled, Categories::All).await.unwrap_err();
+    assert_eq!(error.code, Some(503));
+
+    let state = server.state().await;
+    let count = |path: &str| state.requests.iter().filter(|(_, p)| p == path).count();
+    assert_eq!(count("/api/v2/app/version"), 2);
+    assert_eq!(count("/api/v2/search/start"), 1);
+    assert!(state.search_jobs.is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn requests_in_flight_are_capped() {
+    let server = MockServer::start().await;
+    let api = QbitApi::builder(server.url())
+        .auth(server.credentials())
+        .rate_limit(RateLimit::new().max_in_flight(3))
+        .build()
+        .await
+        .unwrap();
+    server.state().await.response_delay = Duration::from_millis(100);
+
+    let calls: Vec<_> = (0..12)
+        .map(|_| {
+            let api = api.clone();
+            tokio::spawn(async move { api.app_version().await })
+        })
+        .collect();
+    for call in calls {
+        call.await.unwrap().unwrap();
+    }
+
+    assert_eq!(server.state().await.max_in_flight, 3);
+}
+
+#[tokio::test(start_paused = true)]
+async fn requests_are_sent_at_the_configured_rate() {
+    let server = MockServer::start().await;
+    let api = QbitApi::builder(server.url())
+        .auth(server.credentials())
+        .rate_limit(RateLimit::new().requests_per_second(10.0).burst(2))
+        .build()
+        .await
+        .unwrap();
+
+    let start = tokio::time::Instant::now();
+    let calls: Vec<_> = (0..20)
+        .map(|_| {
+            let api = api.clone();
+            tokio::spawn(async move { api.app_version().await })
+        })
+        .collect();
+    for call in calls {
+        call.await.unwrap().unwrap();
+    }
+
+    // at most `burst` requests go out at once, then one every 100ms, whichever clone sends them.
+    assert!(start.elapsed() >= Duration::from_millis(1800), "{:?}", start.elapsed());
+}
+
+#[tokio::test]
+async fn sync_events_are_streamed() {
+    let server = MockServer::start().await;
+    server.state().await.insert_torrent(MockTorrent::new("aaaa", "first"));
+    let api = connect(&server).await;
+
+    let mut events = api.sync_events(Duration::from_millis(10));
+
+    let event = next_event(&mut events).await.unwrap().unwrap();
+    assert!(matches!(&event, SyncEvent::TorrentAdded(torrent) if torrent.name == "first"), "{:?}", event);
+    let event = next_event(&mut events).await.unwrap().unwrap();
+    assert!(matches!(event, SyncEvent::ServerStateChanged(_)), "{:?}", event);
+
+    server.state().await.torrents.get_mut("aaaa").unwrap().progress = 0.5;
+    let event = next_event(&mut events).await.unwrap().unwrap();
+    assert!(matches!(&event, SyncEvent::ProgressChanged { hash, to, .. } if hash == "aaaa" && *to == 0.5), "{:?}", event);
+
+    server.state().await.torrents.clear();
+    let event = next_event(&mut events).await.unwrap().unwrap();
+    assert!(matches!(&event, SyncEvent::TorrentRemoved(torrent) if torrent.hash == "aaaa"), "{:?}", event);
+}
+
+#[tokio::test]
+async fn categories_and_tags_are_stored() {
+    let server = MockServer::start().await;
+    let api = connect(&server).await;
+
+    api.torrents_add_new_category("movies", Some("/movies")).await.unwrap();
+    api.torrents_add_new_tags(vec!["hd", "new"]).await.unwrap();
+
+    let state = server.state().await;
+    assert_eq!(state.categories["movies"], "/movies");
+    assert!(state.tags.contains("hd") && state.tags.contains("new"));
+}
+
+#[tokio::test]
+async fn tags_are_assigned_to_torrents() {
+    let server = MockServer::start().await;
+    server.state().await.insert_torrent(MockTorrent::new("aaaa", "first"));
+    let api = connect(&server).await;
+
+    let hashes = TorrentHashesDesc::Hashes(vec![TorrentHash::new("first", "aaaa")]);
+    api.torrents_add_tags(&hashes, vec!["hd", "new"]).await.unwrap();
+    api.torrents_remove_tags_from_torrents(&hashes, vec!["new"]).await.unwrap();
+    {
+        let state = server.state().await;
+        assert!(state.tags.contains("new"));
+        assert_eq!(state.torrents["aaaa"].tags.iter().collect::<Vec<_>>(), ["hd"]);
+    }
+
+    let error = api.torrents_set_tags(&hashes, vec!["sd"]).await.unwrap_err();
+    assert!(matches!(error.err_type, ErrorType::UnsupportedByServer { .. }));
+
+    server.state().await.web_api_version = "2.11.4".to_string();
+    api.refresh_web_api_version().await.unwrap();
+    api.torrents_set_tags(&hashes, vec!["sd"]).await.unwrap();
+    assert_eq!(server.state().await.torrents["aaaa"].tags.iter().collect::<Vec<_>>(), ["sd"]);
+}
+
+#[tokio::test]
+async fn torrents_are_counted_moved_and_exported() {
+    let server = MockServer::start().await;
+    {
+        let mut state = server.state().await;
+        state.web_api_version = "2.11.2".to_string();
+        state.insert_torrent(MockTorrent::new("aaaa", "first"));
+        state.insert_torrent(MockTorrent::new("bbbb", "second"));
+    }
+    let api = connect(&server).await;
+    assert_eq!(api.torrents_count().await.unwrap(), 2);
+
+    api.torrents_set_save_path(TorrentHashesDesc::All, "/archive").await.unwrap();
+    api.torrents_set_download_path(TorrentHashesDesc::All, "/incomplete").await.unwrap();
+    {
+        let state = server.state().await;
+        assert!(state.torrents.values().all(|t| t.save_path == "/archive" && t.download_path == "/incomplete"));
+    }
+
+    let hash = TorrentHash::new("first", "aaaa");
+    let exported = api.torrents_export(&hash).await.unwrap();
+    assert!(exported.starts_with(b"d8:announce"));
+
+    api.torrents_add_web_seeds(&hash, vec!["http://a.invalid/", "http://b.invalid/"]).await.unwrap();
+    api.torrents_edit_web_seed(&hash, "http://a.invalid/", "http://c.invalid/").await.unwrap();
+    api.torrents_remove_web_seeds(&hash, vec!["http://b.invalid/"]).await.unwrap();
+    assert_eq!(server.state().await.torrents["aaaa"].web_seeds, ["http://c.invalid/"]);
+
+    let error = api.torrents_edit_web_seed(&hash, "http://a.invalid/", "http://d.invalid/").await.unwrap_err();
+    assert_eq!(error.code, Some(409));
+    let error = api.torrents_set_save_path(TorrentHashesDesc::All, "").await.unwrap_err();
+    assert_eq!(error.code, Some(400));
+
+    // a status the endpoint isn't documented to return is passed on as it is.
+    server.state().await.failures.extend([500, 500]);
+    let error = api.torrents_add_web_seeds(&hash, vec!["http://e.invalid/"]).await.unwrap_err();
+    assert_eq!(error.code, Some(500));
+    let error = api.torrents_set_download_path(TorrentHashesDesc::All, "/tmp").await.unwrap_err();
+    assert_eq!(error.code, Some(500));
+
+    let missing = TorrentHash::new("missing", "ffff");
+    let error = api.torrents_export(&missing).await.unwrap_err();
+    assert!(matches!(error.err_type, ErrorType::TorrentHashNotFound));
+}
+
+#[tokio::test]
+async fn torrents_are_created_on_the_server() {
+    let server = MockServer::start().await;
+    server.state().await.web_api_version = "2.11.2".to_string();
+    let api = connect(&server).await;
+
+    let task = TorrentCreationTask::builder("/data/weather")
+        .private(true)
+        .trackers(vec!["http://tracker.invalid/announce".to_string()])
+        .format(TorrentFormat::Hybrid)
+        .build();
+    let torrent = api.torrent_creator_create_torrent(&task, Duration::from_millis(10), None).await.unwrap();
+
+    assert!(torrent.windows(7).any(|w| w == b"weather"));
+    assert!(server.state().await.creation_tasks.is_empty());
+
+    let task_id = api.torrent_creator_add_task(&task).await.unwrap();
+    server.state().await.creation_tasks.get_mut(&task_id).unwrap().status = "Failed".to_string();
+    let status = api.torrent_creator_status(Some(&task_id)).await.unwrap();
+    assert_eq!(status[0].status, TorrentCreationState::Failed);
+    assert!(api.torrent_creator_wait_for_torrent(&task_id, Duration::from_millis(10), None).await.is_err());
+
+    // a task that never finishes is given up on.
+    server.state().await.creation_tasks.get_mut(&task_id).unwrap().status = "Running".to_string();
+    let waited = api.torrent_creator_wait_for_torrent(&task_id, Duration::from_millis(10), Some(Duration::from_millis(100)));
+    let error = tokio::time::timeout(Duration::from_secs(5), waited).await.unwrap().unwrap_err();
+    assert!(matches!(error.err_type, ErrorType::MiscError(_)));
+    assert!(server.state().await.creation_tasks.contains_key(&task_id));
+}
+
+#[tokio::test]
+async fn rss_feeds_are_added() {
+    let server = MockServer::start().await;
+    let api = connect(&server).await;
+
+    api.rss_add_folder("news").await.unwrap();
+    api.rss_add_feed("http://example.com/feed.xml", Some("news\\example")).await.unwrap();
+
+    let items = api.rss_get_all_items(None).await.unwrap();
+    assert_eq!(items["news"]["example"]["url"], "http://example.com/feed.xml");
+
+    let tree = api.rss_get_all_items_typed(Some(true)).await.unwrap();
+    assert!(tree.get("news").unwrap().is_folder());
+    let Some(RssItem::Feed { url, is_loading, .. }) = tree.get("news\\example") else {
+        panic!("the feed is missing: {:?}", tree);
+    };
+    assert_eq!((url.as_str(), *is_loading), ("http://example.com/feed.xml", false));
+}
+
+#[tokio::test]
+async fn rss_feeds_are_edited() {
+    let server = MockServer::start().await;
+    let api = connect(&server).await;
+
+    api.rss_add_feed("http://example.com/feed.xml", Some("example")).await.unwrap();
+    api.rss_set_feed_url("example", "http://example.com/rss.xml").await.unwrap();
+    api.rss_mark_as_read("example", None::<String>).await.unwrap();
+    // qbittorrent ignores the items that don't exist.
+    api.rss_mark_as_read("missing", Some("1")).await.unwrap();
+
+    let items = api.rss_get_all_items(None).await.unwrap();
+    assert_eq!(items["example"]["url"], "http://example.com/rss.xml");
+
+    server.state().await.web_api_version = "2.11.4".to_string();
+    api.refresh_web_api_version().await.unwrap();
+    api.rss_set_feed_refresh_interval("example", Duration::from_secs(15 * 60)).await.unwrap();
+    assert_eq!(server.state().await.rss_items["example"]["refreshInterval"], 900);
+}
+
+#[tokio::test]
+async fn rss_conflicts_have_typed_errors() {
+    let server = MockServer::start().await;
+    let api = connect(&server).await;
+
+    api.rss_add_folder("news").await.unwrap();
+    api.rss_add_feed("http://example.com/feed.xml", None::<String>).await.unwrap();
+
+    let error = api.rss_add_folder("news").await.unwrap_err();
+    assert!(matches!(error.err_type, ErrorType::RssItemAlreadyExists(_)), "{:?}", error);
+    assert_eq!(error.code, Some(409));
+    let error = api.rss_add_feed("http://example.com/feed.xml", Some("news\\again")).await.unwrap_err();
+    assert!(matches!(error.err_type, ErrorType::RssItemAlreadyExists(_)), "{:?}", error);
+
+    let error = api.rss_add_folder("missing\\news").await.unwrap_err();
+    assert!(matches!(error.err_type, ErrorType::RssItemNotFound(_)), "{:?}", error);
+    let error = api.rss_remove_item("missing").await.unwrap_err();
+    assert!(matches!(error.err_type, ErrorType::RssItemNotFound(_)), "{:?}", error);
+    let error = api.rss_set_feed_url("news", "http://example.com/other.xml").await.unwrap_err();
+    assert!(matches!(error.err_type, ErrorType::RssItemNotFound(_)), "{:?}", error);
+
+    let error = api.rss_move_item("news", "news\\inner").await.unwrap_err();
+    assert!(matches!(error.err_type, ErrorType::RssInvalidPath(_)), "{:?}", error);
+    assert!(api.rss_get_all_items(None).await.unwrap()["news"].is_object());
+    let error = api.rss_remove_item("").await.unwrap_err();
+    assert!(matches!(error.err_type, ErrorType::RssInvalidPath(_)), "{:?}", error);
+
+    let error = api.rss_set_feed_refresh_interval("http://example.com/feed.xml", Duration::from_secs(60)).await.unwrap_err();
+    assert!(matches!(error.err_type, ErrorType::UnsupportedByServer { .. }), "{:?}", error);
+
+    server.state().await.web_api_version = "2.11.4".to_string();
+    api.refresh_web_api_version().await.unwrap();
+    let error = api.rss_set_feed_refresh_interval("missing", Duration::from_secs(60)).await.unwrap_err();
+    assert!(matches!(error.err_type, ErrorType::RssItemNotFound(_)), "{:?}", error);
+}
+
+#[tokio::test]
+async fn old_servers_are_refused_the_newer_torrent_endpoints() {
+    let server = MockServer::start().await;
+    {
+        let mut state = server.state().await;
+        state.web_api_version = "2.8.3".to_string();
+        state.insert_torrent(MockTorrent::new("aaaa", "first"));
+    }
+    let api = connect(&server).await;
+
+    let error = api.torrents_count().await.unwrap_err();
+    assert!(matches!(error.err_type, ErrorType::UnsupportedByServer { required: ApiVersion::V2_9_3, .. }), "{:?}", error);
+    let error = api.torrents_export(TorrentHash::new("first", "aaaa")).await.unwrap_err();
+    assert!(matches!(error.err_type, ErrorType::UnsupportedByServer { required: ApiVersion::V2_8_14, .. }), "{:?}", error);
+    let error = api.torrents_set_save_path(TorrentHashesDesc::All, "/archive").await.unwrap_err();
+    assert!(matches!(error.err_type, ErrorType::UnsupportedByServer { required: ApiVersion::V2_8_4, .. }), "{:?}", error);
+    let error = api.torrents_set_download_path(TorrentHashesDesc::All, "/incomplete").await.unwrap_err();
+    assert!(matches!(error.err_type, ErrorType::UnsupportedByServer { required: ApiVersion::V2_8_4, .. }), "{:?}", error);
+
+    let state = server.state().await;
+    assert!(state.requests.iter().all(|(_, path)| !path.starts_with("/api/v2/torrents/")), "{:?}", state.requests);
+}
+
+fn search_result(name: &str) -> serde_json::Value {
+    serde_json::json!({
+        "fileName": name,
+        "fileUrl": format!("http://site.invalid/{}.torrent", name),
+        "fileSize": 1024,
+        "nbSeeders": 10,
+        "nbLeechers": -1,
+        "siteUrl": "http://site.invalid",
+        "descrLink": format!("http://site.invalid/{}", name),
+    })
+}
+
+#[tokio::test]
+async fn search_sessions_stream_results_until_stopped() {
+    let server = MockServer::start().await;
+    server.state().await.search_catalog = ["ubuntu 22.04", "debian 12", "ubuntu 24.04"].map(search_result).to_vec();
+    let api = connect(&server).await;
+
+    let mut session = api
+        .search_session("ubuntu", SearchPluginsSpec::All, Categories::All, Duration::from_millis(10))
+        .await
+        .unwrap();
+
+    let mut results = Vec::new();
+    while let Some(result) = std::future::poll_fn(|cx| Pin::new(&mut session).poll_next(cx)).await {
+        results.push(result.unwrap());
+    }
+    assert_eq!(results.iter().map(|r| r.file_name.as_str()).collect::<Vec<_>>(), ["ubuntu 22.04", "ubuntu 24.04"]);
+    assert_eq!((results[0].file_size, results[0].nb_leechers), (1024, -1));
+
+    let id = session.id();
+    let status = api.search_status_typed(Some(id)).await.unwrap();
+    assert_eq!(status, [SearchStatus { id, status: SearchJobStatus::Stopped, total: 2 }]);
+
+    drop(session);
+    for _ in 0..100 {
+        if server.state().await.search_jobs.is_empty() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert!(server.state().await.search_jobs.is_empty());
+}
+
+#[tokio::test]
+async fn search_sessions_keep_polling_after_an_error() {
+    let server = MockServer::start().await;
+    server.state().await.search_catalog = ["ubuntu 22.04", "debian 12", "ubuntu 24.04"].map(search_result).to_vec();
+    let api = connect(&server).await;
+
+    let mut session = api
+        .search_session("ubuntu", SearchPluginsSpec::All, Categories::All, Duration::from_millis(50))
+        .await
+        .unwrap();
+    // the next poll fails with a status `/search/results` isn't documented to return.
+    server.state().await.failures.push_back(500);
+
+    let (mut results, mut errors) = (Vec::new(), Vec::new());
+    while let Some(result) = std::future::poll_fn(|cx| Pin::new(&mut session).poll_next(cx)).await {
+        match result {
+            Ok(result) => results.push(result.file_name),
+            Err(e) => errors.push(e.code),
+        }
+    }
+    assert_eq!(errors, [Some(500)]);
+    assert_eq!(results, ["ubuntu 22.04", "ubuntu 24.04"]);
+}
+
+#[tokio::test]
+async fn only_five_searches_run_at_once() {
+    let server = MockServer::start().await;
+    server.state().await.search_catalog = vec![search_result("ubuntu")];
+    let api = connect(&server).await;
+
+    let mut ids = Vec::new();
+    for _ in 0..5 {
+        ids.push(api.search_start("ubuntu", SearchPluginsSpec::Enabled, Categories::All).await.unwrap());
+    }
+
+    let error = api.search_start("ubuntu", SearchPluginsSpec::Enabled, Categories::All).await.unwrap_err();
+    assert_eq!(error.code, Some(409));
+
+    let page = api.search_results_typed(ids[0], None, None).await.unwrap();
+    assert_eq!((page.status, page.total), (SearchJobStatus::Stopped, 1));
+    api.search_start("ubuntu", SearchPluginsSpec::Enabled, Categories::All).await.unwrap();
+}
+
+#[tokio::test]
+async fn the_best_search_results_are_picked_and_added() {
+    let server = MockServer::start().await;
+    let result = |name: &str, url: &str, size: i64, seeders: i64| {
+        let mut result = search_result(name);
+        result["fileUrl"] = url.into();
+        result["fileSize"] = size.into();
+        result["nbSeeders"] = seeders.into();
+        result
+    };
+    let magnet = |hash: char, name: &str| format!("magnet:?xt=urn:btih:{}&dn={}", hash.to_string().repeat(40), name);
+    server.state().await.search_catalog = vec![
+        result("Ubuntu 24.04", &magnet('a', "ubuntu-24.04"), 5_000, 100),
+        result("ubuntu 24.04 ", "http://other.invalid/ubuntu.torrent", 5_000, 50),
+        result("Ubuntu 24.04 mirror", &magnet('a', "mirror"), 5_000, 80),
+        result("Ubuntu 22.04", &magnet('b', "ubuntu-22.04"), 4_000, 3),
+        result("Ubuntu tiny", &magnet('c', "ubuntu-tiny"), 10, 500),
+        result("Ubuntu 23.10", &magnet('d', "ubuntu-23.10"), 4_500, 60),
+    ];
+    let api = connect(&server).await;
+
+    let builder = || {
+        SearchPick::builder("ubuntu")
+            .min_seeders(5)
+            .min_size(1_000)
+            .limit(5)
+            .poll_interval(Duration::from_millis(10))
+    };
+
+    let names = |results: &[SearchResult]| results.iter().map(|r| r.file_name.clone()).collect::<Vec<_>>();
+    let picked = api.search_pick_results(builder().build()).await.unwrap();
+    assert_eq!(names(&picked), ["Ubuntu 24.04", "Ubuntu 23.10"]);
+
+    let smallest_first = builder().score(|result| -(result.file_size as f64)).build();
+    let picked = api.search_pick_results(&smallest_first).await.unwrap();
+    assert_eq!(names(&picked), ["Ubuntu 23.10", "Ubuntu 24.04"]);
+
+    let picked = api
+        .search_and_pick(builder().limit(1).build(), TorrentAddDescriptor::builder(vec![]).category("linux"))
+        .await
+        .unwrap();
+    assert_eq!(picked.len(), 1);
+    assert_eq!(picked[0].result.file_name, "Ubuntu 24.04");
+    assert_eq!(picked[0].outcome, AddTorrentOutcome::Added(TorrentHash::new("ubuntu-24.04", "a".repeat(40))));
+    assert_eq!(server.state().await.torrents[&"a".repeat(40)].category, "linux");
+}
+
+#[tokio::test]
+async fn search_plugins_converge_to_a_manifest() {
+    let server = MockServer::start().await;
+    let api = connect(&server).await;
+
+    let directory = std::env::temp_dir().join(format!("qbit-plugins-{}", std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+    let file = directory.join("mysite.py");
+    std::fs::write(&file, "#VERSION: 1.2\n#AUTHORS: someone\n").unwrap();
+
+    api.search_install_plugin_file(&file).await.unwrap();
+    let plugins = api.search_get_search_plugins_info().await.unwrap();
+    let mysite = plugins.iter().find(|p| p.name == "mysite").unwrap();
+    assert_eq!((mysite.version.as_str(), mysite.enabled), ("1.2", true));
+
+    std::fs::write(&file, "#VERSION: 1.3\n").unwrap();
+    let source = file.to_str().unwrap();
+    let manifest = SearchPluginManifest {
+        plugins: vec![
+            DesiredSearchPlugin { min_version: Some("1.3".to_string()), enabled: false, ..DesiredSearchPlugin::new("mysite", source) },
+            DesiredSearchPlugin::new("eztv", "http://plugins.invalid/eztv.py"),
+        ],
+        uninstall_others: true,
+    };
+
+    let outcome = api.search_apply_plugin_manifest(&manifest, Duration::from_secs(5)).await.unwrap();
+    std::fs::remove_dir_all(&directory).unwrap();
+    assert_eq!(outcome.applied.install, [source, "http://plugins.invalid/eztv.py"]);
+    assert_eq!(outcome.applied.uninstall, ["mock"]);
+    assert_eq!(outcome.applied.disable, ["mysite"]);
+    assert!(outcome.skipped.is_empty());
+
+    let plugins = api.search_get_search_plugins_info().await.unwrap();
+    let summary: Vec<_> = plugins.iter().map(|p| (p.name.as_str(), p.version.as_str(), p.enabled)).collect();
+    assert_eq!(summary, [("mysite", "1.3", false), ("eztv", "1.0", true)]);
+
+    let installed = api.search_get_search_plugins_info().await.unwrap();
+    assert!(manifest.diff(&installed).is_empty());
+}
+
+#[tokio::test]
+async fn manifests_wait_for_the_plugins_to_be_installed() {
+    let server = MockServer::start().await;
+    server.state().await.plugin_install_polls = 3;
+    let api = connect(&server).await;
+
+    let manifest = SearchPluginManifest {
+        plugins: vec![DesiredSearchPlugin { enabled: false, ..DesiredSearchPlugin::new("eztv", "http://plugins.invalid/eztv.py") }],
+        uninstall_others: false,
+    };
+    let outcome = api.search_apply_plugin_manifest(&manifest, Duration::from_secs(5)).await.unwrap();
+    assert_eq!(outcome.applied.disable, ["eztv"]);
+    assert!(outcome.skipped.is_empty());
+    assert!(manifest.diff(&api.search_get_search_plugins_info().await.unwrap()).is_empty());
+
+    // a plugin still installing after the timeout can't be disabled yet.
+    server.state().await.plugin_install_polls = 100;
+    let manifest = SearchPluginManifest {
+        plugins: vec![DesiredSearchPlugin { enabled: false, ..DesiredSearchPlugin::new("jackett", "http://plugins.invalid/jackett.py") }],
+        uninstall_others: false,
+    };
+    let outcome = api.search_apply_plugin_manifest(&manifest, Duration::from_millis(300)).await.unwrap();
+    assert_eq!(outcome.applied.install, ["http://plugins.invalid/jackett.py"]);
+    assert!(outcome.applied.disable.is_empty());
+    assert_eq!(outcome.skipped.install, ["http://plugins.invalid/jackett.py"]);
+    assert_eq!(outcome.skipped.disable, ["jackett"]);
+
+    // once it's installed, applying the manifest again finishes the job.
+    server.state().await.plugin_install_polls = 0;
+    server.state().await.installing_plugins.iter_mut().for_each(|(polls, _)| *polls = 1);
+    let outcome = api.search_apply_plugin_manifest(&manifest, Duration::from_secs(5)).await.unwrap();
+    assert_eq!(outcome.applied.disable, ["jackett"]);
+    assert!(outcome.applied.install.is_empty() && outcome.skipped.is_empty());
+}
+
+#[tokio::test]
+async fn recorded_exchanges_are_replayed() {
+    let server = MockServer::start().await;
+    let cassette = std::env::temp_dir().join(format!("qbit-cassette-{}.json", std::process::id()));
+
+    let data = b"d4:infod6:lengthi5e4:name5:notes12:piece lengthi16384e6:pieces20:aaaaaaaaaaaaaaaaaaaaee";
+    let metainfo = Metainfo::from_bytes(&data[..]).unwrap();
+    let descriptor = TorrentAddDescriptor::builder(vec![Torrent::from_metainfo(metainfo.clone())])
+        .category("docs")
+        .build()
+        .unwrap();
+
+    let api = QbitApi::builder(server.url())
+        .auth(server.credentials())
+        .cassette(CassetteMode::Record(cassette.clone()))
+        .build()
+        .await
+        .unwrap();
+    api.torrents_add_new_category("docs", Some("/docs")).await.unwrap();
+    let recorded = api.torrents_add_torrent(&descriptor).await.unwrap();
+
+    // neither the credentials nor the session id are written down.
+    let file = std::fs::read_to_string(&cassette).unwrap();
+    assert!(!file.contains("adminadmin"));
+    let sid = server.state().await.sessions.iter().next().unwrap().clone();
+    assert!(!file.contains(&sid));
+
+    // the replay doesn't reach the server, and logs in with other credentials.
+    let requests = server.state().await.requests.len();
+    let api = QbitApi::builder(server.url())
+        .auth(Credentials::new("someone", "else"))
+        .cassette(CassetteMode::Replay(cassette.clone()))
+        .build()
+        .await
+        .unwrap();
+    api.torrents_add_new_category("docs", Some("/docs")).await.unwrap();
+    let replayed = api.torrents_add_torrent(&descriptor).await.unwrap();
+    assert_eq!(server.state().await.requests.len(), requests);
+    assert_eq!(replayed, recorded);
+    assert_eq!(recorded, [AddTorrentOutcome::Added(metainfo.torrent_hash())]);
+
+    // a request that wasn't recorded has nothing to be replayed with.
+    assert!(api.torrents_add_new_category("other", None::<String>).await.is_err());
+    std::fs::remove_file(&cassette).unwrap();
+}
@@ -0,0 +1,81 @@
+use qbittorrent_rust::RssItem;
+
+const ITEMS: &str = r#"{
+    "Linux": {
+        "Debian": {
+            "uid": "{6a8d2a21-3c71-4b5b-a2b8-2d8c9c7d2f10}",
+            "url": "https://www.debian.org/News/news",
+            "title": "Debian News",
+            "lastBuildDate": "Tue, 08 Oct 2024 10:00:00 GMT",
+            "isLoading": false,
+            "hasError": false,
+            "articles": [
+                {
+                    "id": "https://www.debian.org/News/2024/20241008",
+                    "title": "Debian 12.8 released",
+                    "date": "08 Oct 2024 10:00:00 +0000",
+                    "link": "https://www.debian.org/News/2024/20241008",
+                    "torrentURL": "https://cdimage.debian.org/debian-12.8.0-amd64-netinst.iso.torrent",
+                    "isRead": true
+                },
+                { "id": "2", "title": "Debian 12.9 released" }
+            ]
+        },
+        "Empty": {}
+    },
+    "Arch": { "uid": "{b0b3}", "url": "https://archlinux.org/feeds/news/" }
+}"#;
+
+#[test]
+fn the_rss_tree_is_parsed() {
+    let tree: RssItem = serde_json::from_str(ITEMS).unwrap();
+
+    let Some(RssItem::Feed { uid, title, last_build_date, articles, .. }) = tree.get("Linux\\Debian") else {
+        panic!("Linux\\Debian isn't a feed: {:?}", tree.get("Linux\\Debian"));
+    };
+    assert_eq!(uid, "{6a8d2a21-3c71-4b5b-a2b8-2d8c9c7d2f10}");
+    assert_eq!(title, "Debian News");
+    assert_eq!(last_build_date.as_deref(), Some("Tue, 08 Oct 2024 10:00:00 GMT"));
+    assert_eq!(articles.len(), 2);
+    assert!(articles[0].is_read && !articles[1].is_read);
+    assert_eq!(
+        articles[0].torrent_url.as_deref(),
+        Some("https://cdimage.debian.org/debian-12.8.0-amd64-netinst.iso.torrent")
+    );
+
+    assert!(tree.get("Linux\\Empty").unwrap().is_folder());
+    assert_eq!(tree.get(""), Some(&tree));
+    assert_eq!(tree.get("Linux\\Missing"), None);
+    assert_eq!(tree.get("Arch\\Deeper"), None);
+
+    // without data, feeds only have a uid and a url.
+    let Some(RssItem::Feed { url, title, articles, .. }) = tree.get("Arch") else {
+        panic!("Arch isn't a feed");
+    };
+    assert_eq!((url.as_str(), title.as_str(), articles.len()), ("https://archlinux.org/feeds/news/", "", 0));
+}
+
+#[test]
+fn the_rss_tree_is_walked() {
+    let tree: RssItem = serde_json::from_str(ITEMS).unwrap();
+
+    let paths: Vec<String> = tree.items().into_iter().map(|(path, _)| path).collect();
+    assert_eq!(paths, ["Arch", "Linux", "Linux\\Debian", "Linux\\Empty"]);
+
+    let articles: Vec<(String, String)> = tree
+        .articles()
+        .into_iter()
+        .map(|(path, article)| (path, article.id.clone()))
+        .collect();
+    assert_eq!(
+        articles,
+        [
+            ("Linux\\Debian".to_string(), "https://www.debian.org/News/2024/20241008".to_string()),
+            ("Linux\\Debian".to_string(), "2".to_string()),
+        ]
+    );
+
+    let feed = tree.get("Linux\\Debian").unwrap();
+    assert_eq!(feed.articles().len(), 2);
+    assert_eq!(feed.articles()[0].0, "");
+}
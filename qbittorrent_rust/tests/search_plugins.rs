@@ -0,0 +1,59 @@
+use qbittorrent_rust::{DesiredSearchPlugin, SearchPluginCategory, SearchPluginChanges, SearchPluginInfo, SearchPluginManifest};
+
+fn installed(name: &str, version: &str, enabled: bool) -> SearchPluginInfo {
+    SearchPluginInfo {
+        name: name.to_string(),
+        full_name: name.to_uppercase(),
+        enabled,
+        version: version.to_string(),
+        url: format!("http://{}.invalid", name),
+        supported_categories: vec![SearchPluginCategory { id: "all".to_string(), name: "All categories".to_string() }],
+    }
+}
+
+#[test]
+fn plugin_versions_are_compared_number_by_number() {
+    let plugin = installed("a", "1.9", true);
+
+    assert!(plugin.is_older_than("1.10"));
+    assert!(plugin.is_older_than("2"));
+    assert!(!plugin.is_older_than("1.9.0"));
+    assert!(!plugin.is_older_than("1.8.5"));
+}
+
+#[test]
+fn manifests_are_diffed_against_the_installed_plugins() {
+    let plugins = [
+        installed("current", "2.0", true),
+        installed("outdated", "1.2", true),
+        installed("disabled", "1.0", false),
+        installed("extra", "1.0", true),
+    ];
+    let manifest = SearchPluginManifest {
+        plugins: vec![
+            DesiredSearchPlugin { min_version: Some("2.0".to_string()), ..DesiredSearchPlugin::new("current", "http://src/current.py") },
+            DesiredSearchPlugin {
+                min_version: Some("1.10".to_string()),
+                enabled: false,
+                ..DesiredSearchPlugin::new("outdated", "http://src/outdated.py")
+            },
+            DesiredSearchPlugin::new("disabled", "http://src/disabled.py"),
+            DesiredSearchPlugin::new("missing", "/plugins/missing.py"),
+        ],
+        uninstall_others: true,
+    };
+
+    assert_eq!(
+        manifest.diff(&plugins),
+        SearchPluginChanges {
+            install: vec!["http://src/outdated.py".to_string(), "/plugins/missing.py".to_string()],
+            uninstall: vec!["extra".to_string()],
+            enable: vec!["disabled".to_string()],
+            disable: vec!["outdated".to_string()],
+        }
+    );
+
+    let keep_others = SearchPluginManifest { uninstall_others: false, ..manifest };
+    assert!(keep_others.diff(&plugins).uninstall.is_empty());
+    assert!(SearchPluginManifest::default().diff(&plugins).is_empty());
+}
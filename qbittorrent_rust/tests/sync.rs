@@ -0,0 +1,217 @@
+use qbittorrent_rust::{error_handling::error_type::ErrorType, SyncEvent, SyncState, TorrentState};
+use serde_json::json;
+
+const HASH: &str = "0123456789abcdef0123456789abcdef01234567";
+const OTHER_HASH: &str = "89abcdef0123456789abcdef0123456789abcdef";
+
+fn full_update() -> serde_json::Value {
+    json!({
+        "rid": 1,
+        "full_update": true,
+        "torrents": {
+            HASH: { "name": "ubuntu", "state": "downloading", "progress": 0.25, "category": "linux", "tags": "iso, lts" },
+            OTHER_HASH: { "name": "debian", "state": "stalledUP", "progress": 1.0 },
+        },
+        "categories": { "linux": { "name": "linux", "savePath": "/isos" } },
+        "tags": ["iso", "lts"],
+        "server_state": { "connection_status": "connected", "dl_info_speed": 100 },
+        "trackers": { "http://t.invalid/announce": [HASH, OTHER_HASH] },
+    })
+}
+
+#[test]
+fn full_updates_fill_the_state() {
+    let mut state = SyncState::new();
+    state.apply(full_update()).unwrap();
+
+    assert_eq!(state.rid(), 1);
+    assert_eq!(state.torrents().len(), 2);
+
+    let torrent = state.torrent(HASH).unwrap();
+    assert_eq!((torrent.hash.as_str(), torrent.name.as_str()), (HASH, "ubuntu"));
+    assert_eq!((torrent.state, torrent.progress), (TorrentState::Downloading, 0.25));
+    assert_eq!(torrent.tags, ["iso", "lts"]);
+
+    assert_eq!(state.categories()["linux"].save_path, "/isos");
+    assert_eq!(state.tags(), ["iso", "lts"]);
+    assert_eq!(state.server_state().connection_status, "connected");
+    assert_eq!(state.server_state().dl_info_speed, 100);
+    assert_eq!(state.trackers()["http://t.invalid/announce"], [HASH, OTHER_HASH]);
+}
+
+#[test]
+fn partial_updates_are_merged_into_the_existing_entries() {
+    let mut state = SyncState::new();
+    state.apply(full_update()).unwrap();
+
+    state
+        .apply(json!({
+            "rid": 2,
+            "torrents": { HASH: { "progress": 0.5 } },
+            "categories": { "linux": { "savePath": "/data/isos" } },
+            "tags": ["new"],
+            "server_state": { "dl_info_speed": 200 },
+        }))
+        .unwrap();
+
+    assert_eq!(state.rid(), 2);
+    let torrent = state.torrent(HASH).unwrap();
+    assert_eq!((torrent.name.as_str(), torrent.progress), ("ubuntu", 0.5));
+    assert_eq!(torrent.category, "linux");
+
+    let category = &state.categories()["linux"];
+    assert_eq!((category.name.as_str(), category.save_path.as_str()), ("linux", "/data/isos"));
+    assert_eq!(state.tags(), ["iso", "lts", "new"]);
+    assert_eq!(state.server_state().connection_status, "connected");
+    assert_eq!(state.server_state().dl_info_speed, 200);
+}
+
+#[test]
+fn removed_entries_are_dropped() {
+    let mut state = SyncState::new();
+    state.apply(full_update()).unwrap();
+
+    state
+        .apply(json!({
+            "rid": 2,
+            "torrents_removed": [OTHER_HASH],
+            "categories_removed": ["linux"],
+            "tags_removed": ["iso"],
+            "trackers_removed": ["http://t.invalid/announce"],
+        }))
+        .unwrap();
+
+    assert!(state.torrent(OTHER_HASH).is_none());
+    assert!(state.torrent(HASH).is_some());
+    assert!(state.categories().is_empty());
+    assert_eq!(state.tags(), ["lts"]);
+    assert!(state.trackers().is_empty());
+
+    // a removed torrent that comes back starts from scratch, without its old keys.
+    state.apply(json!({ "rid": 3, "torrents": { OTHER_HASH: { "progress": 0.1 } } })).unwrap();
+    let torrent = state.torrent(OTHER_HASH).unwrap();
+    assert_eq!((torrent.name.as_str(), torrent.progress), ("", 0.1));
+}
+
+#[test]
+fn full_updates_replace_everything() {
+    let mut state = SyncState::new();
+    state.apply(full_update()).unwrap();
+
+    state
+        .apply(json!({
+            "rid": 5,
+            "full_update": true,
+            "torrents": { HASH: { "name": "ubuntu" } },
+        }))
+        .unwrap();
+
+    assert_eq!(state.rid(), 5);
+    assert_eq!(state.torrents().len(), 1);
+    // the keys of the previous data aren't merged into the new one.
+    assert_eq!(state.torrent(HASH).unwrap().progress, 0.0);
+    assert!(state.categories().is_empty());
+    assert!(state.tags().is_empty());
+    assert!(state.trackers().is_empty());
+    // the server state isn't part of every full update, so it's kept.
+    assert_eq!(state.server_state().dl_info_speed, 100);
+}
+
+#[test]
+fn invalid_main_data_is_rejected() {
+    let mut state = SyncState::new();
+
+    let error = state.apply(json!([1, 2])).unwrap_err();
+    assert!(matches!(error.err_type, ErrorType::ParameterNotExpected));
+
+    let error = state.apply(json!({ "torrents": { HASH: { "progress": "half" } } })).unwrap_err();
+    assert!(matches!(error.err_type, ErrorType::JsonSerdeError(_)));
+    assert_eq!(state.rid(), 0);
+}
+
+/// the events as short strings, since [`SyncEvent`] can't be compared.
+fn describe(events: &[SyncEvent]) -> Vec<String> {
+    events
+        .iter()
+        .map(|event| match event {
+            SyncEvent::TorrentAdded(torrent) => format!("added {}", torrent.name),
+            SyncEvent::TorrentRemoved(torrent) => format!("removed {}", torrent.name),
+            SyncEvent::StateChanged { from, to, .. } => format!("state {} -> {}", from.as_str(), to.as_str()),
+            SyncEvent::ProgressChanged { from, to, .. } => format!("progress {} -> {}", from, to),
+            SyncEvent::CategoryChanged { from, to, .. } => format!("category {:?} -> {:?}", from, to),
+            SyncEvent::TagsChanged { from, to, .. } => format!("tags {:?} -> {:?}", from, to),
+            SyncEvent::ServerStateChanged(state) => format!("server {}", state.dl_info_speed),
+        })
+        .collect()
+}
+
+#[test]
+fn the_first_update_adds_every_torrent() {
+    let mut state = SyncState::new();
+    let mut events = describe(&state.apply(full_update()).unwrap());
+    events.sort();
+
+    assert_eq!(events, ["added debian", "added ubuntu", "server 100"]);
+}
+
+#[test]
+fn torrent_changes_produce_events() {
+    let mut state = SyncState::new();
+    state.apply(full_update()).unwrap();
+
+    let events = state
+        .apply(json!({
+            "rid": 2,
+            "torrents": { HASH: { "state": "uploading", "progress": 1.0, "category": "", "tags": "iso" } },
+        }))
+        .unwrap();
+
+    assert_eq!(
+        describe(&events),
+        [
+            "state downloading -> uploading",
+            "progress 0.25 -> 1",
+            "category \"linux\" -> \"\"",
+            "tags [\"iso\", \"lts\"] -> [\"iso\"]",
+        ]
+    );
+    assert!(events.iter().all(|event| event.hash() == Some(HASH)));
+}
+
+#[test]
+fn unchanged_data_produces_no_events() {
+    let mut state = SyncState::new();
+    state.apply(full_update()).unwrap();
+
+    let events = state
+        .apply(json!({
+            "rid": 2,
+            "torrents": { HASH: { "name": "ubuntu", "progress": 0.25 } },
+            "server_state": { "dl_info_speed": 100 },
+        }))
+        .unwrap();
+    assert!(events.is_empty(), "{:?}", events);
+
+    let events = state.apply(full_update()).unwrap();
+    assert!(events.is_empty(), "{:?}", events);
+}
+
+#[test]
+fn removed_torrents_are_reported_with_their_last_version() {
+    let mut state = SyncState::new();
+    state.apply(full_update()).unwrap();
+    state.apply(json!({ "rid": 2, "torrents": { OTHER_HASH: { "progress": 0.5 } } })).unwrap();
+
+    let events = state.apply(json!({ "rid": 3, "torrents_removed": [OTHER_HASH] })).unwrap();
+    let [SyncEvent::TorrentRemoved(torrent)] = &events[..] else {
+        panic!("unexpected events: {:?}", events);
+    };
+    assert_eq!((torrent.name.as_str(), torrent.progress), ("debian", 0.5));
+
+    // a full update without a torrent removes it too.
+    let events = state
+        .apply(json!({ "rid": 4, "full_update": true, "torrents": {}, "server_state": { "dl_info_speed": 0 } }))
+        .unwrap();
+    assert_eq!(describe(&events), ["removed ubuntu", "server 0"]);
+    assert_eq!(events[1].hash(), None);
+}